@@ -0,0 +1,26 @@
+/* The C ABI of weather-banner, built with:
+ *
+ *   cargo rustc --release --lib --crate-type cdylib --features cdylib
+ */
+#ifndef WEATHER_BANNER_H
+#define WEATHER_BANNER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Renders the year of a station in a CSV file to a PNG at out_path. The CSV has a header
+ * row and columns of date (YYYY-MM-DD), tmin, tmax and tmean (°F), wind (knots) and precip
+ * (inches). Returns 0 on success and -1 on failure, with the reason in wb_last_error. */
+int wb_render_png(const char *station_csv_path, int year, int width, int height,
+                  const char *out_path);
+
+/* The reason the last call on this thread failed, or NULL if none has. The string belongs
+ * to the library and lasts until the next failure on the thread. */
+const char *wb_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
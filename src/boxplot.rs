@@ -0,0 +1,156 @@
+use super::{
+    chart::{self, month_spans, Panel},
+    gsod::Station,
+    heatmap, percentile_of, render, time, Range, Scale, TAU,
+};
+use chrono::Datelike;
+use std::error::Error;
+
+// The five numbers of a box and whiskers.
+struct Quartiles {
+    min: f64,
+    q1: f64,
+    median: f64,
+    q3: f64,
+    max: f64,
+}
+
+impl Quartiles {
+    fn of(mut vals: Vec<f64>) -> Option<Quartiles> {
+        if vals.is_empty() {
+            return None;
+        }
+        vals.sort_by(|a, b| a.total_cmp(b));
+        Some(Quartiles {
+            min: vals[0],
+            q1: percentile_of(&vals, 0.25),
+            median: percentile_of(&vals, 0.5),
+            q3: percentile_of(&vals, 0.75),
+            max: vals[vals.len() - 1],
+        })
+    }
+}
+
+// Renders the distribution of the days of each month as a box and whiskers around the ring:
+// the box runs from the lower to the upper quartile with a bar at the median, and the
+// whiskers reach out to the extremes.
+pub(crate) fn render(
+    ctx: &cairo::Context,
+    width: f64,
+    height: f64,
+    year: time::Year,
+    station: &Station,
+    metric: heatmap::Metric,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let header_height = render::render_header(
+        ctx,
+        station,
+        &render::describe_year(year, &opts.locale),
+        width,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let mut months = vec![Vec::new(); 12];
+    for day in station.days() {
+        if let Some(v) = metric.value_of(day) {
+            months[day.date().month0() as usize].push(v);
+        }
+    }
+    let quartiles = months.into_iter().map(Quartiles::of).collect::<Vec<_>>();
+    if quartiles.iter().all(Option::is_none) {
+        return Err(format!("no data for station: {}", station.id()).into());
+    }
+
+    let (panel, pinned) = match metric {
+        heatmap::Metric::Temperature => (Panel::Temperature, &opts.temperature_range),
+        heatmap::Metric::Precipitation => (Panel::Precipitation, &opts.precipitation_range),
+    };
+    let range = pinned.clone().unwrap_or_else(|| {
+        metric.range_of(
+            quartiles
+                .iter()
+                .flatten()
+                .flat_map(|q| [q.min, q.max].into_iter()),
+        )
+    });
+    let gradient = metric.gradient();
+
+    let body_height = height - header_height;
+    let r = (width.min(body_height) / 2.0 - 40.0).max(10.0);
+    let rrange = Range::new(r * opts.radii.min(), r * opts.radii.max());
+    let radius = |v: f64| rrange.project(range.normalize_clamped(v));
+
+    ctx.save()?;
+    ctx.translate(width / 2.0, header_height + body_height / 2.0);
+
+    render::render_title(
+        ctx,
+        opts.locale.label(metric.title()),
+        0.0,
+        -rrange.max() - 10.0,
+        opts,
+    )?;
+
+    ctx.save()?;
+    render::render_months(ctx, year, &opts.month_range(&rrange), opts)?;
+    ctx.restore()?;
+
+    ctx.save()?;
+    let scale = Scale::from_range(&range, 5.0)?;
+    render::render_scales(ctx, &scale, &range, &rrange, metric.units(), panel, opts)?;
+    ctx.restore()?;
+
+    ctx.save()?;
+    render::orient(ctx, year, opts);
+    ctx.set_line_width(1.0);
+    for ((s, e), q) in month_spans(year).into_iter().zip(&quartiles) {
+        let q = match q {
+            Some(q) => q,
+            None => continue,
+        };
+        let t = (s + e) / 2.0 * TAU - TAU / 4.0;
+        let w = (e - s) * TAU * 0.25;
+        let (ta, tb) = (t - w, t + w);
+        let (ca, cb) = (t - w / 2.0, t + w / 2.0);
+
+        opts.theme.foreground.with_alpha(0.6).set(ctx);
+        ctx.new_path();
+        ctx.move_to(radius(q.min) * t.cos(), radius(q.min) * t.sin());
+        ctx.line_to(radius(q.q1) * t.cos(), radius(q.q1) * t.sin());
+        ctx.move_to(radius(q.q3) * t.cos(), radius(q.q3) * t.sin());
+        ctx.line_to(radius(q.max) * t.cos(), radius(q.max) * t.sin());
+        ctx.new_sub_path();
+        ctx.arc(0.0, 0.0, radius(q.min), ca, cb);
+        ctx.new_sub_path();
+        ctx.arc(0.0, 0.0, radius(q.max), ca, cb);
+        ctx.stroke()?;
+
+        ctx.new_path();
+        ctx.arc(0.0, 0.0, radius(q.q3), ta, tb);
+        ctx.arc_negative(0.0, 0.0, radius(q.q1), tb, ta);
+        ctx.close_path();
+        let color = gradient.at(range.normalize_clamped(q.median));
+        color.with_alpha(0.5).set(ctx);
+        ctx.fill_preserve()?;
+        color.set(ctx);
+        ctx.stroke()?;
+
+        opts.theme.foreground.set(ctx);
+        ctx.set_line_width(2.5);
+        ctx.new_path();
+        ctx.arc(0.0, 0.0, radius(q.median), ta, tb);
+        ctx.stroke()?;
+        ctx.set_line_width(1.0);
+    }
+    ctx.restore()?;
+
+    ctx.restore()?;
+    Ok(())
+}
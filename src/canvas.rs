@@ -0,0 +1,425 @@
+use super::{
+    chart::{
+        self, Center, Header, Layout, MonthLabels, Options, Panel, PrecipScale, Rotation,
+        ScaleSide, StartMonth, TemperatureBand,
+    },
+    input,
+    locale::Locale,
+    model,
+    theme::Theme,
+    time, Color, Range,
+};
+use std::error::Error;
+use std::f64::consts::{PI, TAU};
+
+// The few drawing operations that the renderers without cairo need, so that the chart model
+// is laid out once here and drawn by each of them. Filled paths use the even-odd rule, which
+// lets a band around the year be a path of its outer and inner edges.
+pub(crate) trait Canvas {
+    fn fill(&mut self, path: &Path, color: &Color);
+
+    // lines are 2 wide, as they are in cairo, and dotted strokes are drawn as dots of the
+    // given length and gap.
+    fn stroke(&mut self, path: &Path, color: &Color, dots: Option<(f64, f64)>);
+
+    fn text(&mut self, text: &Text, color: &Color);
+}
+
+// Lines through points, with arcs already broken up into them.
+#[derive(Debug, Default)]
+pub(crate) struct Path {
+    pub(crate) subpaths: Vec<Subpath>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Subpath {
+    pub(crate) points: Vec<(f64, f64)>,
+    pub(crate) closed: bool,
+}
+
+impl Path {
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.subpaths.push(Subpath {
+            points: vec![(x, y)],
+            closed: false,
+        });
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        match self.subpaths.last_mut() {
+            Some(subpath) => subpath.points.push((x, y)),
+            None => self.move_to(x, y),
+        }
+    }
+
+    // an arc around (cx, cy) from angle a to angle b, continuing the current subpath.
+    fn arc(&mut self, cx: f64, cy: f64, r: f64, a: f64, b: f64) {
+        let n = ((b - a).abs() * 180.0 / PI).ceil().max(1.0) as usize;
+        for i in 0..=n {
+            let t = a + (b - a) * i as f64 / n as f64;
+            self.line_to(cx + r * t.cos(), cy + r * t.sin());
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.closed = true;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Weight {
+    Thin,
+    Normal,
+    Bold,
+}
+
+// A line of text at (x, y). The anchor places it horizontally; it sits on y unless it is
+// centered on it. The angle, in degrees, turns it clockwise around (x, y).
+#[derive(Debug)]
+pub(crate) struct Text {
+    pub(crate) text: String,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) size: f64,
+    pub(crate) weight: Weight,
+    pub(crate) anchor: Anchor,
+    pub(crate) centered: bool,
+    pub(crate) angle: f64,
+}
+
+impl Text {
+    fn new(text: &str, x: f64, y: f64, size: f64) -> Text {
+        Text {
+            text: String::from(text),
+            x,
+            y,
+            size,
+            weight: Weight::Normal,
+            anchor: Anchor::Start,
+            centered: false,
+            angle: 0.0,
+        }
+    }
+}
+
+// The font files that the pure Rust renderer draws text with, see src/raster.rs.
+#[cfg(any(feature = "native", feature = "skia"))]
+#[derive(clap::Args, Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct FontArgs {
+    /// A TrueType or OpenType font for the text of --backend skia, in place of a sans-serif
+    /// font found among the system's.
+    #[clap(long, env = "WEATHER_BANNER_FONT")]
+    pub font: Option<String>,
+
+    /// The font of the bold text of --backend skia, by default --font.
+    #[clap(long)]
+    pub font_bold: Option<String>,
+
+    /// The font of the thin text of --backend skia, by default --font.
+    #[clap(long)]
+    pub font_thin: Option<String>,
+}
+
+// The model of the year of a station in CSV, in the format of --source file, with the
+// defaults of the render command. The name is what the header calls the station.
+pub(crate) fn banner(
+    csv: &str,
+    name: &str,
+    year: i32,
+) -> Result<(model::Chart, Theme), Box<dyn Error>> {
+    let mut station = input::read(csv.as_bytes(), name, String::from(name))?;
+    station.retain_year(year);
+    if station.days().is_empty() {
+        return Err(format!("there are no days in {}", year).into());
+    }
+
+    // these are the defaults of the render command.
+    let opts = Options {
+        debug: false,
+        downsample_by: 2,
+        smooth: true,
+        envelope_window: 1,
+        temperature_band: TemperatureBand::MinMax,
+        sigma_window: 15,
+        min_samples: 0,
+        temperature_range: None,
+        wind_range: None,
+        precipitation_range: None,
+        month_labels: MonthLabels::Auto,
+        callouts: false,
+        weather_ring: false,
+        feels_like: false,
+        theme: Theme::default(),
+        center: Center::default(),
+        title: None,
+        subtitle: None,
+        details_in_footer: false,
+        locator: false,
+        locale: Locale::default(),
+        header: Header::Full,
+        layout: Layout::Banner,
+        panels: vec![Panel::Temperature, Panel::Wind, Panel::Precipitation],
+        scale_sides: vec![ScaleSide::Left],
+        grid_rings: false,
+        radii: Range::new(0.6, 0.9),
+        month_band: Range::new(5.0, 40.0),
+        start_month: StartMonth::Jan,
+        rotation: Rotation::Cw,
+        seasons: Vec::new(),
+        week_ticks: false,
+        upright_month_labels: false,
+        monthly: false,
+        beaufort: false,
+        precip_scale: PrecipScale::Linear,
+        snow_split: false,
+        #[cfg(feature = "native")]
+        hotspots: None,
+    };
+    let names = vec![String::from(name)];
+    let model = chart::model(
+        time::Year::from_ordinal(year),
+        &[station],
+        &names,
+        None,
+        &opts,
+    )?;
+    Ok((model, opts.theme))
+}
+
+// Draws a radial banner of the model, laid out as the render command lays out its own.
+pub(crate) fn draw<C: Canvas>(
+    c: &mut C,
+    model: &model::Chart,
+    width: f64,
+    height: f64,
+    theme: &Theme,
+) {
+    c.fill(&rect(0.0, 0.0, width, height), &theme.background);
+
+    // the header, as the render command's full header lays it out.
+    let name = model
+        .stations
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let fg = theme.foreground.with_alpha(0.9);
+    c.text(
+        &Text {
+            weight: Weight::Thin,
+            ..Text::new(&name, 20.0, 56.0, 42.0)
+        },
+        &fg,
+    );
+    c.text(
+        &Text {
+            anchor: Anchor::End,
+            ..Text::new(&model.year.to_string(), width - 20.0, 56.0, 24.0)
+        },
+        &fg,
+    );
+    let header_height = 110.0;
+
+    let body_height = height - header_height;
+    let n = model.panels.len() as f64;
+    let r = (width / (2.0 * n)).min(body_height / 2.0);
+    let rrange = Range::new(r * 0.6, r * 0.9);
+    let cy = header_height + body_height / 2.0;
+    for (i, panel) in model.panels.iter().enumerate() {
+        let cx = (2 * i + 1) as f64 * width / (2.0 * n);
+        draw_panel(c, model, panel, (cx, cy), &rrange, theme);
+    }
+}
+
+fn draw_panel<C: Canvas>(
+    c: &mut C,
+    model: &model::Chart,
+    panel: &model::Panel,
+    (cx, cy): (f64, f64),
+    rrange: &Range,
+    theme: &Theme,
+) {
+    // the point at a radius and a fraction of the way around the year, which starts at the
+    // top and runs clockwise.
+    let point = |r: f64, u: f64| {
+        let t = angle_of(u);
+        (cx + r * t.cos(), cy + r * t.sin())
+    };
+    let radius = |v: f64| rrange.min() + v.clamp(0.0, 1.0) * (rrange.max() - rrange.min());
+    let line = |path: &mut Path, values: &[f64]| {
+        let n = values.len() as f64;
+        for (i, v) in values.iter().enumerate() {
+            let (x, y) = point(radius(*v), i as f64 / n);
+            match i {
+                0 => path.move_to(x, y),
+                _ => path.line_to(x, y),
+            }
+        }
+        path.close();
+    };
+    // the area between two lines around the year.
+    let ring = |low: &[f64], high: &[f64]| {
+        let mut path = Path::default();
+        line(&mut path, high);
+        line(&mut path, low);
+        path
+    };
+
+    c.text(
+        &Text {
+            anchor: Anchor::Middle,
+            ..Text::new(&panel.title, cx, cy - rrange.max() - 10.0, 12.0)
+        },
+        &theme.foreground.with_alpha(0.6),
+    );
+
+    // the months, in a ring inside of the chart.
+    let (inner, outer) = (rrange.min() - 40.0, rrange.min() - 5.0);
+    let dt = 0.5 / model.days as f64;
+    for month in &model.months {
+        let (s, e) = (angle_of(month.start + dt), angle_of(month.end - dt));
+        let mut path = Path::default();
+        path.arc(cx, cy, outer, s, e);
+        path.arc(cx, cy, inner, e, s);
+        path.close();
+        c.fill(&path, &theme.foreground.with_alpha(0.05));
+
+        if let Some(label) = &month.label {
+            let u = (month.start + month.end) / 2.0;
+            let (x, y) = point((inner + outer) / 2.0, u);
+            c.text(
+                &Text {
+                    anchor: Anchor::Middle,
+                    centered: true,
+                    angle: u * 360.0,
+                    ..Text::new(label, x, y, 10.0)
+                },
+                &theme.foreground,
+            );
+        }
+    }
+
+    // the scale, as dotted rings that leave a gap at the top left for their labels.
+    let units = match panel.panel.as_str() {
+        "wind" => " kts",
+        "precipitation" => " in",
+        _ => panel.units.as_str(),
+    };
+    let innermost = panel
+        .scale
+        .first()
+        .map_or(rrange.min(), |s| radius(s.position));
+    let color = theme.foreground.with_alpha(0.6);
+    for step in &panel.scale {
+        let r = radius(step.position);
+        let ta = PI - ((10.0 - innermost) / r).clamp(-1.0, 1.0).asin();
+        let mut path = Path::default();
+        path.arc(cx, cy, r, ta, TAU * 0.75 - TAU);
+        path.line_to(cx - rrange.max(), cy - r);
+        c.stroke(&path, &color, Some((1.0, 4.0)));
+        c.text(
+            &Text {
+                anchor: Anchor::End,
+                centered: true,
+                ..Text::new(
+                    &format!("{}{}", step.label, units),
+                    cx - rrange.max() - 5.0,
+                    cy - r,
+                    10.0,
+                )
+            },
+            &color,
+        );
+    }
+
+    if let Some(band) = &panel.band {
+        c.fill(
+            &ring(&band.low, &band.high),
+            &theme.foreground.with_alpha(0.15),
+        );
+    }
+
+    for series in &panel.series {
+        let color = match (panel.panel.as_str(), series.name) {
+            (_, "mean") => theme.temperature_line,
+            (_, "feels-like") => theme.feels_like,
+            ("temperature", _) => theme.temperature_band,
+            ("feels-like", _) => theme.feels_like,
+            ("wind", _) => theme.wind,
+            _ => theme.precipitation,
+        };
+        match (&series.low, &series.high, &series.values) {
+            (Some(low), Some(high), _) => {
+                let path = ring(low, high);
+                c.fill(&path, &color.with_alpha(0.1));
+                c.stroke(&path, &color, None);
+            }
+            (_, _, Some(values)) if series.kind == "bars" => {
+                let mut path = Path::default();
+                let n = values.len() as f64;
+                for (i, v) in values.iter().enumerate() {
+                    let u = i as f64 / n;
+                    let (x, y) = point(rrange.min(), u);
+                    path.move_to(x, y);
+                    let (x, y) = point(radius(*v), u);
+                    path.line_to(x, y);
+                }
+                c.stroke(&path, &color, None);
+            }
+            (_, _, Some(values)) => {
+                let mut path = Path::default();
+                line(&mut path, values);
+                c.stroke(&path, &color, None);
+            }
+            _ => {}
+        }
+    }
+
+    // the stats, stacked in the middle of the ring.
+    let spacing = 46.0;
+    let top = cy - (panel.stats.len() as f64 - 1.0) * spacing / 2.0;
+    for (i, stat) in panel.stats.iter().enumerate() {
+        let y = top + spacing * i as f64 + 8.0;
+        c.text(
+            &Text {
+                weight: Weight::Bold,
+                anchor: Anchor::Middle,
+                ..Text::new(&stat.label, cx, y - 18.0, 11.0)
+            },
+            &color,
+        );
+        c.text(
+            &Text {
+                weight: Weight::Thin,
+                anchor: Anchor::Middle,
+                centered: true,
+                ..Text::new(&stat.value, cx, y, 28.0)
+            },
+            &color,
+        );
+    }
+}
+
+// the angle of a fraction of the way around the year.
+fn angle_of(u: f64) -> f64 {
+    u * TAU - TAU / 4.0
+}
+
+fn rect(x: f64, y: f64, width: f64, height: f64) -> Path {
+    let mut path = Path::default();
+    path.move_to(x, y);
+    path.line_to(x + width, y);
+    path.line_to(x + width, y + height);
+    path.line_to(x, y + height);
+    path.close();
+    path
+}
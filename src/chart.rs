@@ -0,0 +1,1362 @@
+use super::{
+    climatology::Climatology,
+    feels_like, gsod,
+    gsod::Station,
+    locale::{Label, Locale},
+    model,
+    theme::Theme,
+    time, Range, Scale, Series,
+};
+#[cfg(feature = "native")]
+use super::{svg, Unit, TAU};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "native")]
+use std::cell::RefCell;
+use std::error::Error;
+
+// The options of a render and the series, ranges and stats of the charts, which don't depend
+// on how they are drawn.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Header {
+    /// No header, leaving the whole image to the charts.
+    None,
+    /// A single line with the title and the dates.
+    Minimal,
+    /// The title, the dates and the station details or legend.
+    Full,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MonthLabels {
+    /// Pick the densest style whose labels fit the arc of a month.
+    Auto,
+    All,
+    /// Every other month, starting with January.
+    Alternate,
+    /// January, April, July and October.
+    Quarters,
+    /// A single letter for each month.
+    Initials,
+    /// The full name of each month.
+    Full,
+    /// No labels, leaving the month ring bare.
+    None,
+}
+
+impl MonthLabels {
+    pub(crate) fn label_for(
+        self,
+        i: usize,
+        month: &time::Month,
+        locale: &Locale,
+    ) -> Option<String> {
+        let name = locale.month_abbr(month.start());
+        match self {
+            MonthLabels::Auto | MonthLabels::All => Some(name),
+            MonthLabels::Alternate if i.is_multiple_of(2) => Some(name),
+            MonthLabels::Quarters if i.is_multiple_of(3) => Some(name),
+            MonthLabels::Initials => name.chars().next().map(|c| c.to_uppercase().collect()),
+            MonthLabels::Full => Some(locale.month_name(month.start())),
+            MonthLabels::None => None,
+            _ => None,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    /// The metric panels side by side.
+    Banner,
+    /// One small temperature ring per year in --years, all on a shared scale.
+    Grid,
+    /// A single panel filling the canvas, with its text scaled up to match.
+    Single,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Panel {
+    Temperature,
+    /// The heat index or wind chill against the mean temperature.
+    FeelsLike,
+    Wind,
+    Precipitation,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartMonth {
+    Jan,
+    Feb,
+    Mar,
+    Apr,
+    May,
+    Jun,
+    Jul,
+    Aug,
+    Sep,
+    Oct,
+    Nov,
+    Dec,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TemperatureBand {
+    /// The band from the day's min to its max temperature.
+    MinMax,
+    /// The band a standard deviation either side of the mean temperature, over
+    /// --sigma-window days.
+    Sigma,
+    /// Both of the bands, the standard deviation over the min and max.
+    Both,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrecipScale {
+    /// Bars as long as the precipitation.
+    Linear,
+    /// Bars as long as the square root of the precipitation.
+    Sqrt,
+    /// Bars as long as the log of the precipitation, down to a hundredth of an inch.
+    Log,
+}
+
+#[cfg(feature = "native")]
+impl PrecipScale {
+    // GSOD reports precipitation to a hundredth of an inch, so the log scale starts there
+    // rather than running off to zero.
+    const LOG_FLOOR: f64 = 0.01;
+
+    pub(crate) fn apply(self, v: f64) -> f64 {
+        match self {
+            PrecipScale::Linear => v,
+            PrecipScale::Sqrt => v.max(0.0).sqrt(),
+            PrecipScale::Log => (v.max(0.0) / Self::LOG_FLOOR).ln_1p(),
+        }
+    }
+
+    pub(crate) fn transform(self, range: &Range) -> Range {
+        Range::new(self.apply(range.min()), self.apply(range.max()))
+    }
+
+    pub(crate) fn scale(self, range: &Range) -> Result<Scale, Box<dyn Error>> {
+        match self {
+            PrecipScale::Log => Scale::from_range_log(range, Self::LOG_FLOOR),
+            _ => Scale::from_range(range, 4.0),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rotation {
+    /// Clockwise.
+    Cw,
+    /// Counterclockwise.
+    Ccw,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScaleSide {
+    Left,
+    Right,
+    /// No scale, leaving the panel to its data.
+    None,
+}
+
+impl Panel {
+    pub(crate) fn title(self) -> Label {
+        match self {
+            Panel::Temperature => Label::Temperature,
+            Panel::FeelsLike => Label::FeelsLike,
+            Panel::Wind => Label::Wind,
+            Panel::Precipitation => Label::Precipitation,
+        }
+    }
+
+    // whether any day of the station has what the panel draws, which isn't a given for
+    // sources other than GSOD.
+    pub(crate) fn has_data(self, station: &Station) -> bool {
+        station.days().iter().any(|day| match self {
+            Panel::Temperature | Panel::FeelsLike => day.mean_temperature().is_some(),
+            Panel::Wind => day.mean_wind().is_some(),
+            Panel::Precipitation => day.precipitation().is_some(),
+        })
+    }
+
+    // the values of a day shown by the panel, for its tooltip.
+    #[cfg(feature = "native")]
+    pub(crate) fn describe(self, day: &gsod::Day, locale: &Locale) -> Option<String> {
+        let value = |label, v: Option<f64>, units| {
+            v.map(|v| format!("{} {}{}", locale.label(label), locale.number(v, 1), units))
+        };
+        let vals = match self {
+            Panel::Temperature => vec![
+                value(
+                    Label::Max,
+                    day.max_temperature().map(|t| t.in_fahrenheit()),
+                    "°F",
+                ),
+                value(
+                    Label::Avg,
+                    day.mean_temperature().map(|t| t.in_fahrenheit()),
+                    "°F",
+                ),
+                value(
+                    Label::Min,
+                    day.min_temperature().map(|t| t.in_fahrenheit()),
+                    "°F",
+                ),
+            ],
+            Panel::FeelsLike => vec![
+                value(Label::FeelsLike, feels_like(day), "°F"),
+                value(
+                    Label::Avg,
+                    day.mean_temperature().map(|t| t.in_fahrenheit()),
+                    "°F",
+                ),
+            ],
+            Panel::Wind => vec![
+                value(
+                    Label::Max,
+                    day.max_sustained_wind().map(|s| s.in_knots()),
+                    " kts",
+                ),
+                value(Label::Avg, day.mean_wind().map(|s| s.in_knots()), " kts"),
+            ],
+            Panel::Precipitation => vec![value(
+                Label::Total,
+                day.precipitation().map(|p| p.in_inches()),
+                " in",
+            )],
+        };
+        let vals = vals.into_iter().flatten().collect::<Vec<_>>();
+        (!vals.is_empty()).then(|| vals.join("  "))
+    }
+}
+
+// The stats in the center of each of the panels, in order, from the [center] table of the
+// config. A panel without any keeps its usual stats.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Center {
+    pub temperature: Vec<CenterStat>,
+    pub feels_like: Vec<CenterStat>,
+    pub wind: Vec<CenterStat>,
+    pub precipitation: Vec<CenterStat>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CenterStat {
+    pub stat: Stat,
+    // the text over the value in place of the stat's own label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stat {
+    Max,
+    Mean,
+    Median,
+    // the values a tenth and nine tenths of the way up the days, which show the skew a mean
+    // hides.
+    P10,
+    P90,
+    Min,
+    // the precipitation of the year and the days that had any, of rain or of snow.
+    Total,
+    Days,
+    RainDays,
+    SnowDays,
+    // the days with a high above 90°F, a low below 32°F and a low below 0°F, going by the
+    // feels like temperature itself on its panel.
+    SummerDays,
+    FrostDays,
+    IceDays,
+}
+
+impl Center {
+    pub(crate) fn stats(&self, panel: Panel) -> &[CenterStat] {
+        match panel {
+            Panel::Temperature => &self.temperature,
+            Panel::FeelsLike => &self.feels_like,
+            Panel::Wind => &self.wind,
+            Panel::Precipitation => &self.precipitation,
+        }
+    }
+
+    // Refuses the stats that mean nothing for their panel, like the total of the wind.
+    #[cfg(feature = "native")]
+    pub(crate) fn check(&self) -> Result<(), Box<dyn Error>> {
+        for panel in Panel::value_variants() {
+            for s in self.stats(*panel) {
+                if !s.stat.applies_to(*panel) {
+                    return Err(format!(
+                        "{} isn't a stat of the {} panel",
+                        s.stat.to_possible_value().unwrap().get_name(),
+                        panel.to_possible_value().unwrap().get_name()
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Stat {
+    #[cfg(feature = "native")]
+    fn applies_to(self, panel: Panel) -> bool {
+        match self {
+            Stat::Max | Stat::Mean | Stat::Median | Stat::P10 | Stat::P90 | Stat::Min => true,
+            Stat::Total | Stat::Days | Stat::RainDays | Stat::SnowDays => {
+                panel == Panel::Precipitation
+            }
+            Stat::SummerDays | Stat::FrostDays | Stat::IceDays => {
+                matches!(panel, Panel::Temperature | Panel::FeelsLike)
+            }
+        }
+    }
+
+    fn label(self) -> Label {
+        match self {
+            Stat::Max => Label::Max,
+            Stat::Mean => Label::Avg,
+            Stat::Median => Label::Median,
+            Stat::P10 => Label::P10,
+            Stat::P90 => Label::P90,
+            Stat::Min => Label::Min,
+            Stat::Total => Label::Total,
+            Stat::Days => Label::Days,
+            Stat::RainDays => Label::RainDays,
+            Stat::SnowDays => Label::SnowDays,
+            Stat::SummerDays => Label::SummerDays,
+            Stat::FrostDays => Label::FrostDays,
+            Stat::IceDays => Label::IceDays,
+        }
+    }
+}
+
+// The web build fills in all of the options, but draws only the banner that it has, which
+// reads few of them.
+#[cfg_attr(not(feature = "native"), allow(dead_code))]
+pub(crate) struct Options {
+    pub(crate) debug: bool,
+    pub(crate) downsample_by: u32,
+    pub(crate) smooth: bool,
+    pub(crate) envelope_window: u32,
+    pub(crate) temperature_band: TemperatureBand,
+    // the days the standard deviation of the sigma band is taken over.
+    pub(crate) sigma_window: u32,
+    pub(crate) min_samples: u32,
+    // the ranges of the scales, when they are pinned rather than fit to the stations.
+    pub(crate) temperature_range: Option<Range>,
+    pub(crate) wind_range: Option<Range>,
+    pub(crate) precipitation_range: Option<Range>,
+    pub(crate) month_labels: MonthLabels,
+    pub(crate) callouts: bool,
+    pub(crate) weather_ring: bool,
+    pub(crate) feels_like: bool,
+    pub(crate) theme: Theme,
+    pub(crate) center: Center,
+    pub(crate) title: Option<String>,
+    pub(crate) subtitle: Option<String>,
+    pub(crate) details_in_footer: bool,
+    // a globe at the right of the header marking where the station is.
+    pub(crate) locator: bool,
+    pub(crate) locale: Locale,
+    pub(crate) header: Header,
+    pub(crate) layout: Layout,
+    pub(crate) panels: Vec<Panel>,
+    // the side of each of the panels that its scale is labeled on, the last one standing in
+    // for the panels past the end.
+    pub(crate) scale_sides: Vec<ScaleSide>,
+    pub(crate) grid_rings: bool,
+    // where the data of a panel is drawn, as fractions of its radius, and how far inside of
+    // that the month ring is, in pixels.
+    pub(crate) radii: Range,
+    pub(crate) month_band: Range,
+    // the month at the top of the rings and which way they run from it.
+    pub(crate) start_month: StartMonth,
+    pub(crate) rotation: Rotation,
+    // the panels with the seasons shaded behind their data.
+    pub(crate) seasons: Vec<Panel>,
+    pub(crate) week_ticks: bool,
+    // whether the month labels stay upright rather than turning with the ring.
+    pub(crate) upright_month_labels: bool,
+    // whether the panels show a wedge for each month rather than the days.
+    pub(crate) monthly: bool,
+    // whether the wind band is colored by Beaufort force.
+    pub(crate) beaufort: bool,
+    pub(crate) precip_scale: PrecipScale,
+    // whether the days of snow are told apart from the days of rain.
+    pub(crate) snow_split: bool,
+    #[cfg(feature = "native")]
+    pub(crate) hotspots: Option<RefCell<Vec<svg::Hotspot>>>,
+}
+
+#[cfg(feature = "native")]
+impl Options {
+    // The fraction of the year at the top of the rings, the start of --start-month.
+    pub(crate) fn ring_start(&self, year: time::Year) -> f64 {
+        month_spans(year)[self.start_month as usize].0
+    }
+
+    // The angle, clockwise from the top of a ring, that a fraction of the year is drawn at.
+    pub(crate) fn angle_of(&self, year: time::Year, f: f64) -> f64 {
+        let t = (f - self.ring_start(year)) * TAU;
+        match self.rotation {
+            Rotation::Cw => t,
+            Rotation::Ccw => -t,
+        }
+    }
+
+    // Where a value of the panel lies in its range, which for precipitation is on
+    // --precip-scale.
+    pub(crate) fn normalize(&self, panel: Panel, range: &Range, v: f64) -> Unit {
+        match (panel, self.precip_scale) {
+            (Panel::Precipitation, PrecipScale::Sqrt | PrecipScale::Log) => self
+                .precip_scale
+                .transform(range)
+                .normalize(self.precip_scale.apply(v)),
+            _ => range.normalize(v),
+        }
+    }
+
+    // The radii of the month ring of a panel whose data is drawn over rrange.
+    pub(crate) fn month_range(&self, rrange: &Range) -> Range {
+        Range::new(
+            (rrange.min() - self.month_band.max()).max(0.0),
+            rrange.min() - self.month_band.min(),
+        )
+    }
+
+    pub(crate) fn scale_side(&self, panel: Panel) -> ScaleSide {
+        let i = self
+            .panels
+            .iter()
+            .position(|p| *p == panel)
+            .unwrap_or_default();
+        self.scale_sides
+            .get(i)
+            .or(self.scale_sides.last())
+            .copied()
+            .unwrap_or(ScaleSide::Left)
+    }
+}
+
+pub(crate) fn check_panels(stations: &[Station], opts: &Options) -> Result<(), Box<dyn Error>> {
+    for panel in &opts.panels {
+        if let Some(station) = stations.iter().find(|s| !panel.has_data(s)) {
+            return Err(format!(
+                "station {} has no data for the {} panel, leave it out of --panels",
+                station.id(),
+                panel.to_possible_value().unwrap().get_name()
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn temperature_range_of(year: time::Year, stations: &[Station]) -> Range {
+    stations
+        .iter()
+        .map(|station| {
+            let min_temps = Series::for_each_day(year, station.days().iter(), |day| {
+                day.min_temperature().map(|t| t.in_fahrenheit())
+            });
+            let max_temps = Series::for_each_day(year, station.days().iter(), |day| {
+                day.max_temperature().map(|t| t.in_fahrenheit())
+            });
+            Range::union(max_temps.range(), min_temps.range())
+        })
+        .reduce(|a, b| Range::union(&a, &b))
+        .map(widened)
+        .unwrap_or_else(|| Range::new(0.0, 1.0))
+}
+
+// Gives a range without any width, like that of a constant series, a degree of width on
+// either side of its value so there is a scale to draw it on, without pushing a range that
+// starts at or above nothing below it.
+pub(crate) fn widened(range: Range) -> Range {
+    if range.max() != range.min() {
+        return range;
+    }
+    let lo = match range.min() >= 0.0 {
+        true => (range.min() - 1.0).max(0.0),
+        false => range.min() - 1.0,
+    };
+    Range::new(lo, range.max() + 1.0)
+}
+
+// The temperature range of the stations widened to fit the feels like line and the
+// percentile band, along with the band on that range.
+pub(crate) fn temperature_range_with_band(
+    year: time::Year,
+    stations: &[Station],
+    history: Option<&Climatology>,
+    opts: &Options,
+) -> (Range, Option<(Series, Series)>) {
+    let band = history.map(|history| percentile_band(year, history, opts));
+    let range = match opts.feels_like {
+        true => Range::union(
+            &temperature_range_of(year, stations),
+            &feels_like_range_of(year, stations, opts),
+        ),
+        false => temperature_range_of(year, stations),
+    };
+    let range = match (&opts.temperature_range, &band) {
+        (Some(range), _) => range.clone(),
+        (None, Some((low, high))) => Range::union(&range, &Range::union(low.range(), high.range())),
+        (None, None) => range,
+    };
+    let band = band.map(|(low, high)| (low.with_range(&range), high.with_range(&range)));
+    (range, band)
+}
+
+// The range of both the mean and the feels like temperatures of the stations, unless the
+// temperature scale is pinned.
+pub(crate) fn feels_like_range_of(year: time::Year, stations: &[Station], opts: &Options) -> Range {
+    if let Some(range) = &opts.temperature_range {
+        return range.clone();
+    }
+    stations
+        .iter()
+        .map(|station| {
+            let mean_temps = Series::for_each_day(year, station.days().iter(), |day| {
+                day.mean_temperature().map(|t| t.in_fahrenheit())
+            });
+            let feels_like = Series::for_each_day(year, station.days().iter(), feels_like);
+            Range::union(mean_temps.range(), feels_like.range())
+        })
+        .reduce(|a, b| Range::union(&a, &b))
+        .map(widened)
+        .unwrap_or_else(|| Range::new(0.0, 1.0))
+}
+
+pub(crate) fn wind_range_of(year: time::Year, stations: &[Station], opts: &Options) -> Range {
+    if let Some(range) = &opts.wind_range {
+        return range.clone();
+    }
+    stations
+        .iter()
+        .map(|station| {
+            let mean_wind = Series::for_each_day(year, station.days().iter(), |day| {
+                day.mean_wind().map(|s| s.in_knots())
+            });
+            let max_sustained_wind = Series::for_each_day(year, station.days().iter(), |day| {
+                day.max_sustained_wind().map(|s| s.in_knots())
+            });
+            Range::union(mean_wind.range(), max_sustained_wind.range())
+        })
+        .reduce(|a, b| Range::union(&a, &b))
+        .map(widened)
+        .unwrap_or_else(|| Range::new(0.0, 1.0))
+}
+
+pub(crate) fn precipitation_range_of(
+    year: time::Year,
+    stations: &[Station],
+    opts: &Options,
+) -> Range {
+    if let Some(range) = &opts.precipitation_range {
+        return range.clone();
+    }
+    // monthly wedges run from nothing to the month's total.
+    let range_of = |station| match opts.monthly {
+        true => Range::new(
+            0.0,
+            precipitation_series(year, station)
+                .resample_monthly(year, |vals| vals.iter().sum())
+                .values()
+                .iter()
+                .fold(0.0, |a: f64, b| a.max(*b)),
+        ),
+        false => precipitation_series(year, station).range().clone(),
+    };
+    stations
+        .iter()
+        .map(range_of)
+        .reduce(|a, b| Range::union(&a, &b))
+        .map(widened)
+        .unwrap_or_else(|| Range::new(0.0, 1.0))
+}
+
+pub(crate) fn precipitation_series(year: time::Year, station: &gsod::Station) -> Series {
+    Series::for_each_day(year, station.days().iter(), |day| {
+        match day.precipitation() {
+            Some(p) => Some(p.in_inches()),
+            None => Some(0.0),
+        }
+    })
+}
+
+// Whether the precipitation of each day of the year fell as snow, going by the snow flag of
+// the day or by the snow on the ground getting deeper since the day before.
+pub(crate) fn snow_days(year: time::Year, station: &gsod::Station) -> Vec<bool> {
+    let flags = Series::for_each_day(year, station.days().iter(), |day| {
+        Some(if day.indicators().snow() { 1.0 } else { 0.0 })
+    });
+    let depth = Series::for_each_day(year, station.days().iter(), |day| {
+        day.snow_depth().map(|d| d.in_inches())
+    });
+    (0..flags.values().len())
+        .map(|i| {
+            let deeper = i > 0
+                && !depth.is_filled(i)
+                && !depth.is_filled(i - 1)
+                && depth.values()[i] > depth.values()[i - 1];
+            (!flags.is_filled(i) && flags.values()[i] > 0.0) || deeper
+        })
+        .collect()
+}
+
+// The daily min, max and mean temperature series for a year, already downsampled.
+// The 10th and 90th percentiles of the historical mean temperature on each day, downsampled
+// to match the temperature series.
+fn percentile_band(year: time::Year, history: &Climatology, opts: &Options) -> (Series, Series) {
+    let low = history.percentile(year, 0.1);
+    let high = history.percentile(year, 0.9);
+    if opts.downsample_by > 1 {
+        let k = opts.downsample_by as usize;
+        let mean = |vals: &[f64]| vals.iter().sum::<f64>() / vals.len() as f64;
+        (low.downsample_by(k, mean), high.downsample_by(k, mean))
+    } else {
+        (low, high)
+    }
+}
+
+// the samples and the sigma band are drawn only by the command line tool.
+#[cfg_attr(not(feature = "native"), allow(dead_code))]
+pub(crate) struct TemperatureSeries {
+    pub(crate) min: Series,
+    pub(crate) max: Series,
+    pub(crate) mean: Series,
+    pub(crate) samples: Series,
+    // a standard deviation below and above the mean, when --temperature-band has it.
+    pub(crate) sigma: Option<(Series, Series)>,
+    pub(crate) avg: f64,
+    pub(crate) high: f64,
+    pub(crate) low: f64,
+}
+
+// The wedge of each month of a panel, from low to high, and the mean that is marked across
+// it, for panels that have one.
+#[cfg(feature = "native")]
+pub(crate) struct MonthlySeries {
+    pub(crate) low: Series,
+    pub(crate) high: Series,
+    pub(crate) mean: Option<Series>,
+}
+
+// Collapses the days of a panel to its months on the given range: the lowest min and the
+// highest max temperature around the mean temperature, the same of the feels like
+// temperature, the lowest mean wind up to the highest sustained wind around the mean wind,
+// and the total precipitation, on --precip-scale.
+#[cfg(feature = "native")]
+pub(crate) fn monthly_series(
+    year: time::Year,
+    station: &gsod::Station,
+    panel: Panel,
+    range: &Range,
+    opts: &Options,
+) -> MonthlySeries {
+    let series =
+        |f: fn(&gsod::Day) -> Option<f64>| Series::for_each_day(year, station.days().iter(), f);
+    let min = |s: Series| {
+        s.resample_monthly(year, |vals| vals.iter().fold(f64::MAX, |a, b| a.min(*b)))
+            .with_range(range)
+    };
+    let max = |s: Series| {
+        s.resample_monthly(year, |vals| vals.iter().fold(f64::MIN, |a, b| a.max(*b)))
+            .with_range(range)
+    };
+    let mean = |s: Series| {
+        s.resample_monthly(year, |vals| vals.iter().sum::<f64>() / vals.len() as f64)
+            .with_range(range)
+    };
+    match panel {
+        Panel::Temperature => MonthlySeries {
+            low: min(series(|day| {
+                day.min_temperature().map(|t| t.in_fahrenheit())
+            })),
+            high: max(series(|day| {
+                day.max_temperature().map(|t| t.in_fahrenheit())
+            })),
+            mean: Some(mean(series(|day| {
+                day.mean_temperature().map(|t| t.in_fahrenheit())
+            }))),
+        },
+        Panel::FeelsLike => MonthlySeries {
+            low: min(series(feels_like)),
+            high: max(series(feels_like)),
+            mean: Some(mean(series(feels_like))),
+        },
+        Panel::Wind => MonthlySeries {
+            low: min(series(|day| day.mean_wind().map(|s| s.in_knots()))),
+            high: max(series(|day| day.max_sustained_wind().map(|s| s.in_knots()))),
+            mean: Some(mean(series(|day| day.mean_wind().map(|s| s.in_knots())))),
+        },
+        Panel::Precipitation => {
+            let range = opts.precip_scale.transform(range);
+            let totals = precipitation_series(year, station)
+                .resample_monthly(year, |vals| vals.iter().sum())
+                .map(|v| opts.precip_scale.apply(v))
+                .with_range(&range);
+            MonthlySeries {
+                low: Series::from_iterator(totals.values().iter().map(|_| Some(0.0)))
+                    .with_range(&range),
+                high: totals,
+                mean: None,
+            }
+        }
+    }
+}
+
+// Builds the temperature series for a year. The series share the given range, or the union
+// of the min and max temperatures when no range is given.
+pub(crate) fn temperature_series(
+    year: time::Year,
+    station: &gsod::Station,
+    range: Option<&Range>,
+    opts: &Options,
+) -> TemperatureSeries {
+    let min_temps = Series::for_each_day(year, station.days().iter(), |day| {
+        day.min_temperature().map(|t| t.in_fahrenheit())
+    });
+
+    let max_temps = Series::for_each_day(year, station.days().iter(), |day| {
+        day.max_temperature().map(|t| t.in_fahrenheit())
+    });
+
+    let mean_temps = Series::for_each_day(year, station.days().iter(), |day| {
+        day.mean_temperature().map(|t| t.in_fahrenheit())
+    });
+
+    let range = match range {
+        Some(range) => range.clone(),
+        None => Range::union(max_temps.range(), min_temps.range()),
+    };
+
+    let min_temps = min_temps.with_range(&range);
+    let max_temps = max_temps.with_range(&range);
+    let mean_temps = mean_temps.with_range(&range);
+
+    let avg_mean_temp = mean_temps.summarize().mean();
+    let high = max_temps.summarize().max();
+    let low = min_temps.summarize().min();
+
+    let (min_temps, max_temps) = if opts.envelope_window > 1 {
+        let window = opts.envelope_window as usize;
+        (
+            min_temps.rolling(window, |vals| {
+                vals.iter().fold(f64::MAX, |min, val| min.min(*val))
+            }),
+            max_temps.rolling(window, |vals| {
+                vals.iter().fold(f64::MIN, |max, val| max.max(*val))
+            }),
+        )
+    } else {
+        (min_temps, max_temps)
+    };
+
+    let min_temps = if opts.downsample_by > 1 {
+        min_temps.downsample_by(opts.downsample_by as usize, |vals| {
+            vals.iter().fold(f64::MAX, |min, val| min.min(*val))
+        })
+    } else {
+        min_temps
+    };
+
+    let max_temps = if opts.downsample_by > 1 {
+        max_temps.downsample_by(opts.downsample_by as usize, |vals| {
+            vals.iter().fold(f64::MIN, |max, val| max.max(*val))
+        })
+    } else {
+        max_temps
+    };
+
+    let sigma = match opts.temperature_band {
+        TemperatureBand::MinMax => None,
+        TemperatureBand::Sigma | TemperatureBand::Both => {
+            Some(sigma_band(&mean_temps, opts.sigma_window as usize, opts))
+        }
+    };
+
+    let samples = mean_temperature_samples(year, station);
+    let mean_temps = downsample_mean(mean_temps, &samples, opts);
+
+    TemperatureSeries {
+        min: min_temps,
+        max: max_temps,
+        mean: mean_temps,
+        samples: downsample_samples(samples, opts),
+        sigma,
+        avg: avg_mean_temp,
+        high,
+        low,
+    }
+}
+
+// The mean temperature over a rolling window of days, a standard deviation lower and higher,
+// downsampled like the rest of the temperatures.
+fn sigma_band(mean_temps: &Series, window: usize, opts: &Options) -> (Series, Series) {
+    let mean = |vals: &[f64]| vals.iter().sum::<f64>() / vals.len() as f64;
+    let std_dev = |vals: &[f64]| {
+        let m = mean(vals);
+        (vals.iter().map(|v| (v - m).powi(2)).sum::<f64>() / vals.len() as f64).sqrt()
+    };
+    let window = window.max(1);
+    let means = mean_temps.rolling(window, mean);
+    let devs = mean_temps.rolling(window, std_dev);
+    let offset = |sign: f64| {
+        Series::from_iterator(
+            means
+                .values()
+                .iter()
+                .zip(devs.values())
+                .enumerate()
+                .map(|(i, (m, d))| (!mean_temps.is_filled(i)).then_some(m + sign * d)),
+        )
+        .with_range(mean_temps.range())
+    };
+    let (low, high) = (offset(-1.0), offset(1.0));
+    if opts.downsample_by > 1 {
+        (
+            low.downsample_by(opts.downsample_by as usize, mean),
+            high.downsample_by(opts.downsample_by as usize, mean),
+        )
+    } else {
+        (low, high)
+    }
+}
+
+// The labelled values in the center of the temperature panel.
+pub(crate) fn temperature_stats(
+    stations: &[Station],
+    temps: &[TemperatureSeries],
+    opts: &Options,
+) -> Vec<(String, String)> {
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v| opts.locale.number(v, 1);
+    match temps {
+        [t] => vec![
+            (label(Label::Max), format!("{}°F", num(t.high))),
+            (label(Label::Avg), format!("{}°F", num(t.avg))),
+            (label(Label::Min), format!("{}°F", num(t.low))),
+        ],
+        _ => stations
+            .iter()
+            .zip(temps.iter())
+            .map(|(s, t)| {
+                (
+                    format!("{} {}", label(Label::Avg), s.id()),
+                    format!("{}°F", num(t.avg)),
+                )
+            })
+            .collect(),
+    }
+}
+
+// The daily mean temperature on the given range, averaged over --downsample-by days.
+pub(crate) fn mean_temperature_series(
+    year: time::Year,
+    station: &gsod::Station,
+    range: &Range,
+    opts: &Options,
+) -> Series {
+    downsample_mean(
+        Series::for_each_day(year, station.days().iter(), |day| {
+            day.mean_temperature().map(|t| t.in_fahrenheit())
+        })
+        .with_range(range),
+        &mean_temperature_samples(year, station),
+        opts,
+    )
+}
+
+// The number of observations behind each daily mean temperature.
+fn mean_temperature_samples(year: time::Year, station: &gsod::Station) -> Series {
+    Series::for_each_day(year, station.days().iter(), |day| {
+        day.mean_temperature().map(|t| t.samples() as f64)
+    })
+}
+
+// The daily feels like temperature on the given range, averaged over --downsample-by days.
+pub(crate) fn feels_like_series(
+    year: time::Year,
+    station: &gsod::Station,
+    range: &Range,
+    opts: &Options,
+) -> Series {
+    downsample_mean(
+        Series::for_each_day(year, station.days().iter(), feels_like).with_range(range),
+        &mean_temperature_samples(year, station),
+        opts,
+    )
+}
+
+// The mean of each --downsample-by days, weighted by the observations behind each day.
+fn downsample_mean(series: Series, samples: &Series, opts: &Options) -> Series {
+    if opts.downsample_by > 1 {
+        series.downsample_weighted(opts.downsample_by as usize, samples)
+    } else {
+        series
+    }
+}
+
+// The fewest observations behind a day of each --downsample-by days, which is how sure the
+// downsampled value can be.
+fn downsample_samples(samples: Series, opts: &Options) -> Series {
+    if opts.downsample_by > 1 {
+        samples.downsample_by(opts.downsample_by as usize, |vals| {
+            vals.iter().fold(f64::MAX, |min, val| min.min(*val))
+        })
+    } else {
+        samples
+    }
+}
+
+// The labelled values in the center of the feels like panel.
+pub(crate) fn feels_like_stats(
+    year: time::Year,
+    stations: &[Station],
+    opts: &Options,
+) -> Vec<(String, String)> {
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v| format!("{}°F", opts.locale.number(v, 1));
+    let summaries = stations
+        .iter()
+        .map(|station| Series::for_each_day(year, station.days().iter(), feels_like).summarize())
+        .collect::<Vec<_>>();
+    match &summaries[..] {
+        [s] => vec![
+            (label(Label::Max), num(s.max())),
+            (label(Label::Avg), num(s.mean())),
+            (label(Label::Min), num(s.min())),
+        ],
+        _ => stations
+            .iter()
+            .zip(summaries.iter())
+            .map(|(station, s)| {
+                (
+                    format!("{} {}", label(Label::Avg), station.id()),
+                    num(s.mean()),
+                )
+            })
+            .collect(),
+    }
+}
+
+#[cfg_attr(not(feature = "native"), allow(dead_code))]
+pub(crate) struct WindSeries {
+    pub(crate) mean: Series,
+    pub(crate) max: Series,
+    pub(crate) samples: Series,
+    pub(crate) avg: f64,
+    pub(crate) high: f64,
+}
+
+pub(crate) fn wind_series(
+    year: time::Year,
+    station: &gsod::Station,
+    range: &Range,
+    opts: &Options,
+) -> WindSeries {
+    let mean_wind = Series::for_each_day(year, station.days().iter(), |day| {
+        day.mean_wind().map(|s| s.in_knots())
+    });
+
+    let max_sustained_wind = Series::for_each_day(year, station.days().iter(), |day| {
+        day.max_sustained_wind().map(|s| s.in_knots())
+    });
+
+    let mean_wind = mean_wind.with_range(range);
+    let max_sustained_wind = max_sustained_wind.with_range(range);
+
+    let avg_mean_wind = mean_wind.summarize().mean();
+    let max_wind = mean_wind
+        .summarize()
+        .max()
+        .max(max_sustained_wind.summarize().max());
+
+    let samples = Series::for_each_day(year, station.days().iter(), |day| {
+        day.mean_wind().map(|s| s.samples() as f64)
+    });
+    let mean_wind = downsample_mean(mean_wind, &samples, opts);
+
+    let max_sustained_wind = if opts.downsample_by > 1 {
+        max_sustained_wind.downsample_by(opts.downsample_by as usize, |vals| {
+            vals.iter().fold(f64::MIN, |max, val| max.max(*val))
+        })
+    } else {
+        max_sustained_wind
+    };
+
+    WindSeries {
+        mean: mean_wind,
+        max: max_sustained_wind,
+        samples: downsample_samples(samples, opts),
+        avg: avg_mean_wind,
+        high: max_wind,
+    }
+}
+
+// The labelled values in the center of the wind panel.
+pub(crate) fn wind_stats(
+    stations: &[Station],
+    winds: &[WindSeries],
+    opts: &Options,
+) -> Vec<(String, String)> {
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v| opts.locale.number(v, 1);
+    match winds {
+        [w] => vec![
+            (label(Label::Max), format!("{} kts", num(w.high))),
+            (label(Label::Avg), format!("{} kts", num(w.avg))),
+        ],
+        _ => stations
+            .iter()
+            .zip(winds.iter())
+            .map(|(s, w)| {
+                (
+                    format!("{} {}", label(Label::Avg), s.id()),
+                    format!("{} kts", num(w.avg)),
+                )
+            })
+            .collect(),
+    }
+}
+
+// The labelled values in the center of the precipitation panel.
+pub(crate) fn precipitation_stats(
+    year: time::Year,
+    stations: &[Station],
+    opts: &Options,
+) -> Vec<(String, String)> {
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v| opts.locale.number(v, 1);
+    let mut labels = Vec::new();
+    for station in stations {
+        let percipitation = precipitation_series(year, station);
+        let num_days =
+            percipitation
+                .values()
+                .iter()
+                .fold(0, |sum, val| if *val > 0.0 { sum + 1 } else { sum });
+        let total = percipitation.summarize().sum();
+        match stations.len() {
+            1 if opts.snow_split => {
+                let snow = snow_days(year, station);
+                let num_snow = percipitation
+                    .values()
+                    .iter()
+                    .zip(snow)
+                    .filter(|(val, snow)| **val > 0.0 && *snow)
+                    .count();
+                labels.push((label(Label::RainDays), format!("{}", num_days - num_snow)));
+                labels.push((label(Label::SnowDays), format!("{}", num_snow)));
+                labels.push((label(Label::Total), format!("{} in", num(total))));
+            }
+            1 => {
+                labels.push((label(Label::Days), format!("{}", num_days)));
+                labels.push((label(Label::Total), format!("{} in", num(total))));
+            }
+            _ => labels.push((
+                format!("{} {}", label(Label::Total), station.id()),
+                format!("{} in", num(total)),
+            )),
+        }
+    }
+    labels
+}
+
+// The labelled values in the center of a panel as they're given in the [center] table of the
+// config, or none to leave the panel to its usual stats. Overlaid stations keep theirs too,
+// since there's only room for one value of each.
+pub(crate) fn center_stats(
+    year: time::Year,
+    stations: &[Station],
+    panel: Panel,
+    opts: &Options,
+) -> Option<Vec<(String, String)>> {
+    let station = match stations {
+        [station] => station,
+        _ => return None,
+    };
+    let stats = opts.center.stats(panel);
+    if stats.is_empty() {
+        return None;
+    }
+
+    let each_day =
+        |f: fn(&gsod::Day) -> Option<f64>| Series::for_each_day(year, station.days().iter(), f);
+    let (values, high, low, units) = match panel {
+        Panel::Temperature => (
+            each_day(|day| day.mean_temperature().map(|t| t.in_fahrenheit())),
+            each_day(|day| day.max_temperature().map(|t| t.in_fahrenheit())),
+            each_day(|day| day.min_temperature().map(|t| t.in_fahrenheit())),
+            "°F",
+        ),
+        Panel::FeelsLike => (
+            each_day(feels_like),
+            each_day(feels_like),
+            each_day(feels_like),
+            "°F",
+        ),
+        Panel::Wind => (
+            each_day(|day| day.mean_wind().map(|s| s.in_knots())),
+            each_day(|day| day.max_sustained_wind().map(|s| s.in_knots())),
+            each_day(|day| day.mean_wind().map(|s| s.in_knots())),
+            " kts",
+        ),
+        Panel::Precipitation => (
+            precipitation_series(year, station),
+            precipitation_series(year, station),
+            precipitation_series(year, station),
+            " in",
+        ),
+    };
+    // whether each of the days with precipitation had snow.
+    let wet = || {
+        values
+            .values()
+            .iter()
+            .zip(snow_days(year, station))
+            .filter(|(val, _)| **val > 0.0)
+            .map(|(_, snow)| snow)
+            .collect::<Vec<_>>()
+    };
+    let summary = values.summarize();
+    let num = |v| format!("{}{}", opts.locale.number(v, 1), units);
+    Some(
+        stats
+            .iter()
+            .map(|s| {
+                let value = match s.stat {
+                    Stat::Max => num(high.summarize().max().max(summary.max())),
+                    Stat::Mean => num(summary.mean()),
+                    Stat::Median => num(values.quantile(0.5)),
+                    Stat::P10 => num(values.quantile(0.1)),
+                    Stat::P90 => num(values.quantile(0.9)),
+                    Stat::Min => num(low.summarize().min().min(summary.min())),
+                    Stat::Total => num(summary.sum()),
+                    Stat::Days => format!("{}", wet().len()),
+                    Stat::RainDays => format!("{}", wet().iter().filter(|snow| !**snow).count()),
+                    Stat::SnowDays => format!("{}", wet().iter().filter(|snow| **snow).count()),
+                    Stat::SummerDays => format!("{}", high.count_where(|t| t > 90.0)),
+                    Stat::FrostDays => format!("{}", low.count_where(|t| t < 32.0)),
+                    Stat::IceDays => format!("{}", low.count_where(|t| t < 0.0)),
+                };
+                let label = match &s.label {
+                    Some(label) => label.clone(),
+                    // the day counts are lowercase for the corner of the temperature panel.
+                    None => opts.locale.label(s.stat.label()).to_uppercase(),
+                };
+                (label, value)
+            })
+            .collect(),
+    )
+}
+
+// The start and end of each month as a fraction of the year.
+pub(crate) fn month_spans(year: time::Year) -> Vec<(f64, f64)> {
+    let num_days = year.duration().num_days();
+    year.months()
+        .map(|month| {
+            let s = month.start().signed_duration_since(year.start()).num_days();
+            let e = month.end().signed_duration_since(year.start()).num_days();
+            (s as f64 / num_days as f64, e as f64 / num_days as f64)
+        })
+        .collect()
+}
+
+// Prepares the radial chart of the stations' year for drawing it elsewhere. The names are
+// those of the stations, in the same order.
+pub(crate) fn model(
+    year: time::Year,
+    stations: &[Station],
+    names: &[String],
+    history: Option<&Climatology>,
+    opts: &Options,
+) -> Result<model::Chart, Box<dyn Error>> {
+    check_panels(stations, opts)?;
+    let (temperature_range, band) = temperature_range_with_band(year, stations, history, opts);
+
+    let stats = |labels: Vec<(String, String)>| {
+        labels
+            .into_iter()
+            .map(|(label, value)| model::Stat { label, value })
+            .collect()
+    };
+    let panel = |panel: Panel, units: &str, range: &Range, lim: f64| {
+        let scale = Scale::from_range(range, lim)?;
+        Ok::<_, Box<dyn Error>>(model::Panel {
+            panel: String::from(panel.to_possible_value().unwrap().get_name()),
+            title: String::from(opts.locale.label(panel.title())),
+            units: String::from(units),
+            min: range.min(),
+            max: range.max(),
+            scale: scale
+                .steps()
+                .iter()
+                .enumerate()
+                .map(|(i, step)| model::Step {
+                    value: *step,
+                    position: range.normalize(*step).value(),
+                    label: opts.locale.localize(&scale.label_for(i)),
+                })
+                .collect(),
+            band: None,
+            series: Vec::new(),
+            stats: Vec::new(),
+        })
+    };
+
+    let mut panels = Vec::new();
+    for p in &opts.panels {
+        panels.push(match p {
+            Panel::Temperature => {
+                let temps = stations
+                    .iter()
+                    .map(|station| {
+                        temperature_series(year, station, Some(&temperature_range), opts)
+                    })
+                    .collect::<Vec<_>>();
+                model::Panel {
+                    band: band.as_ref().map(|(low, high)| model::Band {
+                        low: model::normalized(low),
+                        high: model::normalized(high),
+                    }),
+                    series: stations
+                        .iter()
+                        .zip(temps.iter())
+                        .flat_map(|(s, t)| {
+                            [
+                                Some(model::Series::range("temperature", s.id(), &t.min, &t.max)),
+                                Some(model::Series::line("mean", s.id(), &t.mean)),
+                                opts.feels_like.then(|| {
+                                    model::Series::line(
+                                        "feels-like",
+                                        s.id(),
+                                        &feels_like_series(year, s, &temperature_range, opts),
+                                    )
+                                }),
+                            ]
+                        })
+                        .flatten()
+                        .collect(),
+                    stats: stats(
+                        center_stats(year, stations, Panel::Temperature, opts)
+                            .unwrap_or_else(|| temperature_stats(stations, &temps, opts)),
+                    ),
+                    ..panel(*p, "°F", &temperature_range, 5.0)?
+                }
+            }
+            Panel::FeelsLike => {
+                let range = feels_like_range_of(year, stations, opts);
+                model::Panel {
+                    series: stations
+                        .iter()
+                        .flat_map(|s| {
+                            [
+                                model::Series::line(
+                                    "mean",
+                                    s.id(),
+                                    &mean_temperature_series(year, s, &range, opts),
+                                ),
+                                model::Series::line(
+                                    "feels-like",
+                                    s.id(),
+                                    &feels_like_series(year, s, &range, opts),
+                                ),
+                            ]
+                        })
+                        .collect(),
+                    stats: stats(
+                        center_stats(year, stations, Panel::FeelsLike, opts)
+                            .unwrap_or_else(|| feels_like_stats(year, stations, opts)),
+                    ),
+                    ..panel(*p, "°F", &range, 5.0)?
+                }
+            }
+            Panel::Wind => {
+                let range = wind_range_of(year, stations, opts);
+                let winds = stations
+                    .iter()
+                    .map(|station| wind_series(year, station, &range, opts))
+                    .collect::<Vec<_>>();
+                model::Panel {
+                    series: stations
+                        .iter()
+                        .zip(winds.iter())
+                        .map(|(s, w)| model::Series::range("wind", s.id(), &w.mean, &w.max))
+                        .collect(),
+                    stats: stats(
+                        center_stats(year, stations, Panel::Wind, opts)
+                            .unwrap_or_else(|| wind_stats(stations, &winds, opts)),
+                    ),
+                    ..panel(*p, "kts", &range, 5.0)?
+                }
+            }
+            Panel::Precipitation => {
+                let range = precipitation_range_of(year, stations, opts);
+                model::Panel {
+                    series: stations
+                        .iter()
+                        .map(|s| {
+                            model::Series::bars(
+                                "precipitation",
+                                s.id(),
+                                &precipitation_series(year, s).with_range(&range),
+                            )
+                        })
+                        .collect(),
+                    stats: stats(
+                        center_stats(year, stations, Panel::Precipitation, opts)
+                            .unwrap_or_else(|| precipitation_stats(year, stations, opts)),
+                    ),
+                    ..panel(*p, "in", &range, 4.0)?
+                }
+            }
+        });
+    }
+
+    let labels = match opts.month_labels {
+        MonthLabels::Auto => MonthLabels::All,
+        labels => labels,
+    };
+    Ok(model::Chart {
+        stations: stations
+            .iter()
+            .zip(names)
+            .map(|(s, name)| model::Station {
+                id: String::from(s.id()),
+                name: name.clone(),
+            })
+            .collect(),
+        year: year.ordinal(),
+        days: year.days().count(),
+        months: year
+            .months()
+            .zip(month_spans(year))
+            .enumerate()
+            .map(|(i, (month, (start, end)))| model::Month {
+                start,
+                end,
+                label: labels.label_for(i, &month, &opts.locale),
+            })
+            .collect(),
+        panels,
+    })
+}
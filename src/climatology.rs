@@ -0,0 +1,61 @@
+use super::{gsod, percentile_of, time, Series};
+use chrono::prelude::*;
+use std::collections::HashMap;
+
+// The values of a metric on each day of the year, gathered across a number of years. Days
+// are keyed by month and day rather than ordinal so that a leap day doesn't shift the rest
+// of the year.
+#[derive(Debug)]
+pub struct Climatology {
+    days: HashMap<(u32, u32), Vec<f64>>,
+    num_years: usize,
+}
+
+impl Climatology {
+    pub fn from_stations<F>(stations: &[(time::Year, gsod::Station)], f: F) -> Climatology
+    where
+        F: Fn(&gsod::Day) -> Option<f64>,
+    {
+        let mut days: HashMap<(u32, u32), Vec<f64>> = HashMap::new();
+        for (year, station) in stations {
+            for day in station.days() {
+                let date = day.date();
+                if date.year() != year.ordinal() {
+                    continue;
+                }
+                if let Some(v) = f(day) {
+                    days.entry((date.month(), date.day())).or_default().push(v);
+                }
+            }
+        }
+
+        for vals in days.values_mut() {
+            vals.sort_by(|a, b| a.total_cmp(b));
+        }
+
+        Climatology {
+            days,
+            num_years: stations.len(),
+        }
+    }
+
+    pub fn num_years(&self) -> usize {
+        self.num_years
+    }
+
+    // The p-th percentile, p in [0, 1], of each day of the given year. A leap day falls back
+    // to the 28th of February when the history has no leap years, and days without any
+    // history are filled.
+    pub fn percentile(&self, year: time::Year, p: f64) -> Series {
+        Series::from_iterator(year.days().map(|day| {
+            let date = day.date();
+            self.days
+                .get(&(date.month(), date.day()))
+                .or_else(|| match (date.month(), date.day()) {
+                    (2, 29) => self.days.get(&(2, 28)),
+                    _ => None,
+                })
+                .map(|vals| percentile_of(vals, p))
+        }))
+    }
+}
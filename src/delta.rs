@@ -0,0 +1,186 @@
+use super::{
+    chart::{self, Panel},
+    gsod::Station,
+    heatmap,
+    locale::Label,
+    render, stripes, time, Font, Range, Scale, TAU,
+};
+use cairo::{Context, FontSlant, FontWeight};
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+use std::error::Error;
+
+fn month_day(date: NaiveDate) -> (u32, u32) {
+    (date.month(), date.day())
+}
+
+// The difference of each day of the year from the same day of the baseline year, for the
+// days both of them have. February 29th only has one when both years are leap years.
+fn deltas(
+    year: time::Year,
+    station: &Station,
+    baseline: &Station,
+    metric: heatmap::Metric,
+) -> Vec<Option<f64>> {
+    let idx = baseline
+        .days()
+        .iter()
+        .filter_map(|day| Some((month_day(day.date()), metric.value_of(day)?)))
+        .collect::<HashMap<_, _>>();
+    let vals = station
+        .days()
+        .iter()
+        .filter_map(|day| Some((day.date(), metric.value_of(day)?)))
+        .collect::<HashMap<NaiveDate, _>>();
+    year.days()
+        .map(|day| {
+            let v = vals.get(&day.date())?;
+            let base = idx.get(&month_day(day.date()))?;
+            Some(v - base)
+        })
+        .collect()
+}
+
+// Renders how much warmer or cooler, or wetter or drier, each day of the year was than the
+// same day of the baseline year, as a bar out from a zero ring for the days above it and in
+// toward the center for the days below it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    year: time::Year,
+    station: &Station,
+    base_year: time::Year,
+    base_station: &Station,
+    metric: heatmap::Metric,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let header_height = render::render_header(
+        ctx,
+        station,
+        &render::describe_year(year, &opts.locale),
+        width,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let vals = deltas(year, station, base_station, metric);
+    let diffs = vals.iter().flatten().copied().collect::<Vec<_>>();
+    if diffs.is_empty() {
+        return Err(format!(
+            "no days of station {} to compare between {} and {}",
+            station.id(),
+            year,
+            base_year
+        )
+        .into());
+    }
+    let extent = diffs
+        .iter()
+        .fold(0.0f64, |max, v| max.max(v.abs()))
+        .max(0.1);
+    let range = Range::new(-extent, extent);
+    let gradient = stripes::diverging();
+
+    let body_height = height - header_height;
+    let r = (width.min(body_height) / 2.0 - 40.0).max(10.0);
+    let rrange = Range::new(r * opts.radii.min(), r * opts.radii.max());
+    let zero = rrange.project(range.normalize_clamped(0.0));
+
+    ctx.save()?;
+    ctx.translate(width / 2.0, header_height + body_height / 2.0);
+
+    render::render_title(
+        ctx,
+        &format!(
+            "{} {} − {}",
+            opts.locale.label(metric.title()),
+            year,
+            base_year
+        ),
+        0.0,
+        -rrange.max() - 10.0,
+        opts,
+    )?;
+
+    ctx.save()?;
+    render::render_months(ctx, year, &opts.month_range(&rrange), opts)?;
+    ctx.restore()?;
+
+    let panel = match metric {
+        heatmap::Metric::Temperature => Panel::Temperature,
+        heatmap::Metric::Precipitation => Panel::Precipitation,
+    };
+    ctx.save()?;
+    let scale = Scale::from_range(&range, 5.0)?;
+    render::render_scales(ctx, &scale, &range, &rrange, metric.units(), panel, opts)?;
+    ctx.restore()?;
+
+    ctx.save()?;
+    render::orient(ctx, year, opts);
+    let dt = TAU / vals.len() as f64;
+    ctx.set_line_width((zero * dt).max(1.0));
+    for (i, v) in vals.iter().enumerate() {
+        let v = match v {
+            Some(v) => *v,
+            None => continue,
+        };
+        let t = i as f64 * dt - TAU / 4.0;
+        let rb = rrange.project(range.normalize_clamped(v));
+        gradient.at(range.normalize_clamped(v)).set(ctx);
+        ctx.new_path();
+        ctx.move_to(zero * t.cos(), zero * t.sin());
+        ctx.line_to(rb * t.cos(), rb * t.sin());
+        ctx.stroke()?;
+    }
+
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.set_line_width(1.0);
+    ctx.new_path();
+    ctx.arc(0.0, 0.0, zero, 0.0, TAU);
+    ctx.stroke()?;
+    ctx.restore()?;
+
+    let signed = |v: f64| match v > 0.0 {
+        true => format!("+{}{}", opts.locale.number(v, 1), metric.units()),
+        false => format!("{}{}", opts.locale.number(v, 1), metric.units()),
+    };
+    let label = |l| String::from(opts.locale.label(l));
+    let mean = diffs.iter().sum::<f64>() / diffs.len() as f64;
+    let (low, high) = diffs
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+    ctx.save()?;
+    render::render_center_text(
+        ctx,
+        &[
+            (label(Label::Max), signed(high)),
+            (label(Label::Avg), signed(mean)),
+            (label(Label::Min), signed(low)),
+        ],
+        &Font::new(
+            "HelveticaNeue-Medium",
+            FontSlant::Normal,
+            FontWeight::Bold,
+            11.0,
+        ),
+        &Font::new(
+            "HelveticaNeue-Thin",
+            FontSlant::Normal,
+            FontWeight::Normal,
+            32.0,
+        ),
+        &opts.theme.foreground.with_alpha(0.6),
+        opts,
+    )?;
+    ctx.restore()?;
+
+    ctx.restore()?;
+    Ok(())
+}
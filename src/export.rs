@@ -0,0 +1,282 @@
+use super::{gsod, isd, render, Data, YearSpec};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum Units {
+    Imperial,
+    Metric,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The station, by GSOD id or by ICAO or IATA airport code (e.g. KBOS or BOS).
+    #[clap(long, alias = "station", default_value_t = String::from("72309693727"))]
+    station_id: String,
+
+    /// The year to use, "latest" for the most recent complete year or "current" for the
+    /// year so far.
+    #[clap(long, default_value = "latest")]
+    year: YearSpec,
+
+    /// What to do with rows whose date falls outside of the requested year.
+    #[clap(long, value_enum, default_value_t = render::StrayDays::Drop)]
+    stray_days: render::StrayDays,
+
+    #[clap(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+
+    #[clap(long, value_enum, default_value_t = Units::Imperial)]
+    units: Units,
+
+    /// Export the station's hourly ISD observations instead of its days.
+    #[clap(long, default_value_t = false)]
+    hourly: bool,
+
+    /// Where to write the export, or "-" for stdout.
+    #[clap(long, default_value_t = String::from("-"))]
+    destination: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Quantity {
+    Temperature,
+    Pressure,
+    Visibility,
+    WindSpeed,
+    Precipitation,
+    SnowDepth,
+    Direction,
+}
+
+impl Quantity {
+    fn suffix(self, units: Units) -> &'static str {
+        match (self, units) {
+            (Quantity::Temperature, Units::Imperial) => "f",
+            (Quantity::Temperature, Units::Metric) => "c",
+            (Quantity::Pressure, _) => "hpa",
+            (Quantity::Visibility, Units::Imperial) => "mi",
+            (Quantity::Visibility, Units::Metric) => "km",
+            (Quantity::WindSpeed, Units::Imperial) => "kt",
+            (Quantity::WindSpeed, Units::Metric) => "ms",
+            (Quantity::Precipitation, Units::Imperial) => "in",
+            (Quantity::Precipitation, Units::Metric) => "mm",
+            (Quantity::SnowDepth, Units::Imperial) => "in",
+            (Quantity::SnowDepth, Units::Metric) => "cm",
+            (Quantity::Direction, _) => "deg",
+        }
+    }
+
+    // values come out of gsod::Day in the units the archive uses, which are the imperial ones
+    // (save for wind in knots and pressure in millibars).
+    fn convert(self, v: f64, units: Units) -> f64 {
+        match (self, units) {
+            (_, Units::Imperial) | (Quantity::Pressure | Quantity::Direction, _) => v,
+            (q, Units::Metric) => {
+                let v = match q {
+                    Quantity::Temperature => (v - 32.0) * 5.0 / 9.0,
+                    Quantity::Visibility => v * 1.609344,
+                    Quantity::WindSpeed => v * 0.514444,
+                    Quantity::Precipitation => v * 25.4,
+                    Quantity::SnowDepth => v * 2.54,
+                    Quantity::Pressure | Quantity::Direction => v,
+                };
+                // the archive only carries a tenth or hundredth of precision, so don't
+                // pretend the converted values have more.
+                (v * 100.0).round() / 100.0
+            }
+        }
+    }
+}
+
+type Column<T> = (&'static str, Quantity, fn(&T) -> Option<f64>);
+
+const COLUMNS: &[Column<gsod::Day>] = &[
+    ("mean_temperature", Quantity::Temperature, |day| {
+        day.mean_temperature().map(|t| t.in_fahrenheit())
+    }),
+    ("min_temperature", Quantity::Temperature, |day| {
+        day.min_temperature().map(|t| t.in_fahrenheit())
+    }),
+    ("max_temperature", Quantity::Temperature, |day| {
+        day.max_temperature().map(|t| t.in_fahrenheit())
+    }),
+    ("mean_dewpoint", Quantity::Temperature, |day| {
+        day.mean_dewpoint().map(|t| t.in_fahrenheit())
+    }),
+    ("mean_sea_level_pressure", Quantity::Pressure, |day| {
+        day.mean_sea_level_pressure().map(|p| p.in_millibars())
+    }),
+    ("mean_station_pressure", Quantity::Pressure, |day| {
+        day.mean_station_pressure().map(|p| p.in_millibars())
+    }),
+    ("mean_visibility", Quantity::Visibility, |day| {
+        day.mean_visibility().map(|d| d.in_miles())
+    }),
+    ("mean_wind", Quantity::WindSpeed, |day| {
+        day.mean_wind().map(|s| s.in_knots())
+    }),
+    ("max_sustained_wind", Quantity::WindSpeed, |day| {
+        day.max_sustained_wind().map(|s| s.in_knots())
+    }),
+    ("max_wind_gust", Quantity::WindSpeed, |day| {
+        day.max_wind_gust().map(|s| s.in_knots())
+    }),
+    ("precipitation", Quantity::Precipitation, |day| {
+        day.precipitation().map(|p| p.in_inches())
+    }),
+    ("snow_depth", Quantity::SnowDepth, |day| {
+        day.snow_depth().map(|d| d.in_inches())
+    }),
+];
+
+const HOURLY_COLUMNS: &[Column<isd::Hour>] = &[
+    ("temperature", Quantity::Temperature, |hour| {
+        hour.temperature().map(|t| t.in_fahrenheit())
+    }),
+    ("dewpoint", Quantity::Temperature, |hour| {
+        hour.dewpoint().map(|t| t.in_fahrenheit())
+    }),
+    ("sea_level_pressure", Quantity::Pressure, |hour| {
+        hour.sea_level_pressure().map(|p| p.in_millibars())
+    }),
+    ("wind_direction", Quantity::Direction, |hour| {
+        hour.wind_direction()
+    }),
+    ("wind_speed", Quantity::WindSpeed, |hour| {
+        hour.wind_speed().map(|s| s.in_knots())
+    }),
+];
+
+// The rows of an export, each a date (or time) and a value for each of the columns.
+type Rows = Vec<(String, Vec<Option<f64>>)>;
+
+fn header<T>(first: &str, columns: &[Column<T>], units: Units) -> Vec<String> {
+    let mut names = vec![String::from(first)];
+    names.extend(
+        columns
+            .iter()
+            .map(|(name, q, _)| format!("{}_{}", name, q.suffix(units))),
+    );
+    names
+}
+
+fn rows_of<T>(
+    items: &[T],
+    columns: &[Column<T>],
+    units: Units,
+    key: impl Fn(&T) -> String,
+) -> Rows {
+    items
+        .iter()
+        .map(|item| {
+            (
+                key(item),
+                columns
+                    .iter()
+                    .map(|(_, q, f)| f(item).map(|v| q.convert(v, units)))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn write_csv<W: Write>(dst: W, names: &[String], rows: &Rows) -> Result<(), Box<dyn Error>> {
+    let mut w = csv::Writer::from_writer(dst);
+    w.write_record(names)?;
+    for (key, vals) in rows {
+        let mut record = vec![key.clone()];
+        record.extend(vals.iter().map(|v| match v {
+            Some(v) => format!("{}", v),
+            None => String::new(),
+        }));
+        w.write_record(&record)?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    dst: W,
+    station: &gsod::Station,
+    units: Units,
+    // what the rows are called, "days" or "hours".
+    of: &str,
+    names: &[String],
+    rows: &Rows,
+) -> Result<(), Box<dyn Error>> {
+    let rows = rows
+        .iter()
+        .map(|(key, vals)| {
+            let mut vals = vals
+                .iter()
+                .map(|v| serde_json::Value::from(*v))
+                .collect::<Vec<_>>();
+            vals.insert(0, serde_json::Value::from(key.clone()));
+            names
+                .iter()
+                .cloned()
+                .zip(vals)
+                .collect::<serde_json::Map<_, _>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut doc = serde_json::json!({
+        "station": station.id(),
+        "name": station.name(),
+        "units": match units {
+            Units::Imperial => "imperial",
+            Units::Metric => "metric",
+        },
+    });
+    doc[of] = serde_json::Value::from(rows);
+    serde_json::to_writer_pretty(dst, &doc)?;
+    Ok(())
+}
+
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    let year = args.year.resolve(data)?;
+    let station_id = isd::resolve_station(data, &args.station_id)?;
+    let station = render::find_station_for_year(data, year, &station_id, args.stray_days)?;
+
+    let mut dst: Box<dyn Write> = match args.destination.as_str() {
+        "-" => Box::new(io::stdout().lock()),
+        path => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+    };
+
+    let (of, names, rows) = if args.hourly {
+        let hours = isd::hours_for_year(data, year, &station_id)?;
+        (
+            "hours",
+            header("time", HOURLY_COLUMNS, args.units),
+            rows_of(&hours, HOURLY_COLUMNS, args.units, |hour| {
+                hour.time().format("%Y-%m-%dT%H:%MZ").to_string()
+            }),
+        )
+    } else {
+        (
+            "days",
+            header("date", COLUMNS, args.units),
+            rows_of(station.days(), COLUMNS, args.units, |day| {
+                day.date().format("%Y-%m-%d").to_string()
+            }),
+        )
+    };
+
+    match args.format {
+        Format::Csv => write_csv(&mut dst, &names, &rows)?,
+        Format::Json => {
+            write_json(&mut dst, &station, args.units, of, &names, &rows)?;
+            writeln!(dst)?;
+        }
+    }
+    dst.flush()?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+// A Fetcher retrieves the contents of a URL. Data uses it to fill its on-disk cache, so an
+// alternative implementation (a mock serving fixture archives, an async client, ...) only
+// needs to know how to get bytes from somewhere.
+pub trait Fetcher: fmt::Debug + Send + Sync {
+    fn fetch(&self, url: &str, dst: &mut dyn io::Write) -> Result<(), Box<dyn Error>>;
+
+    fn exists(&self, url: &str) -> Result<bool, Box<dyn Error>>;
+
+    // when the file at the URL last changed, for fetchers that can tell.
+    fn modified(&self, _url: &str) -> Result<Option<SystemTime>, Box<dyn Error>> {
+        Ok(None)
+    }
+}
+
+// How to reach the network, for networks that can't reach NOAA directly.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct Settings {
+    /// Send requests through this proxy. Without it, HTTPS_PROXY, HTTP_PROXY and NO_PROXY
+    /// are followed.
+    #[clap(long)]
+    proxy: Option<String>,
+
+    /// Trust the certificates in this PEM file, along with the system's, e.g. for a proxy
+    /// that inspects TLS.
+    #[clap(long)]
+    ca_cert: Option<String>,
+
+    /// Give up on a request after this many seconds, or 0 to wait as long as it takes.
+    #[clap(long, default_value_t = 30)]
+    timeout: u64,
+}
+
+#[derive(Debug)]
+pub struct ReqwestFetcher {
+    client: reqwest::blocking::Client,
+}
+
+impl ReqwestFetcher {
+    pub fn new(settings: &Settings) -> Result<ReqwestFetcher, Box<dyn Error>> {
+        // some services, like Nominatim, turn away requests that don't say who is making
+        // them.
+        let mut builder = reqwest::blocking::Client::builder()
+            .user_agent(concat!("weather-banner/", env!("CARGO_PKG_VERSION")))
+            .timeout(match settings.timeout {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            });
+        if let Some(proxy) = &settings.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        if let Some(path) = &settings.ca_cert {
+            let pem =
+                fs::read_to_string(path).map_err(|e| format!("unable to read {}: {}", path, e))?;
+            // a bundle is any number of certificates one after the other.
+            const END: &str = "-----END CERTIFICATE-----";
+            let certs = pem.split_inclusive(END).filter(|c| c.contains(END));
+            for cert in certs {
+                builder = builder
+                    .add_root_certificate(reqwest::Certificate::from_pem(cert.trim().as_bytes())?);
+            }
+        }
+        Ok(ReqwestFetcher {
+            client: builder.build()?,
+        })
+    }
+}
+
+impl Fetcher for ReqwestFetcher {
+    fn fetch(&self, url: &str, dst: &mut dyn io::Write) -> Result<(), Box<dyn Error>> {
+        // a mirror can be a local directory of files.
+        if let Some(path) = url.strip_prefix("file://") {
+            io::copy(
+                &mut fs::File::open(path).map_err(|e| format!("unable to open {}: {}", path, e))?,
+                dst,
+            )?;
+            return Ok(());
+        }
+        self.client
+            .get(url)
+            .send()?
+            .error_for_status()?
+            .copy_to(dst)?;
+        Ok(())
+    }
+
+    fn exists(&self, url: &str) -> Result<bool, Box<dyn Error>> {
+        if let Some(path) = url.strip_prefix("file://") {
+            return Ok(Path::new(path).exists());
+        }
+        let res = self.client.head(url).send()?;
+        match res.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            _ => {
+                res.error_for_status()?;
+                Ok(true)
+            }
+        }
+    }
+    fn modified(&self, url: &str) -> Result<Option<SystemTime>, Box<dyn Error>> {
+        if let Some(path) = url.strip_prefix("file://") {
+            return Ok(Some(fs::metadata(path)?.modified()?));
+        }
+        let res = self.client.head(url).send()?.error_for_status()?;
+        let modified = res
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok());
+        Ok(modified.map(SystemTime::from))
+    }
+}
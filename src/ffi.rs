@@ -0,0 +1,98 @@
+use super::{render, Data};
+use std::cell::RefCell;
+use std::error::Error;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic;
+
+// A C ABI for rendering banners from other languages, declared in include/weather_banner.h.
+// The library is built with:
+//
+//   cargo rustc --release --lib --crate-type cdylib --features cdylib
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(msg: String) {
+    // an error with a NUL in it is cut short rather than lost.
+    let msg = CString::new(msg).unwrap_or_else(|e| {
+        let n = e.nul_position();
+        CString::new(&e.into_vec()[..n]).unwrap_or_default()
+    });
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(msg));
+}
+
+/// Renders the year of a station in a CSV file (as read by `--source file`) to a PNG at
+/// out_path. Returns 0 on success and -1 on failure, with the reason in wb_last_error.
+///
+/// # Safety
+///
+/// station_csv_path and out_path must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn wb_render_png(
+    station_csv_path: *const c_char,
+    year: c_int,
+    width: c_int,
+    height: c_int,
+    out_path: *const c_char,
+) -> c_int {
+    if station_csv_path.is_null() || out_path.is_null() {
+        set_last_error(String::from("station_csv_path and out_path can't be NULL"));
+        return -1;
+    }
+    let (src, dst) = (
+        CStr::from_ptr(station_csv_path)
+            .to_string_lossy()
+            .into_owned(),
+        CStr::from_ptr(out_path).to_string_lossy().into_owned(),
+    );
+
+    // a panic must not unwind into the caller.
+    match panic::catch_unwind(|| render_png(&src, year, width, height, &dst)) {
+        Ok(Ok(())) => 0,
+        Ok(Err(e)) => {
+            set_last_error(e.to_string());
+            -1
+        }
+        Err(_) => {
+            set_last_error(String::from("the render panicked"));
+            -1
+        }
+    }
+}
+
+/// The reason the last call on this thread failed, or NULL if none has. The string belongs
+/// to the library and lasts until the next failure on the thread.
+#[no_mangle]
+pub extern "C" fn wb_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |msg| msg.as_ptr())
+    })
+}
+
+fn render_png(
+    src: &str,
+    year: c_int,
+    width: c_int,
+    height: c_int,
+    dst: &str,
+) -> Result<(), Box<dyn Error>> {
+    let args = render::Args::parse_from([
+        "--source",
+        "file",
+        "--input",
+        src,
+        "--year",
+        &year.to_string(),
+        "--width",
+        &width.to_string(),
+        "--height",
+        &height.to_string(),
+    ])?;
+    // the file has all of the data, so nothing is downloaded into the cache.
+    let png = render::render_png_bytes(&Data::from(std::env::temp_dir())?, &args)?;
+    std::fs::write(dst, png)?;
+    Ok(())
+}
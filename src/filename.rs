@@ -0,0 +1,102 @@
+// Names that Windows refuses to use for a file, regardless of extension.
+const RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// Longest name we will generate, in bytes, which leaves room below the common 255 byte
+// limit for anything a caller appends.
+const MAX_LEN: usize = 200;
+
+fn fold(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À'..='Å' => "A",
+        'à'..='å' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' => "C",
+        'ç' => "c",
+        'È'..='Ë' => "E",
+        'è'..='ë' => "e",
+        'Ì'..='Ï' => "I",
+        'ì'..='ï' => "i",
+        'Ð' => "D",
+        'ð' => "d",
+        'Ñ' => "N",
+        'ñ' => "n",
+        'Ò'..='Ö' | 'Ø' => "O",
+        'ò'..='ö' | 'ø' => "o",
+        'Ù'..='Ü' => "U",
+        'ù'..='ü' => "u",
+        'Ý' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Þ' => "TH",
+        'þ' => "th",
+        'ß' => "ss",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Š' => "S",
+        'š' => "s",
+        'Ž' => "Z",
+        'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// Turns an arbitrary string, like a station name, into a single file name that is safe to
+/// use on Linux, macOS and Windows. Accented letters are folded to ASCII, anything other
+/// than letters, digits, `.`, `_` and `-` becomes `_`, and names Windows reserves for
+/// devices are prefixed.
+///
+/// ```
+/// use weather_banner::filename::sanitize;
+///
+/// assert_eq!(sanitize("72309693727"), "72309693727");
+/// assert_eq!(sanitize("RALEIGH/DURHAM, NC US"), "RALEIGH_DURHAM_NC_US");
+/// assert_eq!(sanitize("Zürich Flughafen"), "Zurich_Flughafen");
+/// assert_eq!(sanitize("a:b*c?.png"), "a_b_c_.png");
+/// assert_eq!(sanitize("../.."), "_");
+/// assert_eq!(sanitize(""), "_");
+/// ```
+///
+/// Windows reserved names are matched without regard to case or extension.
+///
+/// ```
+/// use weather_banner::filename::sanitize;
+///
+/// assert_eq!(sanitize("CON"), "_CON");
+/// assert_eq!(sanitize("nul.png"), "_nul.png");
+/// assert_eq!(sanitize("Com1.tar.gz"), "_Com1.tar.gz");
+/// assert_eq!(sanitize("lpt9"), "_lpt9");
+/// assert_eq!(sanitize("CONSOLE.png"), "CONSOLE.png");
+/// assert_eq!(sanitize("COM10"), "COM10");
+/// ```
+pub fn sanitize(name: &str) -> String {
+    let mut s = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => s.push(c),
+            c => match fold(c) {
+                Some(f) => s.push_str(f),
+                None if !s.ends_with('_') => s.push('_'),
+                None => {}
+            },
+        }
+    }
+
+    // windows drops trailing dots and a name made only of dots refers to a directory.
+    let mut s = s
+        .trim_matches(|c| c == '_' || c == '.')
+        .chars()
+        .take(MAX_LEN)
+        .collect::<String>();
+    if s.is_empty() {
+        return String::from("_");
+    }
+
+    let stem = s.split('.').next().unwrap_or("");
+    if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        s.insert(0, '_');
+    }
+    s
+}
@@ -0,0 +1,195 @@
+use super::{filename, gsod, gsod::Location, isd, time, Data};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+// Finds where a place, given by name, is.
+pub trait Geocoder {
+    fn locate(&self, place: &str) -> Result<Location, Box<dyn Error>>;
+}
+
+// OpenStreetMap's Nominatim search. Answers are kept in the data directory, so a place is
+// only ever looked up once.
+pub struct Nominatim<'a> {
+    data: &'a Data,
+}
+
+impl Nominatim<'_> {
+    pub fn new(data: &Data) -> Nominatim<'_> {
+        Nominatim { data }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Place {
+    lat: String,
+    lon: String,
+}
+
+impl Geocoder for Nominatim<'_> {
+    fn locate(&self, place: &str) -> Result<Location, Box<dyn Error>> {
+        let url = reqwest::Url::parse_with_params(
+            "https://nominatim.openstreetmap.org/search",
+            &[("q", place), ("format", "json"), ("limit", "1")],
+        )?;
+        let places: Vec<Place> =
+            serde_json::from_reader(io::BufReader::new(self.data.download_and_open(
+                url.as_str(),
+                format!("geocode-{}.json", filename::sanitize(place)),
+            )?))?;
+        let place = places
+            .first()
+            .ok_or_else(|| format!("unable to find {}", place))?;
+        Ok(Location::new(place.lat.parse()?, place.lon.parse()?))
+    }
+}
+
+// A CSV file of places for looking them up offline, with a header row and columns of name,
+// lat and lng. Names match regardless of case and spacing, so "Portland, OR" finds a row
+// named "portland,or".
+pub struct Gazetteer {
+    places: HashMap<String, Location>,
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl Gazetteer {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Gazetteer, Box<dyn Error>> {
+        let mut r = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .from_path(path.as_ref())?;
+        let mut places = HashMap::new();
+        for record in r.records() {
+            let record = record?;
+            let field = |i: usize| record.get(i).ok_or("short gazetteer row");
+            places.insert(
+                normalize(field(0)?),
+                Location::new(field(1)?.trim().parse()?, field(2)?.trim().parse()?),
+            );
+        }
+        Ok(Gazetteer { places })
+    }
+}
+
+impl Geocoder for Gazetteer {
+    fn locate(&self, place: &str) -> Result<Location, Box<dyn Error>> {
+        self.places
+            .get(&normalize(place))
+            .cloned()
+            .ok_or_else(|| format!("{} isn't in the gazetteer", place).into())
+    }
+}
+
+// The great circle distance between two locations, in km.
+pub(crate) fn distance(a: &Location, b: &Location) -> f64 {
+    let (lat_a, lat_b) = (a.lat().to_radians(), b.lat().to_radians());
+    let dlat = lat_b - lat_a;
+    let dlng = (b.lng() - a.lng()).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (dlng / 2.0).sin().powi(2);
+    2.0 * 6371.0 * h.sqrt().asin()
+}
+
+// How many of the nearest stations are checked for complete data before giving up.
+const CANDIDATES: usize = 25;
+
+// The share of the days of the year a station needs to have reported on to be chosen.
+const COMPLETENESS: f64 = 0.9;
+
+// The number of days a station needs to have reported on in the year to count as complete.
+pub(crate) fn days_needed(year: i32) -> usize {
+    (time::Year::from_ordinal(year).days().count() as f64 * COMPLETENESS).ceil() as usize
+}
+
+// Finds the station nearest to the location that was open through the whole year and
+// reported on most of its days, printing the one it chose.
+pub fn nearest_station(
+    data: &Data,
+    loc: &Location,
+    year: i32,
+) -> Result<isd::StationInfo, Box<dyn Error>> {
+    let nearby = complete_stations_near(data, loc, year)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("no station near {} has complete data for {}", loc, year))?;
+    eprintln!(
+        "using station {} ({}), {:.0} km away, with {} days in {}",
+        nearby.info.id(),
+        nearby.info.name().unwrap_or("unnamed"),
+        nearby.km,
+        nearby.days,
+        year
+    );
+    Ok(nearby.info)
+}
+
+// A station near some location, how far away it is (in km) and how many days it has.
+pub(crate) struct Nearby {
+    pub(crate) km: f64,
+    pub(crate) info: isd::StationInfo,
+    pub(crate) days: usize,
+}
+
+// The stations nearest to the location that were open through the whole year and reported
+// on most of its days, nearest first.
+pub(crate) fn complete_stations_near(
+    data: &Data,
+    loc: &Location,
+    year: i32,
+) -> Result<Vec<Nearby>, Box<dyn Error>> {
+    let y = time::Year::from_ordinal(year);
+    let mut candidates = isd::history(data)?
+        .into_values()
+        .filter(|info| {
+            info.begin().is_some_and(|d| d <= y.start())
+                && info.end().is_some_and(|d| d >= y.end().pred_opt().unwrap())
+        })
+        .filter_map(|info| Some((distance(loc, info.location()?), info)))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    candidates.truncate(CANDIDATES);
+
+    let counts = count_days(
+        data,
+        year,
+        &candidates
+            .iter()
+            .map(|(_, info)| info.id())
+            .collect::<Vec<_>>(),
+    )?;
+    let needed = days_needed(year);
+    Ok(candidates
+        .into_iter()
+        .filter_map(|(km, info)| {
+            let days = *counts.get(info.id())?;
+            (days >= needed).then_some(Nearby { km, info, days })
+        })
+        .collect())
+}
+
+// Counts the rows each of the stations has in the year's archive without parsing them.
+fn count_days(
+    data: &Data,
+    year: i32,
+    ids: &[&str],
+) -> Result<HashMap<String, usize>, Box<dyn Error>> {
+    let mut counts = HashMap::new();
+    let mut archive = gsod::Archive::open(data, year)?;
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let id = match gsod::id_of(&entry)? {
+            Some(id) if ids.contains(&id.as_str()) => id,
+            _ => continue,
+        };
+        // every line but the header is a day.
+        let n = io::BufReader::new(entry).lines().count().saturating_sub(1);
+        counts.insert(id, n);
+    }
+    Ok(counts)
+}
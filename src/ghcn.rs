@@ -0,0 +1,211 @@
+use super::{
+    gsod::{Day, Indicators, Location, Observations, Station},
+    source::{self, DataSource},
+    time, Data,
+};
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead};
+
+// NOAA's Global Historical Climatology Network daily data, which has many more stations
+// and much longer histories than GSOD. Stations are GHCN ids (e.g. USW00013722). The
+// station's CSV is downloaded into the data directory, but a .dly file named ghcn-<id>.dly
+// that is already there, say from the bulk archive, is read instead.
+pub(crate) struct Ghcn<'a> {
+    data: &'a Data,
+}
+
+fn url_for(id: &str) -> String {
+    format!(
+        "https://www.ncei.noaa.gov/data/global-historical-climatology-network-daily/access/{}.csv",
+        id
+    )
+}
+
+// The values of each element reported on each day, in GHCN's units.
+type Elements = BTreeMap<NaiveDate, HashMap<String, f64>>;
+
+impl Ghcn<'_> {
+    pub(crate) fn new(data: &Data) -> Ghcn<'_> {
+        Ghcn { data }
+    }
+
+    fn history(&self, id: &str) -> Result<Station, Box<dyn Error>> {
+        let dly = self.data.path(format!("ghcn-{}.dly", id));
+        if dly.exists() {
+            let elements = read_dly(io::BufReader::new(fs::File::open(dly)?))?;
+            return Ok(Station::new(
+                String::from(id),
+                None,
+                None,
+                None,
+                days_from(&elements),
+            ));
+        }
+
+        let r = self
+            .data
+            .download_and_open(&url_for(id), format!("ghcn-{}.csv", id))?;
+        read_csv(id, r)
+    }
+}
+
+impl DataSource for Ghcn<'_> {
+    fn name(&self) -> &'static str {
+        "NOAA GHCN-Daily"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        Ok(Local::now().year() - 1)
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        source::year_of(&self.history(id)?, year)
+    }
+
+    fn stations(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        Ok(source::years_of(&self.history(id)?, years))
+    }
+}
+
+// Reads the fixed width .dly format: a line for each element in each month, with a value
+// and three flags for each of 31 days.
+fn read_dly<R: BufRead>(r: R) -> Result<Elements, Box<dyn Error>> {
+    let mut elements = Elements::new();
+    for line in r.lines() {
+        let line = line?;
+        let field = |s: usize, e: usize| line.get(s..e).ok_or("short .dly line");
+        let year = field(11, 15)?.parse::<i32>()?;
+        let month = field(15, 17)?.parse::<u32>()?;
+        let element = field(17, 21)?;
+        for d in 0..31 {
+            let s = 21 + d * 8;
+            let value = field(s, s + 5)?.trim().parse::<f64>()?;
+            let qflag = field(s + 6, s + 7)?;
+            let date = match NaiveDate::from_ymd_opt(year, month, d as u32 + 1) {
+                Some(date) => date,
+                None => continue,
+            };
+            if value != -9999.0 && qflag.trim().is_empty() {
+                elements
+                    .entry(date)
+                    .or_default()
+                    .insert(String::from(element), value);
+            }
+        }
+    }
+    Ok(elements)
+}
+
+// Reads the CSV format, which has a column for each element the station reports along with
+// a column of its flags.
+fn read_csv<R: io::Read>(id: &str, r: R) -> Result<Station, Box<dyn Error>> {
+    let mut r = csv::ReaderBuilder::new().has_headers(true).from_reader(r);
+    let headers = r.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let date_ix = column("DATE").ok_or("missing DATE column")?;
+    let columns = headers
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| {
+            !h.ends_with("_ATTRIBUTES")
+                && !matches!(
+                    *h,
+                    "STATION" | "DATE" | "LATITUDE" | "LONGITUDE" | "ELEVATION" | "NAME"
+                )
+        })
+        .map(|(i, h)| (i, h, column(&format!("{}_ATTRIBUTES", h))))
+        .collect::<Vec<_>>();
+
+    let mut elements = Elements::new();
+    let mut details = None;
+    for record in r.records() {
+        let record = record?;
+        let get = |ix: Option<usize>| ix.and_then(|ix| record.get(ix)).unwrap_or("").trim();
+        if details.is_none() {
+            let name = get(column("NAME"));
+            let (lat, lng) = (get(column("LATITUDE")), get(column("LONGITUDE")));
+            details = Some((
+                (!name.is_empty()).then(|| String::from(name)),
+                match (lat.parse::<f64>(), lng.parse::<f64>()) {
+                    (Ok(lat), Ok(lng)) => Some(Location::new(lat, lng)),
+                    _ => None,
+                },
+                get(column("ELEVATION")).parse::<f64>().ok(),
+            ));
+        }
+
+        let date = NaiveDate::parse_from_str(get(Some(date_ix)), "%Y-%m-%d")?;
+        for (ix, element, attrs) in &columns {
+            let value = get(Some(*ix));
+            // the second of the flags is the quality flag, which is empty for values that
+            // passed all of the checks.
+            let qflag = get(*attrs).split(',').nth(1).unwrap_or("").trim();
+            if value.is_empty() || !qflag.is_empty() {
+                continue;
+            }
+            elements
+                .entry(date)
+                .or_default()
+                .insert(String::from(*element), value.parse::<f64>()?);
+        }
+    }
+
+    let (name, loc, elevation) = details.unwrap_or((None, None, None));
+    Ok(Station::new(
+        String::from(id),
+        name,
+        loc,
+        elevation,
+        days_from(&elements),
+    ))
+}
+
+// Maps the elements of each day onto a GSOD day. Temperatures are in tenths of °C,
+// precipitation in tenths of mm, snow depth in mm and wind in tenths of m/s. Many stations
+// don't report a mean temperature, so it falls back to the midpoint of the extremes.
+fn days_from(elements: &Elements) -> Vec<Day> {
+    let fahrenheit = |t: f64| t / 10.0 * 9.0 / 5.0 + 32.0;
+    let knots = |s: f64| s / 10.0 * 1.943_844;
+    elements
+        .iter()
+        .map(|(date, vals)| {
+            let get = |element: &str| vals.get(element).copied();
+            let flag = |elements: &[&str]| elements.iter().any(|e| get(e).is_some_and(|v| v > 0.0));
+            let (max, min) = (get("TMAX"), get("TMIN"));
+            let mean = get("TAVG").or(match (max, min) {
+                (Some(max), Some(min)) => Some((max + min) / 2.0),
+                _ => None,
+            });
+            Day::new(
+                *date,
+                Observations {
+                    mean_temperature: mean.map(fahrenheit),
+                    max_temperature: max.map(fahrenheit),
+                    min_temperature: min.map(fahrenheit),
+                    mean_wind: get("AWND").map(knots),
+                    max_sustained_wind: get("WSF2").map(knots),
+                    max_wind_gust: get("WSFG").map(knots),
+                    precipitation: get("PRCP").map(|p| p / 10.0 / 25.4),
+                    snow_depth: get("SNWD").map(|d| d / 25.4),
+                    indicators: Indicators::from_flags([
+                        flag(&["WT01", "WT02"]),
+                        flag(&["WT16"]),
+                        flag(&["WT18"]) || get("SNOW").is_some_and(|s| s > 0.0),
+                        flag(&["WT05"]),
+                        flag(&["WT03"]),
+                        flag(&["WT10"]),
+                    ]),
+                    samples: 0,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
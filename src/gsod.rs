@@ -1,53 +1,279 @@
 use std::error::Error;
-use std::io;
+use std::io::{self, BufRead, Read};
 
+use chrono::Datelike;
 use csv::StringRecord;
+use flate2::read::GzDecoder;
 use serde::ser::SerializeTuple;
-use serde::Serialize;
-
-#[derive(Debug, Serialize)]
+use serde::{Deserialize, Serialize};
+
+// Stations serialize, as in repros, to
+//
+//   {"schema": 1, "id": "72309693727", "name": "...", "loc": [lat, lng], "elevation": meters,
+//    "days": [{"day": "2022-01-01", "mean_temperature": [°F, samples], ...}, ...]}
+//
+// with the values in GSOD's units (°F, millibars, knots, miles and inches). A mean is its
+// value and the number of observations behind it, an extremity is its value and "*" when GSOD
+// took it from the hourly readings, precipitation is its amount and its attribute letter,
+// and the indicators are GSOD's FRSHTT digits. Anything that changes this gets a new
+// SCHEMA_VERSION.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Station {
+    #[serde(default)]
+    schema: Schema,
     id: String,
     name: Option<String>,
     loc: Option<Location>,
     elevation: Option<Elevation>,
     days: Vec<Day>,
+    #[serde(skip)]
+    num_stray_days: usize,
 }
 
 impl Station {
     pub fn from_entry<R: io::Read>(entry: &mut tar::Entry<R>) -> Result<Station, Box<dyn Error>> {
-        let mut r = csv::ReaderBuilder::new()
+        Self::from_reader(entry)
+    }
+
+    // Parses a station from the CSV contents of a single entry of a GSOD archive.
+    pub fn from_reader<R: io::Read>(r: R) -> Result<Station, Box<dyn Error>> {
+        Ok(Self::parse(r, false)?.0)
+    }
+
+    // Parses a station like from_reader, but skips the rows that can't be parsed instead of
+    // failing on them and returns how many were skipped. An entry without a single good row
+    // still fails.
+    pub fn from_reader_lenient<R: io::Read>(r: R) -> Result<(Station, usize), Box<dyn Error>> {
+        Self::parse(r, true)
+    }
+
+    // Parses a station from one of the fixed-width files that GSOD was published as before
+    // the CSV archives, e.g. 723096-93727-1955.op. They don't have the station's name,
+    // location or elevation.
+    pub fn from_op_reader<R: io::Read>(r: R) -> Result<Station, Box<dyn Error>> {
+        let mut station = None;
+        for line in io::BufReader::new(r).lines() {
+            let line = line?;
+            // the first line is a header, much like the CSV's.
+            if line.starts_with("STN") || line.trim().is_empty() {
+                continue;
+            }
+            Self::add_record(&mut station, record_from_op(&line))?;
+        }
+        station.ok_or_else(|| "empty entry".into())
+    }
+
+    // Reads the station's own fields from the first row of an entry of a GSOD archive along
+    // with its days, which are parsed one row at a time as they are read, so the rest of the
+    // entry is left unread when the days aren't needed.
+    pub fn headers_from_entry<R: io::Read>(entry: R) -> Result<(Header, Days<R>), Box<dyn Error>> {
+        let (header, days, _) = Self::parse_header(entry, false)?;
+        Ok((header, days))
+    }
+
+    // Reads the station's own fields like headers_from_entry, but from the first row that can
+    // be read, and returns how many rows before it couldn't be.
+    pub fn headers_from_reader_lenient<R: io::Read>(
+        r: R,
+    ) -> Result<(Header, Days<R>, usize), Box<dyn Error>> {
+        Self::parse_header(r, true)
+    }
+
+    // a row counts as read when both its day and the station's fields can be parsed, as it
+    // would when reading the whole entry.
+    fn parse_header<R: io::Read>(
+        r: R,
+        lenient: bool,
+    ) -> Result<(Header, Days<R>, usize), Box<dyn Error>> {
+        let mut records = csv::ReaderBuilder::new()
             .has_headers(true)
-            .from_reader(entry);
-        let mut iter = r.records();
-        let mut days = Vec::new();
-        if let Some(record) = iter.next() {
-            let record = record?;
-            let id = from_record(&record, 0)?.to_owned();
-            let loc = parse_location(from_record(&record, 2)?, from_record(&record, 3)?)?;
-            let name = from_record(&record, 5)?;
-            let name = if name.is_empty() {
-                None
-            } else {
-                Some(name.to_owned())
-            };
-            let elevation = Elevation::from_gsod(from_record(&record, 4)?)?;
+            .from_reader(r)
+            .into_records();
+        let mut skipped = 0;
+        for record in records.by_ref() {
+            let header = record
+                .map_err(Box::from)
+                .and_then(|record| Ok((Day::from_record(&record)?, Header::from_record(&record)?)));
+            match header {
+                Ok((day, header)) => {
+                    let days = Days {
+                        first: Some(day),
+                        records,
+                    };
+                    return Ok((header, days, skipped));
+                }
+                Err(_) if lenient => skipped += 1,
+                Err(e) => return Err(e),
+            }
+        }
+        match skipped {
+            0 => Err("empty entry".into()),
+            n => Err(format!("none of the {} rows could be read", n).into()),
+        }
+    }
 
-            days.push(Day::from_record(&record)?);
-            for record in iter {
-                days.push(Day::from_record(&record?)?);
+    fn parse<R: io::Read>(r: R, lenient: bool) -> Result<(Station, usize), Box<dyn Error>> {
+        let mut r = csv::ReaderBuilder::new().has_headers(true).from_reader(r);
+        let mut station = None;
+        let mut skipped = 0;
+        for record in r.records() {
+            match Self::add_record(&mut station, record.map_err(Box::from)) {
+                Ok(()) => {}
+                Err(_) if lenient => skipped += 1,
+                Err(e) => return Err(e),
             }
+        }
+
+        match station {
+            Some(station) => Ok((station, skipped)),
+            None if skipped > 0 => {
+                Err(format!("none of the {} rows could be read", skipped).into())
+            }
+            None => Err("empty entry".into()),
+        }
+    }
+
+    // Adds the day of a row to the station. Every row repeats the station's own fields, which
+    // are taken from the first.
+    fn add_record(
+        station: &mut Option<Station>,
+        record: Result<StringRecord, Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let record = record?;
+        let day = Day::from_record(&record)?;
+        match station {
+            Some(station) => station.days.push(day),
+            None => {
+                let header = Header::from_record(&record)?;
+                *station = Some(Self {
+                    schema: Schema,
+                    id: header.id,
+                    name: header.name,
+                    loc: header.loc,
+                    elevation: header.elevation,
+                    days: vec![day],
+                    num_stray_days: 0,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Builds a station from data that came from somewhere other than a GSOD archive.
+    pub(crate) fn new(
+        id: String,
+        name: Option<String>,
+        loc: Option<Location>,
+        elevation: Option<f64>,
+        days: Vec<Day>,
+    ) -> Station {
+        Station {
+            schema: Schema,
+            id,
+            name,
+            loc,
+            elevation: elevation.map(Elevation::new),
+            days,
+            num_stray_days: 0,
+        }
+    }
 
-            return Ok(Self {
-                id,
-                name,
-                loc,
-                elevation,
-                days,
-            });
+    // A copy of the station with only the given days.
+    #[cfg(feature = "native")]
+    pub(crate) fn with_days(&self, days: Vec<Day>) -> Station {
+        Station {
+            schema: Schema,
+            id: self.id.clone(),
+            name: self.name.clone(),
+            loc: self.loc.clone(),
+            elevation: self.elevation.clone(),
+            days,
+            num_stray_days: 0,
         }
+    }
 
-        Err("empty entry".into())
+    // A station made up of the average of the stations on each day that any of them
+    // reported on. Each value is averaged over the stations that have it and a day's weather
+    // is what any of them saw. The means are weighted by the observations behind them, so a
+    // station that only reported a few times that day counts for less. The station sits at
+    // the middle of the others.
+    #[cfg(feature = "native")]
+    pub(crate) fn average(id: String, name: String, stations: &[Station]) -> Station {
+        let mean = |vals: &mut dyn Iterator<Item = f64>| {
+            let (sum, n) = vals.fold((0.0, 0), |(sum, n), v| (sum + v, n + 1));
+            (n > 0).then(|| sum / n as f64)
+        };
+        // sources that don't count the observations have none, and get the plain mean.
+        let weighted = |vals: &mut dyn Iterator<Item = (f64, i32)>| {
+            let vals = vals.map(|(v, n)| (v, n.max(0) as f64)).collect::<Vec<_>>();
+            let total = vals.iter().map(|(_, n)| n).sum::<f64>();
+            match total > 0.0 {
+                true => Some(vals.iter().map(|(v, n)| v * n).sum::<f64>() / total),
+                false => mean(&mut vals.iter().map(|(v, _)| *v)),
+            }
+        };
+
+        let mut by_date = std::collections::BTreeMap::<_, Vec<&Day>>::new();
+        for day in stations.iter().flat_map(|s| s.days()) {
+            by_date.entry(day.date()).or_default().push(day);
+        }
+        let days = by_date
+            .into_iter()
+            .map(|(date, days)| {
+                let avg = |f: fn(&Day) -> Option<f64>| mean(&mut days.iter().filter_map(|d| f(d)));
+                let wavg = |f: fn(&Day) -> Option<(f64, i32)>| {
+                    weighted(&mut days.iter().filter_map(|d| f(d)))
+                };
+                let mut flags = [false; 6];
+                for day in &days {
+                    for (flag, set) in flags.iter_mut().zip(day.indicators().flags) {
+                        *flag |= set;
+                    }
+                }
+                Day::new(
+                    date,
+                    Observations {
+                        mean_temperature: wavg(|d| {
+                            d.mean_temperature()
+                                .map(|t| (t.in_fahrenheit(), t.samples()))
+                        }),
+                        max_temperature: avg(|d| d.max_temperature().map(|t| t.in_fahrenheit())),
+                        min_temperature: avg(|d| d.min_temperature().map(|t| t.in_fahrenheit())),
+                        mean_dewpoint: wavg(|d| {
+                            d.mean_dewpoint().map(|t| (t.in_fahrenheit(), t.samples()))
+                        }),
+                        mean_sea_level_pressure: wavg(|d| {
+                            d.mean_sea_level_pressure()
+                                .map(|p| (p.in_millibars(), p.samples()))
+                        }),
+                        mean_wind: wavg(|d| d.mean_wind().map(|s| (s.in_knots(), s.samples()))),
+                        max_sustained_wind: avg(|d| d.max_sustained_wind().map(|s| s.in_knots())),
+                        max_wind_gust: avg(|d| d.max_wind_gust().map(|s| s.in_knots())),
+                        precipitation: avg(|d| d.precipitation().map(|p| p.in_inches())),
+                        snow_depth: avg(|d| d.snow_depth().map(|s| s.in_inches())),
+                        indicators: Indicators::from_flags(flags),
+                        samples: days
+                            .iter()
+                            .filter_map(|d| d.mean_temperature().map(|t| t.samples()))
+                            .sum(),
+                    },
+                )
+            })
+            .collect();
+
+        let locs = stations
+            .iter()
+            .filter_map(|s| s.location())
+            .collect::<Vec<_>>();
+        let loc = mean(&mut locs.iter().map(|l| l.lat()))
+            .zip(mean(&mut locs.iter().map(|l| l.lng())))
+            .map(|(lat, lng)| Location::new(lat, lng));
+        let elevation = mean(
+            &mut stations
+                .iter()
+                .filter_map(|s| s.elevation().map(|e| e.in_meters())),
+        );
+        Station::new(id, Some(name), loc, elevation, days)
     }
 
     pub fn id(&self) -> &str {
@@ -69,6 +295,137 @@ impl Station {
     pub fn days(&self) -> &[Day] {
         &self.days
     }
+
+    pub fn header(&self) -> Header {
+        Header {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            loc: self.loc.clone(),
+            elevation: self.elevation.clone(),
+        }
+    }
+
+    // Removes the days that fall outside of the given year, which archives occasionally
+    // contain, and returns how many were removed.
+    pub fn retain_year(&mut self, year: i32) -> usize {
+        let n = self.days.len();
+        self.days.retain(|day| day.date().year() == year);
+        let removed = n - self.days.len();
+        self.num_stray_days += removed;
+        removed
+    }
+
+    pub fn num_stray_days(&self) -> usize {
+        self.num_stray_days
+    }
+}
+
+// The days of an entry, from the row its station's fields were read from on, parsed as
+// they are read.
+pub struct Days<R> {
+    first: Option<Day>,
+    records: csv::StringRecordsIntoIter<R>,
+}
+
+impl<R: io::Read> Iterator for Days<R> {
+    type Item = Result<Day, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(day) = self.first.take() {
+            return Some(Ok(day));
+        }
+        self.records.next().map(|record| Day::from_record(&record?))
+    }
+}
+
+// A station's own fields, which GSOD repeats on every row of its entry. They serialize like
+// those of a station.
+#[derive(Debug, Clone, Serialize)]
+pub struct Header {
+    id: String,
+    name: Option<String>,
+    loc: Option<Location>,
+    elevation: Option<Elevation>,
+}
+
+impl Header {
+    fn from_record(rec: &StringRecord) -> Result<Header, Box<dyn Error>> {
+        let name = from_record(rec, 5)?;
+        Ok(Header {
+            id: from_record(rec, 0)?.to_owned(),
+            name: (!name.is_empty()).then(|| name.to_owned()),
+            loc: parse_location(from_record(rec, 2)?, from_record(rec, 3)?)?,
+            elevation: Elevation::from_gsod(from_record(rec, 4)?)?,
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.loc.as_ref()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn elevation(&self) -> Option<&Elevation> {
+        self.elevation.as_ref()
+    }
+}
+
+// Where the values of a row of an .op file are, as 1-based columns of the line, in the order
+// of the CSV archives' columns from TEMP to FRSHTT.
+const OP_COLUMNS: [(usize, usize); 22] = [
+    (25, 30),   // TEMP
+    (32, 33),   // its count
+    (36, 41),   // DEWP
+    (43, 44),   // its count
+    (47, 52),   // SLP
+    (54, 55),   // its count
+    (58, 63),   // STP
+    (65, 66),   // its count
+    (69, 73),   // VISIB
+    (75, 76),   // its count
+    (79, 83),   // WDSP
+    (85, 86),   // its count
+    (89, 93),   // MXSPD
+    (96, 100),  // GUST
+    (103, 108), // MAX
+    (109, 109), // * when it came from the hourly readings
+    (111, 116), // MIN
+    (117, 117), // * when it came from the hourly readings
+    (119, 123), // PRCP
+    (124, 124), // its attribute
+    (126, 130), // SNDP
+    (133, 138), // FRSHTT
+];
+
+// Rearranges a line of an .op file into a row of the CSV archives, which have the same values
+// and the same markers of missing ones. The station's own fields are left empty but for its
+// id, the STN and WBAN columns run together.
+fn record_from_op(line: &str) -> Result<StringRecord, Box<dyn Error>> {
+    let field = |from: usize, to: usize| {
+        line.get(from - 1..to)
+            .map(str::trim)
+            .ok_or_else(|| format!("line is too short: {}", line))
+    };
+    let date = field(15, 22)?;
+    if date.len() != 8 {
+        return Err(format!("invalid date: {}", date).into());
+    }
+    let mut record = StringRecord::new();
+    record.push_field(&format!("{}{}", field(1, 6)?, field(8, 12)?));
+    record.push_field(&format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]));
+    for _ in 2..6 {
+        record.push_field("");
+    }
+    for (from, to) in OP_COLUMNS {
+        record.push_field(field(from, to)?);
+    }
+    Ok(record)
 }
 
 fn from_record(rec: &StringRecord, ix: usize) -> Result<&str, Box<dyn Error>> {
@@ -87,7 +444,40 @@ fn parse_location(lat: &str, lng: &str) -> Result<Option<Location>, Box<dyn Erro
     )))
 }
 
-#[derive(Debug, Serialize)]
+// The version of the serialized form of stations. Stations serialized before there was a
+// version are the same as version 1.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// Writes the schema of a station as the current version and makes sure that the version of
+// one being read isn't newer than that.
+#[derive(Debug, Clone, Copy, Default)]
+struct Schema;
+
+impl serde::ser::Serialize for Schema {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_u32(SCHEMA_VERSION)
+    }
+}
+
+impl<'de> serde::de::Deserialize<'de> for Schema {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match u32::deserialize(d)? {
+            1..=SCHEMA_VERSION => Ok(Schema),
+            v => Err(serde::de::Error::custom(format!(
+                "station schema {} isn't one this version reads (1 to {})",
+                v, SCHEMA_VERSION
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Day {
     day: chrono::NaiveDate,
     mean_temperature: Option<MeanTemperature>,
@@ -102,9 +492,63 @@ pub struct Day {
     min_temperature: Option<TemperatureExtremity>,
     precipitation: Option<Precipitation>,
     snow_depth: Option<SnowDepth>,
+    #[serde(default)]
+    indicators: Indicators,
+}
+
+// The measurements of a day from a source other than GSOD, already in the units that GSOD
+// uses (°F, millibars, knots and inches). The means are taken to be over the given number of samples.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Observations {
+    pub(crate) mean_temperature: Option<f64>,
+    pub(crate) max_temperature: Option<f64>,
+    pub(crate) min_temperature: Option<f64>,
+    pub(crate) mean_dewpoint: Option<f64>,
+    pub(crate) mean_sea_level_pressure: Option<f64>,
+    pub(crate) mean_wind: Option<f64>,
+    pub(crate) max_sustained_wind: Option<f64>,
+    pub(crate) max_wind_gust: Option<f64>,
+    pub(crate) precipitation: Option<f64>,
+    pub(crate) snow_depth: Option<f64>,
+    pub(crate) indicators: Indicators,
+    pub(crate) samples: i32,
 }
 
 impl Day {
+    pub(crate) fn new(day: chrono::NaiveDate, obs: Observations) -> Day {
+        let mean = |t: Option<f64>| {
+            t.map(|t| MeanTemperature::new(Temperature::from_fahrenheit(t), obs.samples))
+        };
+        let extremity = |t: Option<f64>| {
+            t.map(|t| {
+                TemperatureExtremity::new(
+                    Temperature::from_fahrenheit(t),
+                    DeterminedVia::ExplicitReading,
+                )
+            })
+        };
+        Day {
+            day,
+            mean_temperature: mean(obs.mean_temperature),
+            mean_dewpoint: mean(obs.mean_dewpoint),
+            mean_sea_level_pressure: obs
+                .mean_sea_level_pressure
+                .map(|p| MeanPressure::new(Pressure::from_millibars(p), obs.samples)),
+            mean_station_pressure: None,
+            mean_visibility: None,
+            mean_wind: obs
+                .mean_wind
+                .map(|s| MeanWindSpeed::new(WindSpeed::from_knots(s), obs.samples)),
+            max_sustained_wind: obs.max_sustained_wind.map(WindSpeed::from_knots),
+            max_wind_gust: obs.max_wind_gust.map(WindSpeed::from_knots),
+            max_temperature: extremity(obs.max_temperature),
+            min_temperature: extremity(obs.min_temperature),
+            precipitation: obs.precipitation.map(|p| Precipitation { p, attr: None }),
+            snow_depth: obs.snow_depth.map(|d| SnowDepth { d }),
+            indicators: obs.indicators,
+        }
+    }
+
     fn from_record(rec: &StringRecord) -> Result<Day, Box<dyn Error>> {
         let day = chrono::NaiveDate::parse_from_str(from_record(rec, 1)?, "%Y-%m-%d")?;
         let mean_temperature =
@@ -125,6 +569,7 @@ impl Day {
             TemperatureExtremity::from_gsod(from_record(rec, 22)?, from_record(rec, 23)?)?;
         let precipitation = Precipitation::from_gsod(from_record(rec, 24)?, from_record(rec, 25)?)?;
         let snow_depth = SnowDepth::from_gsod(from_record(rec, 26)?)?;
+        let indicators = Indicators::from_gsod(from_record(rec, 27)?)?;
         Ok(Self {
             day,
             mean_temperature,
@@ -139,6 +584,7 @@ impl Day {
             min_temperature,
             precipitation,
             snow_depth,
+            indicators,
         })
     }
 
@@ -158,6 +604,22 @@ impl Day {
         self.mean_temperature.as_ref()
     }
 
+    pub fn mean_dewpoint(&self) -> Option<&MeanTemperature> {
+        self.mean_dewpoint.as_ref()
+    }
+
+    pub fn mean_sea_level_pressure(&self) -> Option<&MeanPressure> {
+        self.mean_sea_level_pressure.as_ref()
+    }
+
+    pub fn mean_station_pressure(&self) -> Option<&MeanPressure> {
+        self.mean_station_pressure.as_ref()
+    }
+
+    pub fn mean_visibility(&self) -> Option<&MeanDistance> {
+        self.mean_visibility.as_ref()
+    }
+
     pub fn mean_wind(&self) -> Option<&MeanWindSpeed> {
         self.mean_wind.as_ref()
     }
@@ -166,9 +628,21 @@ impl Day {
         self.max_sustained_wind.as_ref()
     }
 
+    pub fn max_wind_gust(&self) -> Option<&WindSpeed> {
+        self.max_wind_gust.as_ref()
+    }
+
     pub fn precipitation(&self) -> Option<&Precipitation> {
         self.precipitation.as_ref()
     }
+
+    pub fn snow_depth(&self) -> Option<&SnowDepth> {
+        self.snow_depth.as_ref()
+    }
+
+    pub fn indicators(&self) -> Indicators {
+        self.indicators
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -225,7 +699,19 @@ impl serde::ser::Serialize for PrecipitationAttr {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for PrecipitationAttr {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let c = char::deserialize(d)?;
+        PrecipitationAttr::from_gsod(&c.to_string())
+            .map_err(serde::de::Error::custom)?
+            .ok_or_else(|| serde::de::Error::custom("empty precipitation attr"))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Precipitation {
     p: f64,
     attr: Option<PrecipitationAttr>,
@@ -265,7 +751,17 @@ impl serde::ser::Serialize for Precipitation {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for Precipitation {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (p, attr) = <(f64, Option<PrecipitationAttr>)>::deserialize(d)?;
+        Ok(Precipitation { p, attr })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SnowDepth {
     d: f64,
 }
@@ -294,7 +790,98 @@ impl serde::ser::Serialize for SnowDepth {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for SnowDepth {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(SnowDepth {
+            d: f64::deserialize(d)?,
+        })
+    }
+}
+
+// The weather that was reported at some point during a day, from the FRSHTT field. A flag
+// that is not set means either that it did not happen or that it was not reported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Indicators {
+    flags: [bool; 6],
+}
+
+impl Indicators {
+    // the flags in the order of FRSHTT: fog, rain, snow, hail, thunder and tornado.
+    #[cfg(feature = "native")]
+    pub(crate) fn from_flags(flags: [bool; 6]) -> Indicators {
+        Indicators { flags }
+    }
+
+    fn from_gsod(s: &str) -> Result<Indicators, Box<dyn Error>> {
+        let s = s.trim();
+        let mut flags = [false; 6];
+        if s.len() != flags.len() {
+            return Err(format!("invalid indicators: {}", s).into());
+        }
+        for (flag, c) in flags.iter_mut().zip(s.chars()) {
+            *flag = match c {
+                '0' => false,
+                '1' => true,
+                _ => return Err(format!("invalid indicators: {}", s).into()),
+            };
+        }
+        Ok(Indicators { flags })
+    }
+
+    fn to_gsod(self) -> String {
+        self.flags
+            .iter()
+            .map(|flag| if *flag { '1' } else { '0' })
+            .collect()
+    }
+
+    pub fn fog(&self) -> bool {
+        self.flags[0]
+    }
+
+    pub fn rain(&self) -> bool {
+        self.flags[1]
+    }
+
+    pub fn snow(&self) -> bool {
+        self.flags[2]
+    }
+
+    pub fn hail(&self) -> bool {
+        self.flags[3]
+    }
+
+    pub fn thunder(&self) -> bool {
+        self.flags[4]
+    }
+
+    pub fn tornado(&self) -> bool {
+        self.flags[5]
+    }
+}
+
+impl serde::ser::Serialize for Indicators {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_str(&self.to_gsod())
+    }
+}
+
+impl<'de> serde::de::Deserialize<'de> for Indicators {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Indicators::from_gsod(&String::deserialize(d)?).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeterminedVia {
     ExplicitReading,
     DerivedFromHourly,
@@ -309,7 +896,7 @@ impl DeterminedVia {
         }
     }
 
-    fn to_str(&self) -> &'static str {
+    fn to_str(self) -> &'static str {
         match self {
             DeterminedVia::ExplicitReading => "",
             DeterminedVia::DerivedFromHourly => "*",
@@ -326,7 +913,16 @@ impl serde::ser::Serialize for DeterminedVia {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for DeterminedVia {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        DeterminedVia::from_gsod(&String::deserialize(d)?).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct TemperatureExtremity {
     t: Temperature,
     d: DeterminedVia,
@@ -358,6 +954,11 @@ impl TemperatureExtremity {
     pub fn in_celsius(&self) -> f64 {
         self.t.in_celsius()
     }
+
+    // whether the station reported the extremity or GSOD took it from the hourly readings.
+    pub fn determined_via(&self) -> DeterminedVia {
+        self.d
+    }
 }
 
 impl serde::ser::Serialize for TemperatureExtremity {
@@ -372,7 +973,17 @@ impl serde::ser::Serialize for TemperatureExtremity {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for TemperatureExtremity {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (t, v) = <(Temperature, DeterminedVia)>::deserialize(d)?;
+        Ok(TemperatureExtremity::new(t, v))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct MeanWindSpeed {
     s: WindSpeed,
     n: i32,
@@ -390,9 +1001,17 @@ impl MeanWindSpeed {
         }
     }
 
+    pub fn speed(&self) -> WindSpeed {
+        self.s
+    }
+
     pub fn in_knots(&self) -> f64 {
         self.s.in_knots()
     }
+
+    pub fn samples(&self) -> i32 {
+        self.n
+    }
 }
 
 impl serde::ser::Serialize for MeanWindSpeed {
@@ -407,13 +1026,23 @@ impl serde::ser::Serialize for MeanWindSpeed {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for MeanWindSpeed {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (s, n) = <(WindSpeed, i32)>::deserialize(d)?;
+        Ok(MeanWindSpeed::new(s, n))
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
 pub struct WindSpeed {
     s: f64,
 }
 
 impl WindSpeed {
-    fn from_knots(s: f64) -> WindSpeed {
+    pub(crate) fn from_knots(s: f64) -> WindSpeed {
         WindSpeed { s }
     }
 
@@ -438,7 +1067,16 @@ impl serde::ser::Serialize for WindSpeed {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for WindSpeed {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(WindSpeed::from_knots(f64::deserialize(d)?))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct MeanDistance {
     d: Distance,
     n: i32,
@@ -455,6 +1093,18 @@ impl MeanDistance {
             None => Ok(None),
         }
     }
+
+    pub fn distance(&self) -> Distance {
+        self.d
+    }
+
+    pub fn in_miles(&self) -> f64 {
+        self.d.in_miles()
+    }
+
+    pub fn samples(&self) -> i32 {
+        self.n
+    }
 }
 
 impl serde::ser::Serialize for MeanDistance {
@@ -469,7 +1119,17 @@ impl serde::ser::Serialize for MeanDistance {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for MeanDistance {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (v, n) = <(Distance, i32)>::deserialize(d)?;
+        Ok(MeanDistance::new(v, n))
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
 pub struct Distance {
     m: f64,
 }
@@ -500,13 +1160,22 @@ impl serde::ser::Serialize for Distance {
     }
 }
 
+impl<'de> serde::de::Deserialize<'de> for Distance {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Distance::from_miles(f64::deserialize(d)?))
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Pressure {
     p: f64,
 }
 
 impl Pressure {
-    fn from_millibars(p: f64) -> Self {
+    pub(crate) fn from_millibars(p: f64) -> Self {
         Self { p }
     }
 
@@ -531,7 +1200,16 @@ impl serde::ser::Serialize for Pressure {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for Pressure {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Pressure::from_millibars(f64::deserialize(d)?))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct MeanPressure {
     p: Pressure,
     n: i32,
@@ -548,6 +1226,18 @@ impl MeanPressure {
             None => Ok(None),
         }
     }
+
+    pub fn pressure(&self) -> Pressure {
+        self.p
+    }
+
+    pub fn in_millibars(&self) -> f64 {
+        self.p.in_millibars()
+    }
+
+    pub fn samples(&self) -> i32 {
+        self.n
+    }
 }
 
 impl serde::ser::Serialize for MeanPressure {
@@ -562,13 +1252,23 @@ impl serde::ser::Serialize for MeanPressure {
     }
 }
 
+impl<'de> serde::de::Deserialize<'de> for MeanPressure {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (p, n) = <(Pressure, i32)>::deserialize(d)?;
+        Ok(MeanPressure::new(p, n))
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Temperature {
     f: f64,
 }
 
 impl Temperature {
-    fn from_fahrenheit(f: f64) -> Self {
+    pub(crate) fn from_fahrenheit(f: f64) -> Self {
         Self { f }
     }
 
@@ -597,7 +1297,16 @@ impl serde::ser::Serialize for Temperature {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for Temperature {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Temperature::from_fahrenheit(f64::deserialize(d)?))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct MeanTemperature {
     t: Temperature,
     n: i32,
@@ -645,7 +1354,17 @@ impl serde::ser::Serialize for MeanTemperature {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for MeanTemperature {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (t, n) = <(Temperature, i32)>::deserialize(d)?;
+        Ok(MeanTemperature::new(t, n))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Elevation {
     m: f64,
 }
@@ -676,7 +1395,16 @@ impl serde::ser::Serialize for Elevation {
     }
 }
 
-#[derive(Debug)]
+impl<'de> serde::de::Deserialize<'de> for Elevation {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Elevation::new(f64::deserialize(d)?))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Location {
     lat: f64,
     lng: f64,
@@ -763,6 +1491,16 @@ impl serde::ser::Serialize for Location {
     }
 }
 
+impl<'de> serde::de::Deserialize<'de> for Location {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (lat, lng) = <(f64, f64)>::deserialize(d)?;
+        Ok(Location::new(lat, lng))
+    }
+}
+
 fn to_dms(v: f64) -> (i32, i32, i32) {
     let v = v.abs();
 
@@ -789,9 +1527,164 @@ fn to_dms(v: f64) -> (i32, i32, i32) {
     (d, m, s)
 }
 
+// A year of GSOD as NOAA publishes it: a gzipped tarball with a CSV entry for each station,
+// named by the station's id. The archive is read as it is inflated, so it can only be gone
+// through once.
+pub struct Archive<R: io::Read> {
+    tar: tar::Archive<GzDecoder<R>>,
+}
+
+impl<R: io::Read> Archive<R> {
+    pub fn new(r: R) -> Archive<R> {
+        Archive {
+            tar: tar::Archive::new(GzDecoder::new(r)),
+        }
+    }
+
+    // The entries as they are, for reading them some other way than parsing every station.
+    pub fn entries(&mut self) -> Result<tar::Entries<'_, GzDecoder<R>>, Box<dyn Error>> {
+        Ok(self.tar.entries()?)
+    }
+
+    // Parses each of the stations as the archive gets to it.
+    pub fn stations(
+        &mut self,
+    ) -> Result<impl Iterator<Item = Result<Station, Box<dyn Error>>> + '_, Box<dyn Error>> {
+        Ok(self
+            .entries()?
+            .map(|entry| Station::from_entry(&mut entry?)))
+    }
+
+    // Finds a station by the name of its entry, without parsing any of the others.
+    pub fn find_by_id(&mut self, id: &str) -> Result<Option<Station>, Box<dyn Error>> {
+        for entry in self.entries()? {
+            let mut entry = entry?;
+            if id_of(&entry)?.as_deref() == Some(id) {
+                return Ok(Some(Station::from_entry(&mut entry)?));
+            }
+        }
+        Ok(None)
+    }
+
+    // Finds the stations whose own fields satisfy the predicate, which only needs the first
+    // row of each of the others parsed.
+    pub fn find_where<F>(&mut self, mut f: F) -> Result<Vec<Station>, Box<dyn Error>>
+    where
+        F: FnMut(&Header) -> bool,
+    {
+        let mut stations = Vec::new();
+        for entry in self.entries()? {
+            let mut contents = Vec::new();
+            entry?.read_to_end(&mut contents)?;
+            if f(&Station::parse_header(&contents[..], false)?.0) {
+                stations.push(Station::from_reader(&contents[..])?);
+            }
+        }
+        Ok(stations)
+    }
+}
+
+#[cfg(feature = "native")]
+impl Archive<std::fs::File> {
+    // The year's archive in the cache, which is downloaded first when it isn't there.
+    pub fn open(data: &super::Data, year: i32) -> Result<Self, Box<dyn Error>> {
+        Ok(Archive::new(data.download_and_open(
+            &url_for(year),
+            format!("{}.tar.gz", year),
+        )?))
+    }
+}
+
+// The id of the station of an entry, from its name, e.g. 72309693727.csv.
+pub fn id_of<R: io::Read>(entry: &tar::Entry<R>) -> Result<Option<String>, Box<dyn Error>> {
+    Ok(entry
+        .path()?
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(String::from))
+}
+
+// The year's .op files, one gzipped file for each station in a plain tarball.
+pub fn legacy_url_for(year: i32) -> String {
+    format!(
+        "https://www.ncei.noaa.gov/pub/data/gsod/{}/gsod_{}.tar",
+        year, year
+    )
+}
+
 pub fn url_for(year: i32) -> String {
     format!(
         "https://www.ncei.noaa.gov/data/global-summary-of-the-day/archive/{}.tar.gz",
         year
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY: &str = concat!(
+    "\"STATION\",\"DATE\",\"LATITUDE\",\"LONGITUDE\",\"ELEVATION\",\"NAME\",\"TEMP\",\"TEMP_ATTRIBUTES\",\"DEWP\",\"DEWP_ATTRIBUTES\",\"SLP\",\"SLP_ATTRIBUTES\",\"STP\",\"STP_ATTRIBUTES\",\"VISIB\",\"VISIB_ATTRIBUTES\",\"WDSP\",\"WDSP_ATTRIBUTES\",\"MXSPD\",\"GUST\",\"MAX\",\"MAX_ATTRIBUTES\",\"MIN\",\"MIN_ATTRIBUTES\",\"PRCP\",\"PRCP_ATTRIBUTES\",\"SNDP\",\"FRSHTT\"\n",
+    "\"72309693727\",\"2022-01-01\",\"35.8922\",\"-78.7819\",\"126.5\",\"RALEIGH DURHAM INTERNATIONAL AIRPORT, NC US\",\"36.5\",\" 24\",\"26.5\",\" 24\",\"1015.2\",\" 24\",\"999.9\",\" 0\",\"10.0\",\" 24\",\"5.6\",\" 24\",\"13.8\",\"999.9\",\"46.5\",\"*\",\"26.5\",\" \",\"0.00\",\"G\",\"999.9\",\"100000\"\n",
+    "\"72309693727\",\"2022-01-02\",\"35.8922\",\"-78.7819\",\"126.5\",\"RALEIGH DURHAM INTERNATIONAL AIRPORT, NC US\",\"27.8\",\" 24\",\"17.8\",\" 24\",\"1015.2\",\" 24\",\"999.9\",\" 0\",\"10.0\",\" 24\",\"7.5\",\" 24\",\"6.5\",\"999.9\",\"37.8\",\"*\",\"17.8\",\" \",\"0.00\",\"G\",\"999.9\",\"000000\"\n",
+    "\"72309693727\",\"2022-01-03\",\"35.8922\",\"-78.7819\",\"126.5\",\"RALEIGH DURHAM INTERNATIONAL AIRPORT, NC US\",\"41.9\",\" 24\",\"31.9\",\" 24\",\"1015.2\",\" 24\",\"999.9\",\" 0\",\"10.0\",\" 24\",\"4.3\",\" 24\",\"11.2\",\"999.9\",\"51.9\",\"*\",\"31.9\",\" \",\"0.00\",\"G\",\"999.9\",\"000000\"\n",
+    );
+
+    #[test]
+    fn headers_come_with_the_days_of_the_same_reader() {
+        let (header, days) = Station::headers_from_entry(ENTRY.as_bytes()).unwrap();
+        assert_eq!(header.id, "72309693727");
+        let dates = days
+            .map(|day| day.map(|day| day.date().to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(dates, ["2022-01-01", "2022-01-02", "2022-01-03"]);
+    }
+
+    #[test]
+    fn lenient_headers_skip_to_the_first_good_row() {
+        let entry = ENTRY.replacen("\"2022-01-01\"", "\"garbage\"", 1);
+        let (header, days, skipped) =
+            Station::headers_from_reader_lenient(entry.as_bytes()).unwrap();
+        assert_eq!(header.id, "72309693727");
+        assert_eq!(skipped, 1);
+        assert_eq!(days.count(), 2);
+    }
+
+    #[test]
+    fn stations_read_back_what_they_serialize() {
+        let station = Station::from_reader(ENTRY.as_bytes()).unwrap();
+        let json = serde_json::to_value(&station).unwrap();
+        assert_eq!(json["schema"], SCHEMA_VERSION);
+
+        let back: Station = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(back.id(), station.id());
+        assert_eq!(back.days().len(), 3);
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+    }
+
+    #[test]
+    fn stations_from_before_the_schema_read_as_the_first_version() {
+        let mut json =
+            serde_json::to_value(Station::from_reader(ENTRY.as_bytes()).unwrap()).unwrap();
+        json.as_object_mut().unwrap().remove("schema");
+        let station: Station = serde_json::from_value(json).unwrap();
+        assert_eq!(station.days().len(), 3);
+    }
+
+    #[test]
+    fn stations_from_a_newer_schema_are_rejected() {
+        let mut json =
+            serde_json::to_value(Station::from_reader(ENTRY.as_bytes()).unwrap()).unwrap();
+        json["schema"] = serde_json::json!(SCHEMA_VERSION + 1);
+        let err = serde_json::from_value::<Station>(json).unwrap_err();
+        assert!(
+            err.to_string().starts_with(&format!(
+                "station schema {} isn't one this version reads",
+                SCHEMA_VERSION + 1
+            )),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use super::{
+    chart, check_unit, gsod, locale, locale::Label, render, time, Color, Font, Gradient, Range,
+    Unit,
+};
+use cairo::{Context, FontSlant, FontWeight};
+use chrono::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric {
+    Temperature,
+    Precipitation,
+}
+
+impl Metric {
+    pub(crate) fn value_of(self, day: &gsod::Day) -> Option<f64> {
+        match self {
+            Metric::Temperature => day.mean_temperature().map(|t| t.in_fahrenheit()),
+            Metric::Precipitation => day.precipitation().map(|p| p.in_inches()),
+        }
+    }
+
+    pub(crate) fn title(self) -> Label {
+        match self {
+            Metric::Temperature => Label::MeanTemperature,
+            Metric::Precipitation => Label::Precipitation,
+        }
+    }
+
+    pub(crate) fn units(self) -> &'static str {
+        match self {
+            Metric::Temperature => "°F",
+            Metric::Precipitation => " in",
+        }
+    }
+
+    pub(crate) fn gradient(self) -> Gradient {
+        match self {
+            Metric::Temperature => Gradient::new(vec![
+                Color::from_u32(0x2c7bb6),
+                Color::from_u32(0xabd9e9),
+                Color::from_u32(0xffffbf),
+                Color::from_u32(0xfdae61),
+                Color::from_u32(0xd7191c),
+            ]),
+            Metric::Precipitation => Gradient::new(vec![
+                Color::from_u32(0xc7f0f0),
+                Color::from_u32(0x2fcbcc),
+                Color::from_u32(0x1b5f8c),
+            ]),
+        }
+    }
+
+    pub(crate) fn range_of<I>(self, vals: I) -> Range
+    where
+        I: Iterator<Item = f64>,
+    {
+        let (min, max) = vals.fold((f64::MAX, f64::MIN), |(min, max), v| {
+            (min.min(v), max.max(v))
+        });
+        match self {
+            Metric::Temperature => Range::new(min, max),
+            Metric::Precipitation => Range::new(0.0, max),
+        }
+    }
+}
+
+pub(crate) fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    year: time::Year,
+    station: &gsod::Station,
+    metric: Metric,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let header_height = render::render_header(
+        ctx,
+        station,
+        &render::describe_year(year, &opts.locale),
+        width,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let mut idx = HashMap::new();
+    for day in station.days() {
+        if let Some(v) = metric.value_of(day) {
+            idx.insert(day.date().ordinal(), v);
+        }
+    }
+
+    if idx.is_empty() {
+        return Err(format!(
+            "no {} data for station: {}",
+            locale::Locale::default().label(metric.title()),
+            station.id()
+        )
+        .into());
+    }
+
+    let range = metric.range_of(idx.values().copied());
+    let gradient = metric.gradient();
+    let empty = opts.theme.foreground.with_alpha(0.05);
+
+    // the grid is laid out like a contributions calendar: one column per week,
+    // one row per weekday starting with sunday.
+    let offset = year.start().weekday().num_days_from_sunday() as usize;
+    let num_weeks = (offset + year.duration().num_days() as usize).div_ceil(7);
+
+    let margin = 20.0;
+    let label_width = 30.0;
+    let label_height = 40.0;
+    let legend_height = 40.0;
+    let body_height = height - header_height;
+    let cell = ((width - 2.0 * margin - label_width) / num_weeks as f64)
+        .min((body_height - 2.0 * margin - label_height - legend_height) / 7.0);
+    let gap = cell * 0.15;
+
+    let grid_width = cell * num_weeks as f64;
+    let grid_height = cell * 7.0;
+    let x0 = (width - grid_width + label_width) / 2.0;
+    let y0 = header_height + (body_height - grid_height - legend_height + label_height) / 2.0;
+
+    if opts.debug {
+        ctx.save()?;
+        Color::from_u32_with_alpha(0xff9900, 0.1).set(ctx);
+        ctx.new_path();
+        ctx.rectangle(x0, y0, grid_width, grid_height);
+        ctx.fill()?;
+        ctx.restore()?;
+    }
+
+    render::render_title(
+        ctx,
+        opts.locale.label(metric.title()),
+        width / 2.0,
+        y0 - label_height + 10.0,
+        opts,
+    )?;
+
+    ctx.save()?;
+    for day in year.days() {
+        let i = day.date().ordinal0() as usize + offset;
+        let x = x0 + (i / 7) as f64 * cell;
+        let y = y0 + (i % 7) as f64 * cell;
+        match idx.get(&day.ordinal()) {
+            Some(v) if *v > 0.0 || matches!(metric, Metric::Temperature) => {
+                gradient.at(check_unit(range.normalize(*v))).set(ctx)
+            }
+            _ => empty.set(ctx),
+        }
+        ctx.new_path();
+        ctx.rectangle(x + gap / 2.0, y + gap / 2.0, cell - gap, cell - gap);
+        ctx.fill()?;
+    }
+    ctx.restore()?;
+
+    let label_font = Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 10.0);
+
+    ctx.save()?;
+    label_font.set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    for month in year.months() {
+        let i = month.start().ordinal0() as usize + offset;
+        let name = opts.locale.month_abbr(month.start());
+        ctx.new_path();
+        ctx.move_to(x0 + (i / 7) as f64 * cell + gap / 2.0, y0 - 6.0);
+        ctx.show_text(&name)?;
+    }
+
+    for (row, day) in [(1, Weekday::Mon), (3, Weekday::Wed), (5, Weekday::Fri)] {
+        let name = opts.locale.weekday_abbr(day);
+        let exts = ctx.text_extents(&name)?;
+        ctx.new_path();
+        ctx.move_to(
+            x0 - exts.x_advance() - 6.0,
+            y0 + row as f64 * cell + cell / 2.0 + exts.height() / 2.0,
+        );
+        ctx.show_text(&name)?;
+    }
+    ctx.restore()?;
+
+    ctx.save()?;
+    render_legend(
+        ctx,
+        &gradient,
+        &range,
+        metric.units(),
+        &label_font,
+        x0 + grid_width,
+        y0 + grid_height + 12.0,
+        cell,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_legend(
+    ctx: &Context,
+    gradient: &Gradient,
+    range: &Range,
+    units: &str,
+    font: &Font,
+    rx: f64,
+    y: f64,
+    cell: f64,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let n = 5;
+    let gap = cell * 0.15;
+
+    font.set(ctx);
+    let max_label = format!("{}{}", opts.locale.number(range.max(), 1), units);
+    let min_label = format!("{}{}", opts.locale.number(range.min(), 1), units);
+    let max_exts = ctx.text_extents(&max_label)?;
+    let min_exts = ctx.text_extents(&min_label)?;
+
+    let x = rx - max_exts.x_advance() - 6.0 - n as f64 * cell;
+    let ty = y + cell / 2.0 + max_exts.height() / 2.0;
+
+    for i in 0..n {
+        gradient.at(Unit::new(i as f64 / (n - 1) as f64)).set(ctx);
+        ctx.new_path();
+        ctx.rectangle(
+            x + i as f64 * cell + gap / 2.0,
+            y + gap / 2.0,
+            cell - gap,
+            cell - gap,
+        );
+        ctx.fill()?;
+    }
+
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.new_path();
+    ctx.move_to(x - min_exts.x_advance() - 6.0, ty);
+    ctx.show_text(&min_label)?;
+
+    ctx.new_path();
+    ctx.move_to(x + n as f64 * cell + 6.0, ty);
+    ctx.show_text(&max_label)?;
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use super::{
+    chart, gsod::Station, heatmap, locale::Label, percentile_of, render, time, Color, Font, Range,
+    Scale,
+};
+use cairo::{Context, FontSlant, FontWeight};
+use std::error::Error;
+
+// Renders how the days of the year are distributed over the values of the metric, as a bar
+// for each bin of the scale's step, with the median and the mode, the middle of the fullest
+// bin, called out.
+pub(crate) fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    year: time::Year,
+    station: &Station,
+    metric: heatmap::Metric,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let header_height = render::render_header(
+        ctx,
+        station,
+        &render::describe_year(year, &opts.locale),
+        width,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let mut vals = station
+        .days()
+        .iter()
+        .filter_map(|day| metric.value_of(day))
+        .collect::<Vec<_>>();
+    if vals.is_empty() {
+        return Err(format!("no data for station: {}", station.id()).into());
+    }
+    vals.sort_by(|a, b| a.total_cmp(b));
+    let median = percentile_of(&vals, 0.5);
+
+    let pinned = match metric {
+        heatmap::Metric::Temperature => &opts.temperature_range,
+        heatmap::Metric::Precipitation => &opts.precipitation_range,
+    };
+    let range = pinned
+        .clone()
+        .unwrap_or_else(|| metric.range_of(vals.iter().copied()));
+    let bin = Scale::from_range(&range, 30.0)?.step();
+    let first = (range.min() / bin).floor() * bin;
+    let n = (((range.max() - first) / bin).floor() as usize + 1).max(1);
+    let mut counts = vec![0usize; n];
+    for v in vals.iter().filter(|v| **v >= first) {
+        counts[(((v - first) / bin) as usize).min(n - 1)] += 1;
+    }
+    let (fullest, most) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, c)| **c)
+        .map(|(i, c)| (i, *c))
+        .unwrap_or_default();
+    let mode = first + (fullest as f64 + 0.5) * bin;
+
+    let margin = 40.0;
+    let title_height = 30.0;
+    let label_height = 30.0;
+    let (x0, x1) = (margin + 20.0, width - margin);
+    let (y0, y1) = (
+        header_height + title_height + margin,
+        height - margin - label_height,
+    );
+    let xrange = Range::new(first, first + n as f64 * bin);
+    let x_of = |v: f64| x0 + xrange.normalize_clamped(v).value() * (x1 - x0);
+    let y_of = |c: f64| y1 - c / most.max(1) as f64 * (y1 - y0);
+
+    render::render_title(
+        ctx,
+        opts.locale.label(metric.title()),
+        width / 2.0,
+        header_height + title_height,
+        opts,
+    )?;
+
+    let label_font = Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 10.0);
+
+    // the counts of days, as faint lines behind the bars.
+    ctx.save()?;
+    label_font.set(ctx);
+    let counts_scale = Scale::from_range(&Range::new(0.0, most.max(1) as f64), 5.0)?;
+    for (i, c) in counts_scale.steps().iter().enumerate() {
+        opts.theme.foreground.with_alpha(0.1).set(ctx);
+        ctx.new_path();
+        ctx.move_to(x0, y_of(*c));
+        ctx.line_to(x1, y_of(*c));
+        ctx.stroke()?;
+        let label = counts_scale.label_for(i);
+        let exts = ctx.text_extents(&label)?;
+        opts.theme.foreground.with_alpha(0.6).set(ctx);
+        ctx.move_to(x0 - exts.x_advance() - 6.0, y_of(*c) + exts.height() / 2.0);
+        ctx.show_text(&label)?;
+    }
+    ctx.restore()?;
+
+    ctx.save()?;
+    let gradient = metric.gradient();
+    let dx = (x1 - x0) / n as f64;
+    for (i, c) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+        gradient
+            .at(range.normalize_clamped(first + (i as f64 + 0.5) * bin))
+            .set(ctx);
+        ctx.new_path();
+        ctx.rectangle(
+            x0 + i as f64 * dx + 1.0,
+            y_of(*c as f64),
+            dx - 2.0,
+            y1 - y_of(*c as f64),
+        );
+        ctx.fill()?;
+    }
+    ctx.restore()?;
+
+    // the values along the bottom.
+    ctx.save()?;
+    label_font.set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    let scale = Scale::from_range(&xrange, 10.0)?;
+    for (i, v) in scale.steps().iter().enumerate() {
+        let label = format!("{}{}", scale.label_for(i), metric.units());
+        let exts = ctx.text_extents(&label)?;
+        ctx.new_path();
+        ctx.move_to(x_of(*v), y1);
+        ctx.line_to(x_of(*v), y1 + 4.0);
+        ctx.stroke()?;
+        ctx.move_to(x_of(*v) - exts.x_advance() / 2.0, y1 + 8.0 + exts.height());
+        ctx.show_text(&label)?;
+    }
+    ctx.restore()?;
+
+    let annotate = |label: Label, v: f64, y: f64| -> Result<(), Box<dyn Error>> {
+        let text = format!(
+            "{} {}{}",
+            opts.locale.label(label),
+            opts.locale.number(v, 1),
+            metric.units()
+        );
+        let exts = ctx.text_extents(&text)?;
+        ctx.move_to(x_of(v) + 4.0, y - exts.height() / 2.0);
+        ctx.show_text(&text)?;
+        Ok(())
+    };
+
+    ctx.save()?;
+    Font::new(
+        "HelveticaNeue-Medium",
+        FontSlant::Normal,
+        FontWeight::Bold,
+        11.0,
+    )
+    .set(ctx);
+    opts.theme.foreground.set(ctx);
+    ctx.set_dash(&[3.0, 2.0], 0.0);
+    ctx.new_path();
+    ctx.move_to(x_of(median), y0 - 24.0);
+    ctx.line_to(x_of(median), y1);
+    ctx.stroke()?;
+    annotate(Label::Median, median, y0 - 16.0)?;
+    // the fullest bin reaches the top of the plot.
+    annotate(Label::Mode, mode, y0)?;
+    ctx.restore()?;
+
+    if opts.debug {
+        ctx.save()?;
+        Color::from_u32(0xff9900).set(ctx);
+        ctx.set_line_width(1.0);
+        ctx.new_path();
+        ctx.rectangle(x0, y0, x1 - x0, y1 - y0);
+        ctx.stroke()?;
+        ctx.restore()?;
+    }
+
+    Ok(())
+}
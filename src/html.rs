@@ -0,0 +1,83 @@
+use super::Color;
+use std::error::Error;
+
+const STYLE: &str = r#"
+body { margin: 0; padding: 20px; font-family: sans-serif; }
+svg { display: block; max-width: 100%; height: auto; }
+.hotspots path.active { fill-opacity: 0.25; }
+button { margin: 12px 0; padding: 4px 10px; }
+table { border-collapse: collapse; font-size: 12px; }
+th, td { padding: 2px 12px 2px 0; text-align: left; }
+tr.active { background: rgba(255, 255, 255, 0.15); }
+"#;
+
+// Highlights a day in every panel while any of them is hovered and builds the table of
+// values from the hotspots the first time it is shown.
+const SCRIPT: &str = r#"
+const hotspots = Array.from(document.querySelectorAll(".hotspots path"));
+const table = document.getElementById("data");
+let rows = {};
+
+function highlight(date) {
+  for (const p of hotspots) p.classList.toggle("active", p.dataset.date === date);
+  for (const [d, tr] of Object.entries(rows)) tr.classList.toggle("active", d === date);
+}
+
+for (const p of hotspots) {
+  p.addEventListener("mouseenter", () => highlight(p.dataset.date));
+  p.addEventListener("mouseleave", () => highlight(null));
+}
+
+function build() {
+  const panels = [...new Set(hotspots.map((p) => p.dataset.panel))];
+  const head = table.createTHead().insertRow();
+  for (const name of ["date", ...panels]) {
+    const th = document.createElement("th");
+    th.textContent = name;
+    head.appendChild(th);
+  }
+  const body = table.createTBody();
+  for (const p of hotspots) {
+    let tr = rows[p.dataset.date];
+    if (!tr) {
+      tr = rows[p.dataset.date] = body.insertRow();
+      tr.insertCell().textContent = p.dataset.date;
+      for (const _ of panels) tr.insertCell();
+      tr.addEventListener("mouseenter", () => highlight(tr.cells[0].textContent));
+      tr.addEventListener("mouseleave", () => highlight(null));
+    }
+    tr.cells[panels.indexOf(p.dataset.panel) + 1].textContent = p.dataset.values;
+  }
+}
+
+document.getElementById("toggle").addEventListener("click", () => {
+  if (!table.tHead) build();
+  table.hidden = !table.hidden;
+});
+"#;
+
+// A single page with the SVG inlined, so it can be published as is.
+pub(crate) fn page(
+    title: &str,
+    svg: &[u8],
+    background: &Color,
+    foreground: &Color,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let svg = std::str::from_utf8(svg)?;
+    let svg = &svg[svg.find("<svg").ok_or("not an svg")?..];
+    let title = title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    Ok(format!(
+        concat!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+            "<title>{}</title>\n<style>body {{ background: {}; color: {}; }}{}</style>\n",
+            "</head>\n<body>\n{}",
+            "<button id=\"toggle\">Data</button>\n<table id=\"data\" hidden></table>\n",
+            "<script>{}</script>\n</body>\n</html>\n"
+        ),
+        title, background, foreground, STYLE, svg, SCRIPT
+    )
+    .into_bytes())
+}
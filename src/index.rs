@@ -0,0 +1,185 @@
+use super::{gsod, gsod::Station, Data};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Seek, Write};
+use std::path::Path;
+
+// NOAA's archives are a single gzip stream, so finding a station means inflating everything
+// in front of it. The first lookup in a year copies the archive into one where every station
+// is a gzip member of its own and records where each member starts as it scans for its own
+// station, so that the lookups after it seek straight to the station they want.
+//
+//   {year}.stations.gz   the stations' CSV files, one gzip member each
+//   {year}.index.csv     id,offset,length of each member
+//
+// The index is rebuilt whenever the archive is newer than it, as with a current year's archive
+// that was downloaded again. Years that are in the store don't need either.
+pub(crate) fn find_station(
+    data: &Data,
+    year: i32,
+    station_id: &str,
+) -> Result<Option<Station>, Box<dyn Error>> {
+    #[cfg(feature = "store")]
+    if let Some(store) = super::store::Store::existing(data)? {
+        if store.has_year(year)? {
+            return store.station(year, station_id);
+        }
+    }
+
+    let archive = format!("{}.tar.gz", year);
+    let (members, index) = (
+        data.path(format!("{}.stations.gz", year)),
+        data.path(format!("{}.index.csv", year)),
+    );
+    let f = data.download_and_open(&gsod::url_for(year), &archive)?;
+    if !is_fresh(&index, &data.path(&archive))? {
+        return build(f, &members, &index, station_id);
+    }
+
+    let (offset, length) = match read_index(&index)?.remove(station_id) {
+        Some(member) => member,
+        None => return Ok(None),
+    };
+    let mut f = fs::File::open(&members)?;
+    f.seek(io::SeekFrom::Start(offset))?;
+    Ok(Some(Station::from_reader(GzDecoder::new(f.take(length)))?))
+}
+
+fn is_fresh(index: &Path, archive: &Path) -> Result<bool, Box<dyn Error>> {
+    if !index.exists() {
+        return Ok(false);
+    }
+    Ok(fs::metadata(index)?.modified()? >= fs::metadata(archive)?.modified()?)
+}
+
+// Copies the archive into its members and index, parsing the wanted station on the way.
+fn build<R: Read>(
+    archive: R,
+    members: &Path,
+    index: &Path,
+    station_id: &str,
+) -> Result<Option<Station>, Box<dyn Error>> {
+    let mut archive = gsod::Archive::new(archive);
+
+    // both files are written next to their destinations and moved into place once complete,
+    // the index last, so that an interrupted build is never mistaken for a finished one.
+    let (members_tmp, index_tmp) = (members.with_extension("part"), index.with_extension("part"));
+    let mut dst = io::BufWriter::new(fs::File::create(&members_tmp)?);
+    let mut w = csv::Writer::from_path(&index_tmp)?;
+    let mut offset = 0;
+    let mut station = None;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let id = match gsod::id_of(&entry)? {
+            Some(id) => id,
+            None => continue,
+        };
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        if id == station_id {
+            station = Some(Station::from_reader(&contents[..])?);
+        }
+        let mut gz = GzEncoder::new(Vec::new(), Compression::fast());
+        gz.write_all(&contents)?;
+        let member = gz.finish()?;
+        dst.write_all(&member)?;
+        w.write_record([id, offset.to_string(), member.len().to_string()])?;
+        offset += member.len() as u64;
+    }
+    dst.flush()?;
+    w.flush()?;
+    fs::rename(&members_tmp, members)?;
+    fs::rename(&index_tmp, index)?;
+    Ok(station)
+}
+
+fn read_index(index: &Path) -> Result<HashMap<String, (u64, u64)>, Box<dyn Error>> {
+    let mut r = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(index)?;
+    let mut members = HashMap::new();
+    for record in r.records() {
+        let record = record?;
+        let (id, offset, length) = match (record.get(0), record.get(1), record.get(2)) {
+            (Some(id), Some(offset), Some(length)) => (id, offset, length),
+            _ => return Err(format!("invalid index: {}", index.display()).into()),
+        };
+        members.insert(String::from(id), (offset.parse()?, length.parse()?));
+    }
+    Ok(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = concat!(
+        "\"STATION\",\"DATE\",\"LATITUDE\",\"LONGITUDE\",\"ELEVATION\",\"NAME\",\"TEMP\",",
+        "\"TEMP_ATTRIBUTES\",\"DEWP\",\"DEWP_ATTRIBUTES\",\"SLP\",\"SLP_ATTRIBUTES\",\"STP\",",
+        "\"STP_ATTRIBUTES\",\"VISIB\",\"VISIB_ATTRIBUTES\",\"WDSP\",\"WDSP_ATTRIBUTES\",\"MXSPD\",",
+        "\"GUST\",\"MAX\",\"MAX_ATTRIBUTES\",\"MIN\",\"MIN_ATTRIBUTES\",\"PRCP\",",
+        "\"PRCP_ATTRIBUTES\",\"SNDP\",\"FRSHTT\"\n",
+    );
+
+    fn entry(id: &str, days: u32) -> String {
+        let mut csv = String::from(HEADER);
+        for day in 1..=days {
+            csv.push_str(&format!(
+                concat!(
+                    "\"{}\",\"2022-01-{:02}\",\"35.8922\",\"-78.7819\",\"126.5\",\"STATION {}\",",
+                    "\"36.5\",\" 24\",\"26.5\",\" 24\",\"1015.2\",\" 24\",\"999.9\",\" 0\",\"10.0\",",
+                    "\" 24\",\"5.6\",\" 24\",\"13.8\",\"999.9\",\"46.5\",\"*\",\"26.5\",\" \",\"0.00\",",
+                    "\"G\",\"999.9\",\"100000\"\n"
+                ),
+                id, day, id
+            ));
+        }
+        csv
+    }
+
+    fn archive(entries: &[(&str, String)]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        for (id, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, format!("{}.csv", id), contents.as_bytes())
+                .unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn members_read_back_through_the_index() {
+        let stations = [("11111111111", 3), ("22222222222", 5), ("33333333333", 1)];
+        let entries = stations
+            .iter()
+            .map(|(id, days)| (*id, entry(id, *days)))
+            .collect::<Vec<_>>();
+        let dir = std::env::temp_dir().join(format!("weather-banner-index-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (members, index) = (dir.join("2022.stations.gz"), dir.join("2022.index.csv"));
+
+        let found = build(&archive(&entries)[..], &members, &index, "22222222222").unwrap();
+        assert_eq!(found.map(|s| s.days().len()), Some(5));
+
+        let offsets = read_index(&index).unwrap();
+        assert_eq!(offsets.len(), stations.len());
+        for (id, contents) in &entries {
+            let (offset, length) = offsets[*id];
+            let mut f = fs::File::open(&members).unwrap();
+            f.seek(io::SeekFrom::Start(offset)).unwrap();
+            let mut read = String::new();
+            GzDecoder::new(f.take(length))
+                .read_to_string(&mut read)
+                .unwrap();
+            assert_eq!(&read, contents);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
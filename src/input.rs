@@ -0,0 +1,138 @@
+use super::gsod::{Day, Observations, Station};
+#[cfg(feature = "native")]
+use super::{
+    source::{self, DataSource},
+    time,
+};
+#[cfg(feature = "native")]
+use chrono::Datelike;
+use chrono::NaiveDate;
+use std::error::Error;
+use std::io;
+#[cfg(feature = "native")]
+use std::path::Path;
+
+// Days read from a CSV file of the user's own, like the export of a personal weather
+// station. The file has a header row naming its columns, which can be in any order:
+//
+//   date    the day, as YYYY-MM-DD
+//   tmin    the minimum temperature, in °F
+//   tmax    the maximum temperature, in °F
+//   tmean   the mean temperature, in °F
+//   wind    the mean wind speed, in knots
+//   precip  the total precipitation, in inches
+//
+// Only date is required and any value can be left empty. When there is no mean temperature,
+// the midpoint of the extremes is used. Other columns are ignored. The file holds a single
+// station, so the station ids given for a render don't matter and the station is named
+// after the file.
+#[cfg(feature = "native")]
+pub(crate) struct Input<'a> {
+    path: &'a str,
+}
+
+#[cfg(feature = "native")]
+impl Input<'_> {
+    pub(crate) fn new(path: &str) -> Input<'_> {
+        Input { path }
+    }
+
+    fn history(&self) -> Result<Station, Box<dyn Error>> {
+        let f = std::fs::File::open(self.path)
+            .map_err(|e| format!("unable to read {}: {}", self.path, e))?;
+        let name = Path::new(self.path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from(self.path));
+        read(f, self.path, name)
+    }
+}
+
+// Reads the days of a station from CSV in the format above. The origin is where the CSV came
+// from, for errors.
+pub(crate) fn read<R: io::Read>(
+    r: R,
+    origin: &str,
+    name: String,
+) -> Result<Station, Box<dyn Error>> {
+    let mut r = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .from_reader(r);
+    let headers = r.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let date_ix = column("date").ok_or_else(|| format!("{} has no date column", origin))?;
+    let (tmin, tmax, tmean, wind, precip) = (
+        column("tmin"),
+        column("tmax"),
+        column("tmean"),
+        column("wind"),
+        column("precip"),
+    );
+
+    let mut days = Vec::new();
+    for (i, record) in r.records().enumerate() {
+        let record = record?;
+        // the header is line 1.
+        let line = i + 2;
+        let field = |ix: Option<usize>| -> Result<Option<f64>, Box<dyn Error>> {
+            match ix.and_then(|ix| record.get(ix)) {
+                None | Some("") => Ok(None),
+                Some(v) => Ok(Some(v.parse::<f64>().map_err(|e| {
+                    format!("{}:{}: invalid value {:?}: {}", origin, line, v, e)
+                })?)),
+            }
+        };
+        let date = record.get(date_ix).unwrap_or("");
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| format!("{}:{}: invalid date {:?}: {}", origin, line, date, e))?;
+        let (min, max) = (field(tmin)?, field(tmax)?);
+        let mean = field(tmean)?.or(match (min, max) {
+            (Some(min), Some(max)) => Some((min + max) / 2.0),
+            _ => None,
+        });
+        let wind = field(wind)?;
+        days.push(Day::new(
+            date,
+            Observations {
+                mean_temperature: mean,
+                max_temperature: max,
+                min_temperature: min,
+                mean_wind: wind,
+                // there is only the one wind speed, so it stands in for the maximum too.
+                max_sustained_wind: wind,
+                precipitation: field(precip)?,
+                ..Default::default()
+            },
+        ));
+    }
+    days.sort_by_key(|day| day.date());
+    Ok(Station::new(name.clone(), Some(name), None, None, days))
+}
+
+#[cfg(feature = "native")]
+impl DataSource for Input<'_> {
+    fn name(&self) -> &'static str {
+        "Local CSV"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        self.history()?
+            .days()
+            .last()
+            .map(|day| day.date().year())
+            .ok_or_else(|| format!("{} has no days", self.path).into())
+    }
+
+    fn station(&self, year: time::Year, _: &str) -> Result<Station, Box<dyn Error>> {
+        source::year_of(&self.history()?, year)
+    }
+
+    fn stations(
+        &self,
+        years: time::Years,
+        _: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        Ok(source::years_of(&self.history()?, years))
+    }
+}
@@ -0,0 +1,367 @@
+use super::{
+    gsod::{Location, Pressure, Temperature, WindSpeed},
+    Data,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::io::{self, BufRead};
+
+// An hour of the NOAA Integrated Surface Database, from its ISD-Lite extract. These are the
+// observations the GSOD days are summarized from, so they carry what a daily summary can't,
+// like the direction of the wind and how the temperature moved through the day. Times are
+// in UTC.
+pub struct Hour {
+    time: NaiveDateTime,
+    temperature: Option<Temperature>,
+    dewpoint: Option<Temperature>,
+    sea_level_pressure: Option<Pressure>,
+    wind_direction: Option<f64>,
+    wind_speed: Option<WindSpeed>,
+}
+
+impl Hour {
+    pub fn time(&self) -> NaiveDateTime {
+        self.time
+    }
+
+    pub fn temperature(&self) -> Option<&Temperature> {
+        self.temperature.as_ref()
+    }
+
+    pub fn dewpoint(&self) -> Option<&Temperature> {
+        self.dewpoint.as_ref()
+    }
+
+    pub fn sea_level_pressure(&self) -> Option<&Pressure> {
+        self.sea_level_pressure.as_ref()
+    }
+
+    // the direction the wind blows from, in degrees clockwise from north. calm hours have
+    // no direction.
+    pub fn wind_direction(&self) -> Option<f64> {
+        self.wind_direction
+    }
+
+    pub fn wind_speed(&self) -> Option<&WindSpeed> {
+        self.wind_speed.as_ref()
+    }
+
+    // Parses a line of ISD-Lite: the year, month, day and hour, then the temperature and
+    // dewpoint (tenths of °C), sea level pressure (tenths of hPa), wind direction (degrees)
+    // and wind speed (tenths of m/s), sky cover and precipitation. -9999 is missing.
+    fn from_isd_lite(line: &str) -> Result<Hour, Box<dyn Error>> {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        if fields.len() < 9 {
+            return Err(format!("short ISD-Lite line: {:?}", line).into());
+        }
+        let int = |i: usize| fields[i].parse::<i64>();
+        let value = |i: usize| -> Result<Option<f64>, Box<dyn Error>> {
+            match int(i)? {
+                -9999 => Ok(None),
+                v => Ok(Some(v as f64)),
+            }
+        };
+
+        let hour = int(3)? as u32;
+        let time = NaiveDate::from_ymd_opt(int(0)? as i32, int(1)? as u32, int(2)? as u32)
+            .and_then(|date| date.and_hms_opt(hour, 0, 0))
+            .ok_or_else(|| format!("invalid time in ISD-Lite line: {:?}", line))?;
+        // the values are only given to a tenth, so the converted ones are rounded rather
+        // than pretending to more precision.
+        let round = |v: f64| (v * 100.0).round() / 100.0;
+        let fahrenheit = |t: f64| Temperature::from_fahrenheit(round(t / 10.0 * 9.0 / 5.0 + 32.0));
+        let wind_speed = value(8)?.map(|s| WindSpeed::from_knots(round(s / 10.0 * 1.943_844)));
+        let calm = wind_speed.as_ref().is_some_and(|s| s.in_knots() == 0.0);
+        Ok(Hour {
+            time,
+            temperature: value(4)?.map(fahrenheit),
+            dewpoint: value(5)?.map(fahrenheit),
+            sea_level_pressure: value(6)?.map(|p| Pressure::from_millibars(p / 10.0)),
+            // calm winds are given a direction of 0, which would otherwise read as north.
+            wind_direction: value(7)?.filter(|_| !calm),
+            wind_speed,
+        })
+    }
+}
+
+// ISD files are named by the USAF and WBAN ids that make up a GSOD station id.
+pub fn url_for(year: i32, station_id: &str) -> Result<String, Box<dyn Error>> {
+    if station_id.len() != 11 {
+        return Err(format!("expected an 11 digit station id: {}", station_id).into());
+    }
+    let (usaf, wban) = station_id.split_at(6);
+    Ok(format!(
+        "https://www.ncei.noaa.gov/pub/data/noaa/isd-lite/{}/{}-{}-{}.gz",
+        year, usaf, wban, year
+    ))
+}
+
+pub fn read<R: io::Read>(r: R) -> Result<Vec<Hour>, Box<dyn Error>> {
+    io::BufReader::new(GzDecoder::new(r))
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|l| l.trim().is_empty()))
+        .map(|line| Hour::from_isd_lite(&line?))
+        .collect()
+}
+
+// Loads the hours of a station in a year, downloading them into the data directory.
+pub fn hours_for_year(
+    data: &Data,
+    year: i32,
+    station_id: &str,
+) -> Result<Vec<Hour>, Box<dyn Error>> {
+    read(data.download_and_open(
+        &url_for(year, station_id)?,
+        format!("isd-lite-{}-{}.gz", station_id, year),
+    )?)
+}
+
+// The difference between the warmest and coolest hour of each day, in °F. Days are UTC days,
+// which span a full day and night anywhere, and need at least 18 hours with a temperature
+// so that gaps don't shrink the range.
+pub fn diurnal_ranges(hours: &[Hour]) -> BTreeMap<NaiveDate, f64> {
+    let mut temps: BTreeMap<NaiveDate, Vec<f64>> = BTreeMap::new();
+    for hour in hours {
+        if let Some(t) = hour.temperature() {
+            temps
+                .entry(hour.time().date())
+                .or_default()
+                .push(t.in_fahrenheit());
+        }
+    }
+    temps
+        .into_iter()
+        .filter(|(_, temps)| temps.len() >= 18)
+        .map(|(date, temps)| {
+            let max = temps.iter().copied().fold(f64::MIN, f64::max);
+            let min = temps.iter().copied().fold(f64::MAX, f64::min);
+            (date, max - min)
+        })
+        .collect()
+}
+
+// The upper bounds, in knots, of the speeds a wind rose is split by. Anything faster falls
+// in one more class past the last.
+pub const SPEED_CLASSES: [f64; 4] = [5.0, 10.0, 15.0, 20.0];
+
+// How often the wind blew from each of 16 directions, split by speed. Sectors go clockwise
+// from north.
+pub struct WindRose {
+    counts: [[usize; SPEED_CLASSES.len() + 1]; WindRose::SECTORS],
+    calm: usize,
+    total: usize,
+    speed: f64,
+}
+
+impl WindRose {
+    pub const SECTORS: usize = 16;
+
+    // Counts the hours with a wind. Hours with a speed but no direction, which are variable
+    // winds, can't be placed on the rose and are left out.
+    pub fn from_hours(hours: &[Hour]) -> WindRose {
+        let mut rose = WindRose {
+            counts: [[0; SPEED_CLASSES.len() + 1]; WindRose::SECTORS],
+            calm: 0,
+            total: 0,
+            speed: 0.0,
+        };
+        for hour in hours {
+            let speed = match hour.wind_speed() {
+                Some(s) => s.in_knots(),
+                None => continue,
+            };
+            match hour.wind_direction() {
+                Some(dir) => {
+                    let n = WindRose::SECTORS as f64;
+                    let sector = ((dir / 360.0 * n).round() as usize) % WindRose::SECTORS;
+                    let class = SPEED_CLASSES
+                        .iter()
+                        .position(|max| speed < *max)
+                        .unwrap_or(SPEED_CLASSES.len());
+                    rose.counts[sector][class] += 1;
+                }
+                None if speed == 0.0 => rose.calm += 1,
+                None => continue,
+            }
+            rose.total += 1;
+            rose.speed += speed;
+        }
+        rose
+    }
+
+    // the fraction of the hours that the wind blew from the sector in the speed class.
+    pub fn frequency(&self, sector: usize, class: usize) -> f64 {
+        self.fraction(self.counts[sector][class])
+    }
+
+    // the fraction of the hours that the wind blew from the sector at any speed.
+    pub fn sector_frequency(&self, sector: usize) -> f64 {
+        self.fraction(self.counts[sector].iter().sum())
+    }
+
+    pub fn calm(&self) -> f64 {
+        self.fraction(self.calm)
+    }
+
+    // the mean speed over all of the hours, calm ones included, in knots.
+    pub fn mean_speed(&self) -> f64 {
+        match self.total {
+            0 => 0.0,
+            n => self.speed / n as f64,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    fn fraction(&self, n: usize) -> f64 {
+        match self.total {
+            0 => 0.0,
+            total => n as f64 / total as f64,
+        }
+    }
+}
+
+const HISTORY_URL: &str = "https://www.ncei.noaa.gov/pub/data/noaa/isd-history.csv";
+
+// What ISD knows about a station from its station list, isd-history.csv, which fills in
+// what the GSOD archives leave out. Codes that a station doesn't have are left out.
+#[derive(Debug, Clone, Serialize)]
+pub struct StationInfo {
+    #[serde(skip)]
+    id: String,
+    #[serde(skip)]
+    name: Option<String>,
+    #[serde(skip)]
+    loc: Option<Location>,
+    wban: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icao: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    begin: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: Option<NaiveDate>,
+}
+
+impl StationInfo {
+    // the GSOD id of the station, its USAF id followed by its WBAN id.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.loc.as_ref()
+    }
+
+    pub fn wban(&self) -> &str {
+        &self.wban
+    }
+
+    // the FIPS code of the country, e.g. US.
+    pub fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+
+    // the postal code of the state, for stations in the US.
+    pub fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
+    pub fn icao(&self) -> Option<&str> {
+        self.icao.as_deref()
+    }
+
+    // the first and last days the station reported on.
+    pub fn begin(&self) -> Option<NaiveDate> {
+        self.begin
+    }
+
+    pub fn end(&self) -> Option<NaiveDate> {
+        self.end
+    }
+}
+
+// Reads isd-history.csv, which has a row of USAF, WBAN, STATION NAME, CTRY, STATE, ICAO,
+// LAT, LON, ELEV(M), BEGIN and END for each station.
+pub fn read_history<R: io::Read>(r: R) -> Result<Vec<StationInfo>, Box<dyn Error>> {
+    let mut r = csv::ReaderBuilder::new().has_headers(true).from_reader(r);
+    let mut infos = Vec::new();
+    for record in r.records() {
+        let record = record?;
+        let field = |i: usize| {
+            record
+                .get(i)
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+        let date = |i: usize| field(i).and_then(|d| NaiveDate::parse_from_str(&d, "%Y%m%d").ok());
+        let (usaf, wban) = (field(0).unwrap_or_default(), field(1).unwrap_or_default());
+        let coord = |i: usize| field(i).and_then(|v| v.parse::<f64>().ok());
+        infos.push(StationInfo {
+            id: format!("{}{}", usaf, wban),
+            name: field(2),
+            // stations without a known location have 0,0 or nothing at all.
+            loc: match (coord(6), coord(7)) {
+                (Some(lat), Some(lng)) if (lat, lng) != (0.0, 0.0) => Some(Location::new(lat, lng)),
+                _ => None,
+            },
+            wban,
+            country: field(3),
+            state: field(4),
+            icao: field(5),
+            begin: date(9),
+            end: date(10),
+        });
+    }
+    Ok(infos)
+}
+
+// Loads the ISD station list, downloading it into the data directory, keyed by GSOD id.
+pub fn history(data: &Data) -> Result<HashMap<String, StationInfo>, Box<dyn Error>> {
+    Ok(
+        read_history(data.download_and_open(HISTORY_URL, "isd-history.csv")?)?
+            .into_iter()
+            .map(|info| (info.id.clone(), info))
+            .collect(),
+    )
+}
+
+// Finds the GSOD id of a station given by its ICAO code (e.g. KBOS), or by an IATA code
+// (e.g. BOS), which is matched against the ICAO codes that end with it, preferring the
+// contiguous US's K prefix. GSOD ids are passed through as they are. When a code has been
+// used by more than one station, the one that reported most recently wins.
+pub fn resolve_station(data: &Data, id: &str) -> Result<String, Box<dyn Error>> {
+    if id.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(String::from(id));
+    }
+
+    let code = id.to_ascii_uppercase();
+    let history = history(data)?;
+    let latest = |f: &dyn Fn(&str) -> bool| {
+        history
+            .values()
+            .filter(|info| info.icao().is_some_and(f))
+            .max_by_key(|info| info.end())
+    };
+    let info = match code.len() {
+        3 => latest(&|icao| icao == format!("K{}", code))
+            .or_else(|| latest(&|icao| icao.len() == 4 && icao.ends_with(&code))),
+        _ => latest(&|icao| icao == code),
+    }
+    .ok_or_else(|| format!("unknown station: {}", id))?;
+    eprintln!("using station {} for {}", info.id(), id);
+    Ok(String::from(info.id()))
+}
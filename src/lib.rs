@@ -1,26 +1,145 @@
+#[cfg(feature = "native")]
 use cairo::{Context, FontSlant, FontWeight};
 use chrono::prelude::*;
+#[cfg(feature = "native")]
+use fetch::{Fetcher, ReqwestFetcher};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
 use std::f64::consts::PI;
+#[cfg(feature = "native")]
 use std::fs;
+#[cfg(feature = "native")]
 use std::path::{Path, PathBuf};
 
+// Checks an invariant of rendering. A broken one means a bug in the data handling or the
+// scales, so debug builds panic to get it noticed while release builds warn (once for each
+// check) and carry on drawing.
+#[cfg(feature = "native")]
+macro_rules! invariant {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            if cfg!(debug_assertions) {
+                panic!($($arg)+);
+            } else {
+                static WARN: std::sync::Once = std::sync::Once::new();
+                WARN.call_once(|| eprintln!("warning: {}", format_args!($($arg)+)));
+            }
+        }
+    };
+}
+
+#[cfg(feature = "native")]
+mod boxplot;
+mod canvas;
+mod chart;
+pub mod climatology;
+#[cfg(feature = "native")]
+mod delta;
+#[cfg(feature = "native")]
+pub mod export;
+#[cfg(feature = "native")]
+pub mod fetch;
+#[cfg(feature = "cdylib")]
+mod ffi;
+pub mod filename;
+#[cfg(feature = "native")]
+pub mod geocode;
+#[cfg(feature = "native")]
+mod ghcn;
 pub mod gsod;
+#[cfg(feature = "native")]
+pub mod heatmap;
+#[cfg(feature = "native")]
+mod histogram;
+#[cfg(feature = "native")]
+mod html;
+#[cfg(feature = "native")]
+mod index;
+mod input;
+#[cfg(feature = "native")]
+pub mod isd;
+#[cfg(feature = "native")]
 pub mod list_stations;
+pub mod locale;
+#[cfg(feature = "native")]
+mod locator;
+#[cfg(feature = "native")]
+mod meteostat;
+mod model;
+#[cfg(feature = "native")]
+mod open_meteo;
+#[cfg(feature = "native")]
+mod period;
+#[cfg(feature = "native")]
+mod placeholder;
+#[cfg(feature = "plotters")]
+pub mod plot;
+#[cfg(feature = "native")]
+mod png;
+#[cfg(feature = "native")]
+pub mod prefetch;
+#[cfg(feature = "native")]
+mod preview;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "skia")]
+pub mod raster;
+#[cfg(feature = "native")]
 pub mod render;
+#[cfg(feature = "native")]
+pub mod report;
+#[cfg(feature = "native")]
+mod repro;
+#[cfg(feature = "native")]
+pub mod source;
+#[cfg(feature = "native")]
+pub mod sparkline;
+#[cfg(feature = "native")]
+pub mod spiral;
+#[cfg(feature = "native")]
+pub mod stats;
+#[cfg(feature = "store")]
+pub mod store;
+#[cfg(feature = "native")]
+pub mod stripes;
+mod svg;
+pub mod theme;
 pub mod time;
+#[cfg(feature = "native")]
+pub mod trend;
+#[cfg(feature = "native")]
+pub mod update;
+#[cfg(feature = "upload")]
+mod upload;
+pub mod web;
+#[cfg(feature = "gui")]
+pub mod window;
 
 pub const TAU: f64 = 2.0 * PI;
 
+// where NOAA's files come from, unless there is a mirror.
+#[cfg(feature = "native")]
+const NCEI: &str = "https://www.ncei.noaa.gov/";
+
+#[cfg(feature = "native")]
 #[derive(Debug)]
 pub struct Data {
     dir: PathBuf,
+    fetcher: Box<dyn Fetcher>,
+    mirror: Option<String>,
 }
 
+#[cfg(feature = "native")]
 impl Data {
     pub fn from<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        Self::with_fetcher(path, Box::new(ReqwestFetcher::new(&Default::default())?))
+    }
+
+    pub fn with_fetcher<P: AsRef<Path>>(
+        path: P,
+        fetcher: Box<dyn Fetcher>,
+    ) -> Result<Self, Box<dyn Error>> {
         let path = path.as_ref();
         if !path.exists() {
             fs::create_dir_all(path)?;
@@ -28,9 +147,38 @@ impl Data {
 
         Ok(Self {
             dir: path.to_owned(),
+            fetcher,
+            mirror: None,
         })
     }
 
+    // Fetches NOAA's files from a mirror instead of ncei.noaa.gov. The mirror is a URL
+    // template in which {path} is the path of the file on NCEI and {file} is its name, so
+    // https://mirror.example.edu/noaa/{path} mirrors the whole site and file:///srv/gsod/{file}
+    // is a flat directory of archives. A mirror without either is taken as a base URL for
+    // the paths.
+    pub fn with_mirror(self, mirror: Option<String>) -> Self {
+        Self { mirror, ..self }
+    }
+
+    fn resolve_url(&self, url: &str) -> String {
+        let (mirror, path) = match (&self.mirror, url.strip_prefix(NCEI)) {
+            (Some(mirror), Some(path)) => (mirror, path),
+            _ => return String::from(url),
+        };
+        if mirror.contains("{path}") || mirror.contains("{file}") {
+            let file = path.rsplit('/').next().unwrap_or(path);
+            mirror.replace("{path}", path).replace("{file}", file)
+        } else {
+            format!("{}/{}", mirror.trim_end_matches('/'), path)
+        }
+    }
+
+    // where a file of the cache lives, whether or not it is there.
+    pub(crate) fn path<P: AsRef<Path>>(&self, name: P) -> PathBuf {
+        self.dir.join(name)
+    }
+
     pub fn download_and_open<P: AsRef<Path>>(
         &self,
         url: &str,
@@ -38,12 +186,143 @@ impl Data {
     ) -> Result<fs::File, Box<dyn Error>> {
         let dst = self.dir.join(dst);
         if !dst.exists() {
-            reqwest::blocking::get(url)?.copy_to(&mut fs::File::create(&dst)?)?;
+            self.download(url, &dst)?;
         }
         Ok(fs::File::open(&dst)?)
     }
+
+    // Downloads the file again when the one at the URL changed after the copy in the cache
+    // was downloaded, as the current year's archive does every day or so. Files that aren't
+    // in the cache yet are downloaded. Returns whether there was anything to download.
+    pub fn refresh<P: AsRef<Path>>(&self, url: &str, dst: P) -> Result<bool, Box<dyn Error>> {
+        let dst = self.dir.join(dst);
+        if dst.exists() {
+            let cached = fs::metadata(&dst)?.modified()?;
+            match self.fetcher.modified(&self.resolve_url(url))? {
+                Some(modified) if modified > cached => {}
+                _ => return Ok(false),
+            }
+        }
+        self.download(url, &dst)?;
+        Ok(true)
+    }
+
+    fn download(&self, url: &str, dst: &Path) -> Result<(), Box<dyn Error>> {
+        // download next to the destination and move it into place once complete so that a
+        // failed download never leaves a truncated file in the cache.
+        let tmp = dst.with_extension("part");
+        let res = self
+            .fetcher
+            .fetch(&self.resolve_url(url), &mut fs::File::create(&tmp)?);
+        if let Err(e) = res {
+            fs::remove_file(&tmp)?;
+            return Err(e);
+        }
+        fs::rename(&tmp, dst)?;
+        Ok(())
+    }
+
+    // Finds the most recent year with a complete archive, preferring archives that are
+    // already in the cache over asking NOAA.
+    pub fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        let last = Local::now().year() - 1;
+        let candidates = (last - 2)..=last;
+        if let Some(year) = candidates
+            .clone()
+            .rev()
+            .find(|year| self.dir.join(format!("{}.tar.gz", year)).exists())
+        {
+            return Ok(year);
+        }
+
+        for year in candidates.rev() {
+            if self
+                .fetcher
+                .exists(&self.resolve_url(&gsod::url_for(year)))?
+            {
+                return Ok(year);
+            }
+        }
+
+        Err("unable to find a recent archive".into())
+    }
+}
+
+// A year given on the command line, either explicitly, as "latest" or as "current".
+#[derive(Debug, Clone, Copy)]
+pub enum YearSpec {
+    Latest,
+    // the year so far, from the archive NOAA adds to every day or so.
+    Current,
+    Year(i32),
+}
+
+#[cfg(feature = "native")]
+impl YearSpec {
+    pub fn resolve(&self, data: &Data) -> Result<i32, Box<dyn Error>> {
+        match self {
+            YearSpec::Latest => data.latest_year(),
+            YearSpec::Current => Ok(Local::now().year()),
+            YearSpec::Year(year) => Ok(*year),
+        }
+    }
+}
+
+impl std::str::FromStr for YearSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "latest" => Ok(YearSpec::Latest),
+            "current" => Ok(YearSpec::Current),
+            s => s
+                .parse::<i32>()
+                .map(YearSpec::Year)
+                .map_err(|_| format!("invalid year: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for YearSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            YearSpec::Latest => write!(f, "latest"),
+            YearSpec::Current => write!(f, "current"),
+            YearSpec::Year(year) => write!(f, "{}", year),
+        }
+    }
+}
+
+impl Serialize for YearSpec {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.collect_str(self)
+    }
 }
 
+// a year can also be given as a bare number, which is what a config file will have.
+impl<'de> Deserialize<'de> for YearSpec {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Year(i32),
+            Spec(String),
+        }
+
+        match Raw::deserialize(d)? {
+            Raw::Year(year) => Ok(YearSpec::Year(year)),
+            Raw::Spec(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Color {
     r: u8,
     g: u8,
@@ -74,6 +353,25 @@ impl Color {
         }
     }
 
+    pub fn with_alpha(&self, a: f64) -> Self {
+        Self {
+            a: (a * 255.0) as u8,
+            ..*self
+        }
+    }
+
+    pub fn lerp(a: &Color, b: &Color, u: Unit) -> Self {
+        let t = u.value();
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Self {
+            r: mix(a.r, b.r),
+            g: mix(a.g, b.g),
+            b: mix(a.b, b.b),
+            a: mix(a.a, b.a),
+        }
+    }
+
+    #[cfg(feature = "native")]
     pub fn set(&self, ctx: &Context) {
         let r = self.r as f64 / 255.0;
         let g = self.g as f64 / 255.0;
@@ -86,6 +384,76 @@ impl Color {
     }
 }
 
+// Colors are written as #rrggbb, or #rrggbbaa when they aren't opaque.
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim().trim_start_matches('#');
+        let v = u32::from_str_radix(hex, 16).map_err(|_| format!("invalid color: {}", s))?;
+        match hex.len() {
+            6 => Ok(Color::from_u32(v)),
+            8 => Ok(Color {
+                a: v as u8,
+                ..Color::from_u32(v >> 8)
+            }),
+            _ => Err(format!("invalid color: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 0xff {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    stops: Vec<Color>,
+}
+
+impl Gradient {
+    pub fn new(stops: Vec<Color>) -> Gradient {
+        assert!(!stops.is_empty());
+        Gradient { stops }
+    }
+
+    pub fn at(&self, u: Unit) -> Color {
+        let n = self.stops.len() - 1;
+        if n == 0 {
+            return self.stops[0];
+        }
+        let v = u.value().clamp(0.0, 1.0) * n as f64;
+        let i = (v.floor() as usize).min(n - 1);
+        Color::lerp(&self.stops[i], &self.stops[i + 1], Unit::new(v - i as f64))
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Unit {
     v: f64,
@@ -105,7 +473,44 @@ impl Unit {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+// How far a normalized value may stray outside of [0, 1] through rounding alone.
+#[cfg(feature = "native")]
+const EPSILON: f64 = 1e-9;
+
+#[cfg(feature = "native")]
+pub(crate) fn check_unit(u: Unit) -> Unit {
+    invariant!(
+        u.value() >= -EPSILON && u.value() <= 1.0 + EPSILON,
+        "normalized value out of range: {}",
+        u.value()
+    );
+    u
+}
+
+#[cfg(feature = "native")]
+pub(crate) fn check_radius(r: f64, rrange: &Range) -> f64 {
+    let eps = EPSILON * (rrange.max() - rrange.min()).abs().max(1.0);
+    invariant!(
+        r >= rrange.min() - eps && r <= rrange.max() + eps,
+        "radius {} outside of {} to {}",
+        r,
+        rrange.min(),
+        rrange.max()
+    );
+    r
+}
+
+#[cfg(feature = "native")]
+pub(crate) fn check_point(x: f64, y: f64) {
+    invariant!(
+        x.is_finite() && y.is_finite(),
+        "non-finite coordinate: ({}, {})",
+        x,
+        y
+    );
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Range {
     min: f64,
     max: f64,
@@ -124,24 +529,60 @@ impl Range {
         self.max
     }
 
+    // A range without any width, like that of a constant series, puts every value at its
+    // bottom rather than dividing by zero.
     pub fn normalize(&self, v: f64) -> Unit {
         let rng = self.max - self.min;
+        if rng == 0.0 {
+            return Unit::zero();
+        }
         Unit::new((v - self.min) / rng)
     }
 
+    // Normalizes the value to [0, 1] like normalize, but for values outside of the range.
+    pub fn normalize_clamped(&self, v: f64) -> Unit {
+        Unit::new(self.normalize(v).value().clamp(0.0, 1.0))
+    }
+
     pub fn project(&self, u: Unit) -> f64 {
         let rng = self.max - self.min;
         self.min + u.value() * rng
     }
 
-    pub fn intersect(a: &Range, b: &Range) -> Range {
+    // The value, moved inside of the range when it is outside of it.
+    pub fn clamp(&self, v: f64) -> f64 {
+        v.clamp(self.min, self.max)
+    }
+
+    // Widens the range by a fraction of its span at each end, e.g. 0.05 to keep the extremes
+    // of a series off of the edges of a chart. A negative fraction narrows it instead, though
+    // never past its middle.
+    pub fn pad(&self, fraction: f64) -> Range {
+        let d = ((self.max - self.min) * fraction).max((self.min - self.max) / 2.0);
+        Range::new(self.min - d, self.max + d)
+    }
+
+    // The smallest range that covers both of them.
+    pub fn union(a: &Range, b: &Range) -> Range {
         Range {
             min: a.min.min(b.min),
             max: a.max.max(b.max),
         }
     }
+
+    // The range that both of them cover, or None when they don't overlap.
+    pub fn intersection(a: &Range, b: &Range) -> Option<Range> {
+        let (min, max) = (a.min.max(b.min), a.max.min(b.max));
+        (min <= max).then_some(Range { min, max })
+    }
+
+    #[deprecated(note = "this is the union of the ranges, use Range::union")]
+    pub fn intersect(a: &Range, b: &Range) -> Range {
+        Range::union(a, b)
+    }
 }
 
+#[cfg(feature = "native")]
 #[derive(Debug)]
 pub struct Font {
     family: &'static str,
@@ -150,6 +591,7 @@ pub struct Font {
     size: f64,
 }
 
+#[cfg(feature = "native")]
 impl Font {
     pub fn new(family: &'static str, slant: FontSlant, weight: FontWeight, size: f64) -> Font {
         Font {
@@ -169,6 +611,7 @@ impl Font {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Series {
     vals: Vec<f64>,
+    filled: Vec<bool>,
     rng: Range,
     min_index: isize,
     max_index: isize,
@@ -180,6 +623,7 @@ impl Series {
         I: Iterator<Item = Option<f64>>,
     {
         let mut vals = Vec::new();
+        let mut filled = Vec::new();
         let mut prev = 0.0;
         let mut max = f64::MIN;
         let mut min = f64::MAX;
@@ -197,14 +641,19 @@ impl Series {
                         min_index = i;
                     }
                     vals.push(val);
+                    filled.push(false);
                     prev = val;
                 }
-                None => vals.push(prev),
+                None => {
+                    vals.push(prev);
+                    filled.push(true);
+                }
             }
         }
 
         Series {
             vals,
+            filled,
             rng: Range::new(min, max),
             min_index: min_index as isize,
             max_index: max_index as isize,
@@ -216,28 +665,108 @@ impl Series {
         I: Iterator<Item = &'a gsod::Day>,
         F: Fn(&gsod::Day) -> Option<f64>,
     {
-        let mut idx = HashMap::new();
-        for day in days {
-            idx.insert(day.date().ordinal(), day);
-        }
+        // only days from the given year can land on its days.
+        let idx = days
+            .filter(|day| day.date() >= year.start() && day.date() < year.end())
+            .map(|day| (day.date(), day))
+            .collect::<HashMap<_, _>>();
+
+        Series::from_iterator(year.days().map(|day| match idx.get(&day.date()) {
+            Some(day) => f(day),
+            None => None,
+        }))
+    }
+
+    // The values of each day of a month, like for_each_day is for a year.
+    pub fn for_each_day_of_month<'a, I, F>(month: time::Month, days: I, f: F) -> Series
+    where
+        I: Iterator<Item = &'a gsod::Day>,
+        F: Fn(&gsod::Day) -> Option<f64>,
+    {
+        let idx = days
+            .filter(|day| day.date() >= month.start() && day.date() < month.end())
+            .map(|day| (day.date(), day))
+            .collect::<HashMap<_, _>>();
 
-        Series::from_iterator(year.days().map(|day| match idx.get(&day.ordinal()) {
+        Series::from_iterator(month.days().map(|day| match idx.get(&day.date()) {
             Some(day) => f(day),
             None => None,
         }))
     }
 
+    // Maps each of the values, and the range along with them, with a function that keeps
+    // their order.
+    pub fn map<F>(&self, f: F) -> Series
+    where
+        F: Fn(f64) -> f64,
+    {
+        Series {
+            vals: self.vals.iter().map(|v| f(*v)).collect(),
+            filled: self.filled.clone(),
+            rng: Range::new(f(self.rng.min()), f(self.rng.max())),
+            min_index: self.min_index,
+            max_index: self.max_index,
+        }
+    }
+
+    // Combines two series value by value, e.g. the max and min temperatures into the range
+    // of each day, up to the end of the shorter of them. A value is filled when either of
+    // the ones it comes from is, and the range is that of the combined values.
+    pub fn zip_with<F>(&self, other: &Series, f: F) -> Series
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        Series::from_iterator(
+            self.vals
+                .iter()
+                .zip(&other.vals)
+                .enumerate()
+                .map(|(i, (a, b))| match self.filled[i] || other.filled[i] {
+                    true => None,
+                    false => Some(f(*a, *b)),
+                }),
+        )
+    }
+
+    pub fn add(&self, other: &Series) -> Series {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    // e.g. a year less its baseline for the anomaly of each day.
+    pub fn sub(&self, other: &Series) -> Series {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    // Multiplies each of the values by k. Unlike map, the range is worked out again, since a
+    // negative k turns it around.
+    pub fn scale(&self, k: f64) -> Series {
+        self.zip_with(self, |a, _| a * k)
+    }
+
+    pub fn fahrenheit_to_celsius(&self) -> Series {
+        self.map(|f| (f - 32.0) * 5.0 / 9.0)
+    }
+
+    pub fn celsius_to_fahrenheit(&self) -> Series {
+        self.map(|c| c * 9.0 / 5.0 + 32.0)
+    }
+
     pub fn with_range(self, rng: &Range) -> Series {
         Series {
             vals: self.vals,
+            filled: self.filled,
             rng: rng.clone(),
             min_index: self.min_index,
             max_index: self.max_index,
         }
     }
 
+    // Values outside of the range, which a range pinned on the command line can leave, are
+    // normalized to its edges.
     pub fn normalize(&self) -> impl Iterator<Item = Unit> + '_ {
-        self.vals.iter().map(move |v| self.rng.normalize(*v))
+        self.vals
+            .iter()
+            .map(move |v| self.rng.normalize_clamped(*v))
     }
 
     pub fn values(&self) -> &[f64] {
@@ -248,13 +777,76 @@ impl Series {
         &self.rng
     }
 
+    pub fn is_filled(&self, i: usize) -> bool {
+        self.filled[i]
+    }
+
+    pub fn summarize(&self) -> Summary {
+        Summary::from_values(
+            self.vals
+                .iter()
+                .zip(self.filled.iter())
+                .filter(|(_, filled)| !**filled)
+                .map(|(v, _)| *v),
+            self.filled.iter().filter(|f| **f).count(),
+        )
+    }
+
+    // Sums how far each real value falls below (heating) or rises above (cooling) the base.
+    // On a series of daily mean temperatures this gives the heating and cooling degree days,
+    // in whatever units the series and base are in.
+    pub fn degree_days(&self, base: f64) -> DegreeDays {
+        let (heating, cooling) = self
+            .vals
+            .iter()
+            .zip(self.filled.iter())
+            .filter(|(_, filled)| !**filled)
+            .fold((0.0, 0.0), |(h, c), (v, _)| {
+                (h + (base - v).max(0.0), c + (v - base).max(0.0))
+            });
+        DegreeDays {
+            base,
+            heating,
+            cooling,
+        }
+    }
+
+    // Counts the real values that satisfy the predicate.
+    pub fn count_where<F>(&self, f: F) -> usize
+    where
+        F: Fn(f64) -> bool,
+    {
+        self.vals
+            .iter()
+            .zip(self.filled.iter())
+            .filter(|(v, filled)| !**filled && f(**v))
+            .count()
+    }
+
+    // The q quantile of the real values, q in [0, 1], linearly interpolated between them. A
+    // series without any real values has no quantiles, so this is NaN.
+    pub fn quantile(&self, q: f64) -> f64 {
+        let mut vals = self
+            .vals
+            .iter()
+            .zip(self.filled.iter())
+            .filter(|(_, filled)| !**filled)
+            .map(|(v, _)| *v)
+            .collect::<Vec<_>>();
+        if vals.is_empty() {
+            return f64::NAN;
+        }
+        vals.sort_by(|a, b| a.total_cmp(b));
+        percentile_of(&vals, q)
+    }
+
     pub fn get(&self, i: isize) -> f64 {
         let n = self.vals.len() as isize;
         self.vals[(((i % n) + n) % n) as usize]
     }
 
     pub fn get_normalized(&self, i: isize) -> Unit {
-        self.rng.normalize(self.get(i))
+        self.rng.normalize_clamped(self.get(i))
     }
 
     pub fn min_index(&self) -> isize {
@@ -265,26 +857,339 @@ impl Series {
         self.max_index
     }
 
+    // Aggregates a window of values centered on each value. The window is truncated at the
+    // ends of the series rather than wrapping around.
+    pub fn rolling<F>(&self, window: usize, agg: F) -> Series
+    where
+        F: Fn(&[f64]) -> f64,
+    {
+        let n = self.vals.len();
+        let half = window / 2;
+        let vals = (0..n)
+            .map(|i| agg(&self.vals[i.saturating_sub(half)..(i + window - half).min(n)]))
+            .collect();
+
+        Series {
+            vals,
+            filled: self.filled.clone(),
+            rng: self.rng.clone(),
+            min_index: self.min_index,
+            max_index: self.max_index,
+        }
+    }
+
     pub fn downsample_by<F>(&self, n: usize, agg: F) -> Series
     where
         F: Fn(&[f64]) -> f64,
     {
         let m = self.vals.len() / n;
         let mut vals = Vec::with_capacity(m);
+        let mut filled = Vec::with_capacity(m);
 
         for i in 0..m {
             let j = i * n;
             let v = agg(&self.vals[j..(j + n)]);
             vals.push(v);
+            filled.push(self.filled[j..(j + n)].iter().all(|f| *f));
         }
 
         Series {
             vals,
+            filled,
             rng: self.rng.clone(),
             min_index: self.min_index / n as isize,
             max_index: self.max_index / n as isize,
         }
     }
+
+    // Aggregates the values of a series with a value for each day of the year over the
+    // weeks, which start on Mondays. The days before the first Monday of the year are a week
+    // of their own, as are those from the last Monday on.
+    pub fn resample_weekly<F>(&self, year: time::Year, agg: F) -> Series
+    where
+        F: Fn(&[f64]) -> f64,
+    {
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (i, day) in year.days().enumerate() {
+            match spans.last_mut() {
+                Some((_, end)) if day.date().weekday() != Weekday::Mon => *end = i + 1,
+                _ => spans.push((i, i + 1)),
+            }
+        }
+        self.resample(&spans, agg)
+    }
+
+    // Aggregates the values of a series with a value for each day of the year over its
+    // months.
+    pub fn resample_monthly<F>(&self, year: time::Year, agg: F) -> Series
+    where
+        F: Fn(&[f64]) -> f64,
+    {
+        let start = |date: NaiveDate| date.signed_duration_since(year.start()).num_days() as usize;
+        let spans = year
+            .months()
+            .map(|month| (start(month.start()), start(month.end())))
+            .collect::<Vec<_>>();
+        self.resample(&spans, agg)
+    }
+
+    // Aggregates the values of each of the spans of indices, which are in order and cover
+    // the whole series. Only the real values of a span are aggregated, unless it has none.
+    // The range and extremes are those of the aggregates, since a month's total can be far
+    // outside of the range of its days.
+    fn resample<F>(&self, spans: &[(usize, usize)], agg: F) -> Series
+    where
+        F: Fn(&[f64]) -> f64,
+    {
+        assert_eq!(spans.last().map(|(_, end)| *end), Some(self.vals.len()));
+        let vals = spans.iter().map(|(start, end)| {
+            let real = (*start..*end)
+                .filter(|i| !self.filled[*i])
+                .map(|i| self.vals[i])
+                .collect::<Vec<_>>();
+            match real.is_empty() {
+                true => agg(&self.vals[*start..*end]),
+                false => agg(&real),
+            }
+        });
+        Series {
+            filled: spans
+                .iter()
+                .map(|(start, end)| self.filled[*start..*end].iter().all(|f| *f))
+                .collect(),
+            ..Series::from_iterator(vals.map(Some))
+        }
+    }
+
+    // Averages every n values, each weighted by the same value of weights, like the number of
+    // observations behind a daily mean. A span without any weight, because its days are
+    // missing or the source doesn't count observations, gets the plain mean.
+    pub fn downsample_weighted(&self, n: usize, weights: &Series) -> Series {
+        assert_eq!(self.vals.len(), weights.vals.len());
+        let mean = self.downsample_by(n, |vals| vals.iter().sum::<f64>() / vals.len() as f64);
+        let vals = mean
+            .vals
+            .iter()
+            .enumerate()
+            .map(|(i, mean)| {
+                let (sum, total) = (i * n..(i + 1) * n).filter(|j| !weights.filled[*j]).fold(
+                    (0.0, 0.0),
+                    |(sum, total), j| {
+                        (
+                            sum + self.vals[j] * weights.vals[j],
+                            total + weights.vals[j],
+                        )
+                    },
+                );
+                match total > 0.0 {
+                    true => sum / total,
+                    false => *mean,
+                }
+            })
+            .collect();
+        Series { vals, ..mean }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DegreeDays {
+    base: f64,
+    heating: f64,
+    cooling: f64,
+}
+
+impl DegreeDays {
+    pub fn base(&self) -> f64 {
+        self.base
+    }
+
+    pub fn heating(&self) -> f64 {
+        self.heating
+    }
+
+    pub fn cooling(&self) -> f64 {
+        self.cooling
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    count: usize,
+    filled: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    median: f64,
+    std_dev: f64,
+    p10: f64,
+    p25: f64,
+    p75: f64,
+    p90: f64,
+}
+
+impl Summary {
+    // Summarizes the real (non-filled) values. `filled` is the number of values that were
+    // carried forward to cover gaps in the data and is only reported.
+    pub fn from_values<I>(iter: I, filled: usize) -> Summary
+    where
+        I: Iterator<Item = f64>,
+    {
+        let mut vals: Vec<f64> = iter.collect();
+        vals.sort_by(|a, b| a.total_cmp(b));
+
+        let count = vals.len();
+        if count == 0 {
+            return Summary {
+                count,
+                filled,
+                sum: 0.0,
+                min: f64::NAN,
+                max: f64::NAN,
+                mean: f64::NAN,
+                median: f64::NAN,
+                std_dev: f64::NAN,
+                p10: f64::NAN,
+                p25: f64::NAN,
+                p75: f64::NAN,
+                p90: f64::NAN,
+            };
+        }
+
+        let sum = vals.iter().sum::<f64>();
+        let mean = sum / count as f64;
+        let var = vals.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / count as f64;
+        Summary {
+            count,
+            filled,
+            sum,
+            min: vals[0],
+            max: vals[count - 1],
+            mean,
+            median: percentile_of(&vals, 0.5),
+            std_dev: var.sqrt(),
+            p10: percentile_of(&vals, 0.1),
+            p25: percentile_of(&vals, 0.25),
+            p75: percentile_of(&vals, 0.75),
+            p90: percentile_of(&vals, 0.9),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn filled(&self) -> usize {
+        self.filled
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn median(&self) -> f64 {
+        self.median
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    pub fn p10(&self) -> f64 {
+        self.p10
+    }
+
+    pub fn p25(&self) -> f64 {
+        self.p25
+    }
+
+    pub fn p75(&self) -> f64 {
+        self.p75
+    }
+
+    pub fn p90(&self) -> f64 {
+        self.p90
+    }
+}
+
+// The temperature (°F) that a day felt like: the heat index when it was hot, the wind chill
+// when it was cold and windy and the mean temperature otherwise. When the dewpoint or the
+// wind is missing, this falls back to the mean temperature too.
+pub(crate) fn feels_like(day: &gsod::Day) -> Option<f64> {
+    let t = day.mean_temperature()?.in_fahrenheit();
+    if t >= 80.0 {
+        Some(day.mean_dewpoint().map_or(t, |d| {
+            heat_index(t, relative_humidity(t, d.in_fahrenheit()))
+        }))
+    } else if t <= 50.0 {
+        Some(
+            day.mean_wind()
+                .map_or(t, |w| wind_chill(t, w.in_knots() * 1.15078)),
+        )
+    } else {
+        Some(t)
+    }
+}
+
+// The relative humidity, in percent, from the temperature and dewpoint (°F).
+fn relative_humidity(t: f64, dewpoint: f64) -> f64 {
+    let c = |f: f64| (f - 32.0) * 5.0 / 9.0;
+    let e = |t: f64| (17.625 * t / (243.04 + t)).exp();
+    (100.0 * e(c(dewpoint)) / e(c(t))).min(100.0)
+}
+
+// The heat index of the National Weather Service, from the temperature (°F) and relative
+// humidity (%).
+fn heat_index(t: f64, rh: f64) -> f64 {
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return simple;
+    }
+
+    let hi = -42.379 + 2.04901523 * t + 10.14333127 * rh
+        - 0.22475541 * t * rh
+        - 0.00683783 * t * t
+        - 0.05481717 * rh * rh
+        + 0.00122874 * t * t * rh
+        + 0.00085282 * t * rh * rh
+        - 0.00000199 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        hi - (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt()
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        hi + (rh - 85.0) / 10.0 * (87.0 - t) / 5.0
+    } else {
+        hi
+    }
+}
+
+// The wind chill of the National Weather Service, from the temperature (°F) and wind speed
+// (mph). It isn't defined for light winds.
+fn wind_chill(t: f64, v: f64) -> f64 {
+    if v <= 3.0 {
+        return t;
+    }
+    let v = v.powf(0.16);
+    35.74 + 0.6215 * t - 35.75 * v + 0.4275 * t * v
+}
+
+// Linearly interpolated percentile of already sorted values, p in [0, 1].
+pub(crate) fn percentile_of(sorted: &[f64], p: f64) -> f64 {
+    let x = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let i = x.floor() as usize;
+    let j = x.ceil() as usize;
+    sorted[i] + (sorted[j] - sorted[i]) * (x - i as f64)
 }
 
 #[derive(Debug)]
@@ -294,40 +1199,113 @@ pub struct Scale {
 }
 
 impl Scale {
-    pub fn from_range(r: &Range, lim: f64) -> Scale {
-        let rng = r.max() - r.min();
-        let mag = (10.0f64).powf((rng.log10() - 1.0).floor());
-        let facs = vec![1, 2, 3, 5, 10, 20, 30, 50];
-        for fac in facs {
-            let step = fac as f64 * mag;
-            let n = rng / step;
-            if n < lim {
-                return Self::from_range_with_step(r, step);
+    // the most steps a scale has, which keeps a step that is tiny next to its range from
+    // filling memory.
+    const MAX_STEPS: f64 = 1000.0;
+
+    // Picks the first of 1, 2, 3 or 5 times a power of ten that splits the range into
+    // fewer than lim steps. A range whose ends are the same, as for a constant series, gets
+    // a step of its magnitude and nothing between its ends. Ranges whose min is above their
+    // max are read the other way around.
+    pub fn from_range(r: &Range, lim: f64) -> Result<Scale, Box<dyn Error>> {
+        // f64::min and max pass over a NaN, so the ends are checked before they're ordered.
+        if !r.min().is_finite() || !r.max().is_finite() {
+            return Err(format!("unable to make a scale from {} to {}", r.min(), r.max()).into());
+        }
+        let (lo, hi) = (r.min().min(r.max()), r.min().max(r.max()));
+        if !(hi - lo).is_finite() {
+            return Err(format!("unable to make a scale from {} to {}", r.min(), r.max()).into());
+        }
+        if lim.is_nan() || lim <= 0.0 {
+            return Err(format!("unable to make a scale of fewer than {} steps", lim).into());
+        }
+        let rng = match hi - lo {
+            0.0 => lo.abs().max(1.0),
+            rng => rng,
+        };
+        let facs = [1, 2, 3, 5, 10, 20, 30, 50];
+        let mut mag = (10.0f64).powf((rng.log10() - 1.0).floor());
+        loop {
+            for fac in facs {
+                let step = fac as f64 * mag;
+                let n = rng / step;
+                if n < lim {
+                    return Self::from_range_with_step(&Range::new(lo, hi), step);
+                }
             }
+            mag *= 100.0;
         }
+    }
 
-        panic!("unreachable");
+    // Steps at the powers of ten from floor up to the top of the range, for a range drawn
+    // on a log scale. Ranges that don't span a few of them get steps at 2 and 5 times each
+    // power as well.
+    pub fn from_range_log(r: &Range, floor: f64) -> Result<Scale, Box<dyn Error>> {
+        let (lo, hi) = (r.min().min(r.max()), r.min().max(r.max()));
+        if !lo.is_finite() || !hi.is_finite() || !floor.is_finite() || floor <= 0.0 {
+            return Err(
+                format!("unable to make a log scale from {} to {}", r.min(), r.max()).into(),
+            );
+        }
+        let powers = |facs: &[f64]| {
+            (0..)
+                .map(|i| floor * 10.0f64.powi(i))
+                .take_while(|p| *p < hi)
+                .flat_map(|p| facs.iter().map(move |f| f * p))
+                .filter(|s| *s > lo && *s < hi)
+                .collect::<Vec<_>>()
+        };
+        let steps = match powers(&[1.0]) {
+            steps if steps.len() < 3 => powers(&[1.0, 2.0, 5.0]),
+            steps => steps,
+        };
+        Ok(Scale {
+            step: steps.first().copied().unwrap_or(floor),
+            steps,
+        })
     }
 
-    pub fn from_range_with_step(r: &Range, step: f64) -> Scale {
-        let mut min = (r.min() / step).floor() * step + step;
-        let max = r.max();
-        let mut steps = Vec::new();
-        while min < max {
-            steps.push(min);
-            min += step;
+    pub fn from_range_with_step(r: &Range, step: f64) -> Result<Scale, Box<dyn Error>> {
+        if !step.is_finite() || step <= 0.0 {
+            return Err(format!("unable to make a scale with a step of {}", step).into());
+        }
+        let (lo, hi) = (r.min().min(r.max()), r.min().max(r.max()));
+        let first = (lo / step).floor() * step + step;
+        if !first.is_finite() || (hi - first) / step > Self::MAX_STEPS {
+            return Err(format!(
+                "unable to make a scale from {} to {} in steps of {}",
+                r.min(),
+                r.max(),
+                step
+            )
+            .into());
         }
-        Scale { step, steps }
+        let steps = (0..)
+            .map(|i| first + i as f64 * step)
+            .take_while(|s| *s < hi)
+            .collect();
+        Ok(Scale { step, steps })
     }
 
+    // Labels have as many digits as the step needs, without trailing zeros, so that the
+    // labels of a scale agree with each other. Steps of a million or more are labeled in
+    // scientific notation.
     pub fn label_for(&self, i: usize) -> String {
-        let s = self.steps[i];
-        if self.step() >= 1.0 {
-            format!("{}", s as i32)
+        let trim = |s: &str| String::from(s.trim_end_matches('0').trim_end_matches('.'));
+        // adding zero turns a -0 into 0.
+        let s = self.steps[i] + 0.0;
+        let mag = self.step().log10().floor();
+        if mag >= 6.0 && s != 0.0 {
+            let p = (s.abs().log10().floor() - mag).max(0.0) as usize;
+            let label = format!("{0:.1$e}", s, p);
+            match label.split_once('e') {
+                Some((m, e)) if m.contains('.') => format!("{}e{}", trim(m), e),
+                _ => label,
+            }
+        } else if mag >= 0.0 {
+            format!("{:.0}", s)
         } else {
-            let p = s.log10().floor().abs() as usize;
-            println!("step = {}, s = {}, p = {}", self.step(), s, p);
-            format!("{0:.1$}", s, p)
+            trim(&format!("{0:.1$}", s, -mag as usize))
         }
     }
 
@@ -344,3 +1322,212 @@ pub enum Direction {
     Right,
     Left,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_series_normalizes_in_range() {
+        let series = Series::from_iterator(std::iter::repeat_n(Some(50.0), 10));
+        assert_eq!(series.range().min(), series.range().max());
+        for u in series.normalize() {
+            assert_eq!(u.value(), 0.0);
+        }
+        assert_eq!(series.get_normalized(3).value(), 0.0);
+    }
+
+    #[test]
+    fn single_day_series_normalizes_in_range() {
+        let csv = "date,tmin,tmax,precip\n2022-06-01,60,80,0.2\n";
+        let station = input::read(csv.as_bytes(), "test", String::from("test")).unwrap();
+        let year = time::Year::from_ordinal(2022);
+        let series = Series::for_each_day(year, station.days().iter(), |day| {
+            day.max_temperature().map(|t| t.in_fahrenheit())
+        });
+        assert_eq!(series.values().len(), 365);
+        assert_eq!(series.summarize().count(), 1);
+        for u in series.normalize() {
+            assert!(
+                (0.0..=1.0).contains(&u.value()),
+                "{} is out of range",
+                u.value()
+            );
+        }
+    }
+
+    #[test]
+    fn scale_from_range_picks_a_round_step() {
+        let scale = Scale::from_range(&Range::new(0.0, 100.0), 6.0).unwrap();
+        assert_eq!(scale.step(), 20.0);
+        assert_eq!(scale.steps(), &[20.0, 40.0, 60.0, 80.0]);
+
+        let scale = Scale::from_range(&Range::new(-10.0, 10.0), 6.0).unwrap();
+        assert_eq!(scale.steps(), &[-5.0, 0.0, 5.0]);
+    }
+
+    #[test]
+    fn scale_from_range_handles_constant_and_invalid_ranges() {
+        let scale = Scale::from_range(&Range::new(50.0, 50.0), 6.0).unwrap();
+        assert!(scale.step() > 0.0);
+        assert!(scale.steps().iter().all(|s| s.is_finite()));
+        assert!(Scale::from_range(&Range::new(0.0, 0.0), 6.0).is_ok());
+
+        assert!(Scale::from_range(&Range::new(f64::NAN, 1.0), 6.0).is_err());
+        assert!(Scale::from_range(&Range::new(0.0, f64::INFINITY), 6.0).is_err());
+        assert!(Scale::from_range(&Range::new(0.0, 1.0), 0.0).is_err());
+    }
+
+    #[test]
+    fn scale_labels_have_the_digits_of_the_step() {
+        let labels = |scale: &Scale| {
+            (0..scale.steps().len())
+                .map(|i| scale.label_for(i))
+                .collect::<Vec<_>>()
+        };
+        let scale = Scale::from_range(&Range::new(0.0, 1.0), 6.0).unwrap();
+        assert_eq!(labels(&scale), ["0.2", "0.4", "0.6", "0.8"]);
+
+        let scale = Scale::from_range(&Range::new(-10.0, 10.0), 6.0).unwrap();
+        assert_eq!(labels(&scale), ["-5", "0", "5"]);
+
+        let scale = Scale::from_range(&Range::new(0.0, 5e6), 6.0).unwrap();
+        assert_eq!(labels(&scale), ["1e6", "2e6", "3e6", "4e6"]);
+    }
+
+    #[test]
+    fn range_clamp_keeps_values_inside() {
+        let range = Range::new(10.0, 20.0);
+        assert_eq!(range.clamp(5.0), 10.0);
+        assert_eq!(range.clamp(15.0), 15.0);
+        assert_eq!(range.clamp(25.0), 20.0);
+        assert_eq!(range.normalize_clamped(25.0).value(), 1.0);
+        assert_eq!(range.normalize_clamped(5.0).value(), 0.0);
+    }
+
+    #[test]
+    fn range_union_and_intersection() {
+        let (a, b) = (Range::new(0.0, 10.0), Range::new(5.0, 20.0));
+        assert_eq!(Range::union(&a, &b), Range::new(0.0, 20.0));
+        assert_eq!(Range::intersection(&a, &b), Some(Range::new(5.0, 10.0)));
+
+        let c = Range::new(30.0, 40.0);
+        assert_eq!(Range::union(&a, &c), Range::new(0.0, 40.0));
+        assert_eq!(Range::intersection(&a, &c), None);
+        assert_eq!(Range::intersection(&c, &a), None);
+
+        // ranges that only touch share their end.
+        let d = Range::new(10.0, 15.0);
+        assert_eq!(Range::intersection(&a, &d), Some(Range::new(10.0, 10.0)));
+    }
+
+    #[test]
+    fn range_pad() {
+        let range = Range::new(0.0, 100.0);
+        assert_eq!(range.pad(0.05), Range::new(-5.0, 105.0));
+        assert_eq!(range.pad(0.0), range);
+        assert_eq!(range.pad(-0.25), Range::new(25.0, 75.0));
+        assert_eq!(range.pad(-2.0), Range::new(50.0, 50.0));
+    }
+
+    fn ones(year: time::Year) -> Series {
+        Series::from_iterator(year.days().map(|_| Some(1.0)))
+    }
+
+    #[test]
+    fn resample_monthly_follows_month_lengths() {
+        let sum = |vals: &[f64]| vals.iter().sum::<f64>();
+        let year = time::Year::from_ordinal(2023);
+        let totals = ones(year).resample_monthly(year, sum);
+        assert_eq!(
+            totals.values(),
+            &[31.0, 28.0, 31.0, 30.0, 31.0, 30.0, 31.0, 31.0, 30.0, 31.0, 30.0, 31.0]
+        );
+        // the range is that of the totals, not of the days.
+        assert_eq!(totals.range(), &Range::new(28.0, 31.0));
+
+        let leap = time::Year::from_ordinal(2024);
+        let totals = ones(leap).resample_monthly(leap, sum);
+        assert_eq!(totals.values().len(), 12);
+        assert_eq!(totals.values()[1], 29.0);
+        assert_eq!(totals.values().iter().sum::<f64>(), 366.0);
+    }
+
+    #[test]
+    fn resample_monthly_marks_months_without_days_filled() {
+        let year = time::Year::from_ordinal(2023);
+        let series = Series::from_iterator(
+            year.days()
+                .map(|day| (day.date().month() != 2).then_some(day.ordinal() as f64)),
+        );
+        let means =
+            series.resample_monthly(year, |vals| vals.iter().sum::<f64>() / vals.len() as f64);
+        assert!(!means.is_filled(0));
+        assert!(means.is_filled(1));
+        assert!(!means.is_filled(2));
+        assert_eq!(means.values()[0], 16.0);
+        assert_eq!(means.values()[2], 75.0);
+    }
+
+    #[test]
+    fn resample_weekly_starts_weeks_on_mondays() {
+        let count = |vals: &[f64]| vals.len() as f64;
+        // January 1st, 2023 was a Sunday, a week of its own.
+        let year = time::Year::from_ordinal(2023);
+        let weeks = ones(year).resample_weekly(year, count);
+        assert_eq!(weeks.values().len(), 53);
+        assert_eq!(weeks.values()[0], 1.0);
+        assert!(weeks.values()[1..].iter().all(|n| *n == 7.0));
+
+        // 2024 starts on a Monday and, as a leap year, ends two days into a week.
+        let leap = time::Year::from_ordinal(2024);
+        let weeks = ones(leap).resample_weekly(leap, count);
+        assert_eq!(weeks.values().len(), 53);
+        assert_eq!(weeks.values()[52], 2.0);
+    }
+
+    fn series(vals: &[Option<f64>]) -> Series {
+        Series::from_iterator(vals.iter().copied())
+    }
+
+    #[test]
+    fn negative_scale_reverses_the_range() {
+        let scaled = series(&[Some(1.0), Some(2.0), Some(3.0)]).scale(-2.0);
+        assert_eq!(scaled.values(), &[-2.0, -4.0, -6.0]);
+        assert_eq!(scaled.range(), &Range::new(-6.0, -2.0));
+        assert_eq!(scaled.min_index(), 2);
+        assert_eq!(scaled.max_index(), 0);
+    }
+
+    #[test]
+    fn zip_with_fills_the_days_filled_in_either_series() {
+        let a = series(&[Some(1.0), None, Some(3.0), Some(4.0)]);
+        let b = series(&[Some(10.0), Some(20.0), None, Some(40.0)]);
+        let sum = a.add(&b);
+        let filled = (0..4).map(|i| sum.is_filled(i)).collect::<Vec<_>>();
+        assert_eq!(filled, [false, true, true, false]);
+        // the filled days carry the last real value and stay out of the range.
+        assert_eq!(sum.values(), &[11.0, 11.0, 11.0, 44.0]);
+        assert_eq!(sum.range(), &Range::new(11.0, 44.0));
+
+        let diff = b.sub(&a);
+        assert_eq!(diff.values()[3], 36.0);
+        assert!(diff.is_filled(1) && diff.is_filled(2));
+    }
+
+    #[test]
+    fn zip_with_stops_at_the_shorter_series() {
+        let a = series(&[Some(1.0), Some(2.0), Some(3.0)]);
+        let b = series(&[Some(1.0), Some(1.0)]);
+        assert_eq!(a.zip_with(&b, |a, b| a * b).values(), &[1.0, 2.0]);
+    }
+
+    #[test]
+    fn temperature_conversions() {
+        let f = series(&[Some(32.0), Some(212.0), Some(-40.0)]);
+        let c = f.fahrenheit_to_celsius();
+        assert_eq!(c.values(), &[0.0, 100.0, -40.0]);
+        assert_eq!(c.range(), &Range::new(-40.0, 100.0));
+        assert_eq!(c.celsius_to_fahrenheit().values(), f.values());
+    }
+}
@@ -1,23 +1,105 @@
-use super::{gsod, Data};
-use chrono::prelude::*;
-use flate2::read::GzDecoder;
+use super::{gsod, isd, Data, YearSpec};
+use serde::Serialize;
 use std::error::Error;
-use tar::Archive;
 
 #[derive(clap::Args, Debug)]
 pub struct Args {
-    #[clap(long, default_value_t = Local::now().year()-1)]
-    year: i32,
+    /// The year to use, "latest" for the most recent complete year or "current" for the
+    /// year so far.
+    #[clap(long, default_value = "latest")]
+    year: YearSpec,
+
+    /// Only list the stations in this country, by FIPS code (e.g. US).
+    #[clap(long)]
+    country: Option<String>,
+
+    /// Only list the stations in this US state, by postal code (e.g. NC).
+    #[clap(long)]
+    state: Option<String>,
+
+    /// Fail on a station whose first row can't be read, rather than skipping rows until one
+    /// can and reporting how many were skipped at the end.
+    #[clap(long, default_value_t = false)]
+    strict: bool,
+}
+
+#[derive(Serialize)]
+struct Listing<'a> {
+    #[serde(flatten)]
+    station: &'a gsod::Header,
+    #[serde(flatten)]
+    info: Option<&'a isd::StationInfo>,
 }
 
 pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
-    let mut r = Archive::new(GzDecoder::new(
-        data.download_and_open(&gsod::url_for(args.year), format!("{}.tar.gz", args.year))?,
-    ));
-    for entry in r.entries()? {
-        let station = gsod::Station::from_entry(&mut entry?)?;
-        let json = serde_json::to_string_pretty(&station)?;
+    let year = args.year.resolve(data)?;
+    let filtered = args.country.is_some() || args.state.is_some();
+
+    // the station list only adds to the listing, so it can go without unless it's needed
+    // for filtering.
+    let history = match isd::history(data) {
+        Ok(history) => history,
+        Err(e) if !filtered => {
+            eprintln!("unable to load the ISD station list: {}", e);
+            Default::default()
+        }
+        Err(e) => return Err(e),
+    };
+    let matches = |want: &Option<String>, have: Option<&str>| match want {
+        Some(want) => have.is_some_and(|have| have.eq_ignore_ascii_case(want)),
+        None => true,
+    };
+
+    let print = |station: &gsod::Header| -> Result<(), Box<dyn Error>> {
+        let info = history.get(station.id());
+        if !matches(&args.country, info.and_then(|i| i.country()))
+            || !matches(&args.state, info.and_then(|i| i.state()))
+        {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(&Listing { station, info })?;
         println!("{}", json);
+        Ok(())
+    };
+
+    #[cfg(feature = "store")]
+    if let Some(store) = super::store::Store::existing(data)? {
+        if store.has_year(year)? {
+            for station in store.stations(year)? {
+                print(&station.header())?;
+            }
+            return Ok(());
+        }
+    }
+
+    let mut archive = gsod::Archive::open(data, year)?;
+    // only the first row of each station is read, the listing doesn't have the days.
+    let (mut bad_rows, mut bad_stations, mut bad_entries) = (0, 0, 0);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if args.strict {
+            print(&gsod::Station::headers_from_entry(&mut entry)?.0)?;
+            continue;
+        }
+        match gsod::Station::headers_from_reader_lenient(&mut entry) {
+            Ok((station, _, skipped)) => {
+                if skipped > 0 {
+                    bad_rows += skipped;
+                    bad_stations += 1;
+                }
+                print(&station)?;
+            }
+            Err(e) => {
+                bad_entries += 1;
+                eprintln!("skipped {}: {}", entry.path()?.display(), e);
+            }
+        }
+    }
+    if bad_rows > 0 || bad_entries > 0 {
+        eprintln!(
+            "skipped {} unreadable rows of {} stations and {} unreadable stations",
+            bad_rows, bad_stations, bad_entries
+        );
     }
     Ok(())
 }
@@ -0,0 +1,380 @@
+#[cfg(feature = "native")]
+use chrono::Weekday;
+use chrono::{NaiveDate, TimeZone, Utc};
+use pure_rust_locales::locale_match;
+use serde::{Deserialize, Serialize};
+
+// The language of the text in a render. Month and weekday names come from chrono's locale
+// data, everything else from the tables below, falling back to English for a language
+// without one.
+#[derive(Debug, Clone, Copy)]
+pub struct Locale {
+    locale: chrono::Locale,
+}
+
+// Most of the labels are only drawn by the command line tool's charts.
+#[cfg_attr(not(feature = "native"), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Label {
+    Temperature,
+    MeanTemperature,
+    AnnualMeanTemperature,
+    DailyMeanTemperature,
+    FeelsLike,
+    Wind,
+    Precipitation,
+    Max,
+    Avg,
+    Min,
+    Median,
+    P10,
+    P90,
+    Mode,
+    Days,
+    RainDays,
+    SnowDays,
+    Total,
+    SummerDays,
+    FrostDays,
+    IceDays,
+    Calm,
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Locale {
+    fn language(&self) -> String {
+        format!("{:?}", self.locale)
+            .split('_')
+            .next()
+            .unwrap_or("")
+            .to_owned()
+    }
+
+    pub(crate) fn label(&self, label: Label) -> &'static str {
+        match self.language().as_str() {
+            "de" => german(label),
+            "fr" => french(label),
+            "es" => spanish(label),
+            "it" => italian(label),
+            "nl" => dutch(label),
+            "pt" => portuguese(label),
+            _ => english(label),
+        }
+    }
+
+    // formats a date with one of chrono's strftime formats using the names of the locale.
+    pub(crate) fn format(&self, date: NaiveDate, fmt: &str) -> String {
+        Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+            .format_localized(fmt, self.locale)
+            .to_string()
+    }
+
+    // a date written out with an abbreviated month, in the order the locale writes dates.
+    #[cfg(feature = "native")]
+    pub(crate) fn date(&self, date: NaiveDate) -> String {
+        let d_fmt = locale_match!(self.locale => LC_TIME::D_FMT);
+        let day_first = match (d_fmt.find("%d").or(d_fmt.find("%e")), d_fmt.find("%m")) {
+            (Some(d), Some(m)) => d < m,
+            _ => false,
+        };
+        let fmt = match (day_first, d_fmt.contains('.')) {
+            (true, true) => "%-d. %b %Y",
+            (true, false) => "%-d %b %Y",
+            (false, _) => "%b %-d, %Y",
+        };
+        self.format(date, fmt)
+    }
+
+    // a number with a fixed number of decimals and the locale's decimal separator.
+    pub(crate) fn number(&self, v: f64, precision: usize) -> String {
+        self.localize(&format!("{:.*}", precision, v))
+    }
+
+    // swaps the decimal point of an already formatted number for the locale's.
+    pub(crate) fn localize(&self, s: &str) -> String {
+        match locale_match!(self.locale => LC_NUMERIC::DECIMAL_POINT) {
+            "" | "." => s.to_owned(),
+            point => s.replace('.', point),
+        }
+    }
+
+    pub(crate) fn month_abbr(&self, date: NaiveDate) -> String {
+        self.format(date, "%b")
+    }
+
+    pub(crate) fn month_name(&self, date: NaiveDate) -> String {
+        self.format(date, "%B")
+    }
+
+    #[cfg(feature = "native")]
+    pub(crate) fn weekday_abbr(&self, weekday: Weekday) -> String {
+        self.format(NaiveDate::from_isoywd_opt(2023, 1, weekday).unwrap(), "%a")
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            locale: chrono::Locale::en_US,
+        }
+    }
+}
+
+// Locales are written as a language tag like de-DE, though the POSIX form de_DE is
+// accepted too.
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.replace('-', "_");
+        chrono::Locale::try_from(name.as_str())
+            .map(|locale| Locale { locale })
+            .map_err(|_| format!("unknown locale: {}", s))
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self.locale).replace('_', "-"))
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+fn english(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPERATURE",
+        Label::MeanTemperature => "MEAN TEMPERATURE",
+        Label::AnnualMeanTemperature => "ANNUAL MEAN TEMPERATURE",
+        Label::DailyMeanTemperature => "DAILY MEAN TEMPERATURE",
+        Label::FeelsLike => "FEELS LIKE",
+        Label::Wind => "WIND",
+        Label::Precipitation => "PRECIPITATION",
+        Label::Max => "MAX",
+        Label::Avg => "AVG",
+        Label::Min => "MIN",
+        Label::Median => "MEDIAN",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODE",
+        Label::Days => "DAYS",
+        Label::RainDays => "RAIN DAYS",
+        Label::SnowDays => "SNOW DAYS",
+        Label::Total => "TOTAL",
+        Label::SummerDays => "summer days",
+        Label::FrostDays => "frost days",
+        Label::IceDays => "ice days",
+        Label::Calm => "CALM",
+        Label::North => "N",
+        Label::East => "E",
+        Label::South => "S",
+        Label::West => "W",
+    }
+}
+
+fn german(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPERATUR",
+        Label::MeanTemperature => "MITTLERE TEMPERATUR",
+        Label::AnnualMeanTemperature => "JAHRESMITTELTEMPERATUR",
+        Label::DailyMeanTemperature => "TAGESMITTELTEMPERATUR",
+        Label::FeelsLike => "GEFÜHLT",
+        Label::Wind => "WIND",
+        Label::Precipitation => "NIEDERSCHLAG",
+        Label::Max => "MAX",
+        Label::Avg => "MITTEL",
+        Label::Min => "MIN",
+        Label::Median => "MEDIAN",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODUS",
+        Label::Days => "TAGE",
+        Label::RainDays => "REGENTAGE",
+        Label::SnowDays => "SCHNEETAGE",
+        Label::Total => "SUMME",
+        Label::SummerDays => "Sommertage",
+        Label::FrostDays => "Frosttage",
+        Label::IceDays => "Eistage",
+        Label::Calm => "WINDSTILLE",
+        Label::North => "N",
+        Label::East => "O",
+        Label::South => "S",
+        Label::West => "W",
+    }
+}
+
+fn french(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPÉRATURE",
+        Label::MeanTemperature => "TEMPÉRATURE MOYENNE",
+        Label::AnnualMeanTemperature => "TEMPÉRATURE MOYENNE ANNUELLE",
+        Label::DailyMeanTemperature => "TEMPÉRATURE MOYENNE JOURNALIÈRE",
+        Label::FeelsLike => "RESSENTI",
+        Label::Wind => "VENT",
+        Label::Precipitation => "PRÉCIPITATIONS",
+        Label::Max => "MAX",
+        Label::Avg => "MOY",
+        Label::Min => "MIN",
+        Label::Median => "MÉDIANE",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODE",
+        Label::Days => "JOURS",
+        Label::RainDays => "JOURS DE PLUIE",
+        Label::SnowDays => "JOURS DE NEIGE",
+        Label::Total => "TOTAL",
+        Label::SummerDays => "jours d'été",
+        Label::FrostDays => "jours de gel",
+        Label::IceDays => "jours sans dégel",
+        Label::Calm => "CALME",
+        Label::North => "N",
+        Label::East => "E",
+        Label::South => "S",
+        Label::West => "O",
+    }
+}
+
+fn spanish(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPERATURA",
+        Label::MeanTemperature => "TEMPERATURA MEDIA",
+        Label::AnnualMeanTemperature => "TEMPERATURA MEDIA ANUAL",
+        Label::DailyMeanTemperature => "TEMPERATURA MEDIA DIARIA",
+        Label::FeelsLike => "SENSACIÓN",
+        Label::Wind => "VIENTO",
+        Label::Precipitation => "PRECIPITACIÓN",
+        Label::Max => "MÁX",
+        Label::Avg => "MEDIA",
+        Label::Min => "MÍN",
+        Label::Median => "MEDIANA",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODA",
+        Label::Days => "DÍAS",
+        Label::RainDays => "DÍAS DE LLUVIA",
+        Label::SnowDays => "DÍAS DE NIEVE",
+        Label::Total => "TOTAL",
+        Label::SummerDays => "días de verano",
+        Label::FrostDays => "días de helada",
+        Label::IceDays => "días de hielo",
+        Label::Calm => "CALMA",
+        Label::North => "N",
+        Label::East => "E",
+        Label::South => "S",
+        Label::West => "O",
+    }
+}
+
+fn italian(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPERATURA",
+        Label::MeanTemperature => "TEMPERATURA MEDIA",
+        Label::AnnualMeanTemperature => "TEMPERATURA MEDIA ANNUALE",
+        Label::DailyMeanTemperature => "TEMPERATURA MEDIA GIORNALIERA",
+        Label::FeelsLike => "PERCEPITA",
+        Label::Wind => "VENTO",
+        Label::Precipitation => "PRECIPITAZIONI",
+        Label::Max => "MAX",
+        Label::Avg => "MEDIA",
+        Label::Min => "MIN",
+        Label::Median => "MEDIANA",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODA",
+        Label::Days => "GIORNI",
+        Label::RainDays => "GIORNI DI PIOGGIA",
+        Label::SnowDays => "GIORNI DI NEVE",
+        Label::Total => "TOTALE",
+        Label::SummerDays => "giorni estivi",
+        Label::FrostDays => "giorni di gelo",
+        Label::IceDays => "giorni di ghiaccio",
+        Label::Calm => "CALMA",
+        Label::North => "N",
+        Label::East => "E",
+        Label::South => "S",
+        Label::West => "O",
+    }
+}
+
+fn dutch(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPERATUUR",
+        Label::MeanTemperature => "GEMIDDELDE TEMPERATUUR",
+        Label::AnnualMeanTemperature => "JAARGEMIDDELDE TEMPERATUUR",
+        Label::DailyMeanTemperature => "DAGGEMIDDELDE TEMPERATUUR",
+        Label::FeelsLike => "GEVOELD",
+        Label::Wind => "WIND",
+        Label::Precipitation => "NEERSLAG",
+        Label::Max => "MAX",
+        Label::Avg => "GEM",
+        Label::Min => "MIN",
+        Label::Median => "MEDIAAN",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODUS",
+        Label::Days => "DAGEN",
+        Label::RainDays => "REGENDAGEN",
+        Label::SnowDays => "SNEEUWDAGEN",
+        Label::Total => "TOTAAL",
+        Label::SummerDays => "zomerse dagen",
+        Label::FrostDays => "vorstdagen",
+        Label::IceDays => "ijsdagen",
+        Label::Calm => "WINDSTIL",
+        Label::North => "N",
+        Label::East => "O",
+        Label::South => "Z",
+        Label::West => "W",
+    }
+}
+
+fn portuguese(label: Label) -> &'static str {
+    match label {
+        Label::Temperature => "TEMPERATURA",
+        Label::MeanTemperature => "TEMPERATURA MÉDIA",
+        Label::AnnualMeanTemperature => "TEMPERATURA MÉDIA ANUAL",
+        Label::DailyMeanTemperature => "TEMPERATURA MÉDIA DIÁRIA",
+        Label::FeelsLike => "SENSAÇÃO",
+        Label::Wind => "VENTO",
+        Label::Precipitation => "PRECIPITAÇÃO",
+        Label::Max => "MÁX",
+        Label::Avg => "MÉDIA",
+        Label::Min => "MÍN",
+        Label::Median => "MEDIANA",
+        Label::P10 => "P10",
+        Label::P90 => "P90",
+        Label::Mode => "MODA",
+        Label::Days => "DIAS",
+        Label::RainDays => "DIAS DE CHUVA",
+        Label::SnowDays => "DIAS DE NEVE",
+        Label::Total => "TOTAL",
+        Label::SummerDays => "dias de verão",
+        Label::FrostDays => "dias de geada",
+        Label::IceDays => "dias de gelo",
+        Label::Calm => "CALMARIA",
+        Label::North => "N",
+        Label::East => "L",
+        Label::South => "S",
+        Label::West => "O",
+    }
+}
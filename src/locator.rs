@@ -0,0 +1,460 @@
+use super::{chart, gsod::Location, TAU};
+use cairo::Context;
+use std::error::Error;
+
+// The coastlines of the land masses as (lng, lat) rings, simplified to a few degrees, which is
+// as much as a globe the size of the header can show.
+const LAND: &[&[(f64, f64)]] = &[
+    // North America
+    &[
+        (-168.0, 66.0),
+        (-162.0, 70.0),
+        (-156.0, 71.0),
+        (-140.0, 70.0),
+        (-128.0, 70.0),
+        (-115.0, 68.0),
+        (-95.0, 72.0),
+        (-82.0, 70.0),
+        (-78.0, 62.0),
+        (-94.0, 59.0),
+        (-90.0, 57.0),
+        (-80.0, 52.0),
+        (-78.0, 58.0),
+        (-70.0, 60.0),
+        (-64.0, 60.0),
+        (-56.0, 52.0),
+        (-60.0, 47.0),
+        (-66.0, 44.0),
+        (-70.0, 42.0),
+        (-74.0, 40.0),
+        (-76.0, 35.0),
+        (-81.0, 31.0),
+        (-80.0, 25.0),
+        (-82.0, 27.0),
+        (-84.0, 30.0),
+        (-90.0, 29.0),
+        (-97.0, 28.0),
+        (-97.0, 22.0),
+        (-94.0, 18.0),
+        (-91.0, 19.0),
+        (-87.0, 21.0),
+        (-88.0, 16.0),
+        (-83.0, 15.0),
+        (-83.0, 10.0),
+        (-79.0, 9.0),
+        (-78.0, 8.0),
+        (-80.0, 7.0),
+        (-85.0, 10.0),
+        (-87.0, 13.0),
+        (-92.0, 15.0),
+        (-96.0, 16.0),
+        (-105.0, 20.0),
+        (-106.0, 23.0),
+        (-112.0, 29.0),
+        (-110.0, 23.0),
+        (-115.0, 30.0),
+        (-117.0, 33.0),
+        (-121.0, 35.0),
+        (-124.0, 40.0),
+        (-124.0, 46.0),
+        (-124.0, 49.0),
+        (-128.0, 51.0),
+        (-133.0, 55.0),
+        (-137.0, 58.0),
+        (-146.0, 61.0),
+        (-152.0, 59.0),
+        (-158.0, 56.0),
+        (-164.0, 55.0),
+        (-158.0, 58.0),
+        (-162.0, 60.0),
+        (-166.0, 62.0),
+        (-164.0, 64.0),
+    ],
+    // Baffin Island
+    &[(-80.0, 73.0), (-62.0, 67.0), (-65.0, 62.0), (-78.0, 64.0)],
+    // Greenland
+    &[
+        (-73.0, 78.0),
+        (-60.0, 82.0),
+        (-32.0, 84.0),
+        (-18.0, 80.0),
+        (-20.0, 72.0),
+        (-22.0, 70.0),
+        (-32.0, 68.0),
+        (-42.0, 60.0),
+        (-48.0, 61.0),
+        (-53.0, 66.0),
+        (-56.0, 72.0),
+        (-66.0, 76.0),
+    ],
+    // Iceland
+    &[
+        (-24.0, 65.0),
+        (-22.0, 66.0),
+        (-15.0, 66.0),
+        (-14.0, 65.0),
+        (-18.0, 63.0),
+        (-22.0, 64.0),
+    ],
+    // South America
+    &[
+        (-78.0, 8.0),
+        (-72.0, 12.0),
+        (-62.0, 11.0),
+        (-52.0, 5.0),
+        (-50.0, 0.0),
+        (-44.0, -2.0),
+        (-35.0, -5.0),
+        (-39.0, -13.0),
+        (-41.0, -22.0),
+        (-48.0, -26.0),
+        (-53.0, -34.0),
+        (-58.0, -38.0),
+        (-65.0, -41.0),
+        (-68.0, -50.0),
+        (-69.0, -55.0),
+        (-73.0, -53.0),
+        (-75.0, -46.0),
+        (-73.0, -38.0),
+        (-71.0, -30.0),
+        (-70.0, -18.0),
+        (-76.0, -14.0),
+        (-81.0, -6.0),
+        (-80.0, -1.0),
+        (-78.0, 2.0),
+        (-77.0, 7.0),
+    ],
+    // Europe and Asia
+    &[
+        (-9.0, 37.0),
+        (-9.0, 43.0),
+        (-2.0, 43.0),
+        (-1.0, 46.0),
+        (-4.0, 48.0),
+        (2.0, 51.0),
+        (5.0, 53.0),
+        (8.0, 54.0),
+        (8.0, 57.0),
+        (11.0, 58.0),
+        (5.0, 59.0),
+        (5.0, 62.0),
+        (14.0, 67.0),
+        (22.0, 70.0),
+        (28.0, 71.0),
+        (41.0, 67.0),
+        (44.0, 68.0),
+        (54.0, 69.0),
+        (60.0, 70.0),
+        (69.0, 73.0),
+        (80.0, 73.0),
+        (87.0, 75.0),
+        (100.0, 78.0),
+        (113.0, 74.0),
+        (130.0, 71.0),
+        (140.0, 72.0),
+        (160.0, 70.0),
+        (180.0, 69.0),
+        (180.0, 65.0),
+        (172.0, 60.0),
+        (162.0, 59.0),
+        (156.0, 51.0),
+        (150.0, 59.0),
+        (142.0, 59.0),
+        (135.0, 55.0),
+        (141.0, 52.0),
+        (140.0, 48.0),
+        (133.0, 43.0),
+        (129.0, 41.0),
+        (129.0, 35.0),
+        (126.0, 35.0),
+        (126.0, 38.0),
+        (121.0, 40.0),
+        (122.0, 37.0),
+        (119.0, 35.0),
+        (121.0, 31.0),
+        (122.0, 29.0),
+        (119.0, 25.0),
+        (114.0, 22.0),
+        (109.0, 21.0),
+        (106.0, 19.0),
+        (109.0, 15.0),
+        (109.0, 12.0),
+        (105.0, 9.0),
+        (101.0, 13.0),
+        (99.0, 10.0),
+        (103.0, 1.0),
+        (98.0, 8.0),
+        (98.0, 16.0),
+        (94.0, 17.0),
+        (92.0, 22.0),
+        (89.0, 22.0),
+        (86.0, 20.0),
+        (80.0, 15.0),
+        (80.0, 10.0),
+        (77.0, 8.0),
+        (73.0, 16.0),
+        (72.0, 21.0),
+        (67.0, 24.0),
+        (62.0, 25.0),
+        (57.0, 26.0),
+        (56.0, 27.0),
+        (51.0, 28.0),
+        (48.0, 30.0),
+        (50.0, 26.0),
+        (52.0, 24.0),
+        (56.0, 24.0),
+        (59.0, 22.0),
+        (55.0, 17.0),
+        (52.0, 16.0),
+        (45.0, 13.0),
+        (43.0, 13.0),
+        (39.0, 21.0),
+        (35.0, 28.0),
+        (34.0, 31.0),
+        (35.0, 33.0),
+        (36.0, 36.0),
+        (30.0, 36.0),
+        (27.0, 37.0),
+        (26.0, 41.0),
+        (23.0, 40.0),
+        (23.0, 37.0),
+        (21.0, 39.0),
+        (19.0, 42.0),
+        (14.0, 45.0),
+        (13.0, 45.0),
+        (16.0, 42.0),
+        (18.0, 40.0),
+        (16.0, 38.0),
+        (12.0, 42.0),
+        (9.0, 44.0),
+        (7.0, 44.0),
+        (3.0, 43.0),
+        (3.0, 42.0),
+        (0.0, 40.0),
+        (-1.0, 37.0),
+        (-5.0, 36.0),
+    ],
+    // Great Britain
+    &[
+        (-5.0, 50.0),
+        (1.0, 51.0),
+        (2.0, 53.0),
+        (-3.0, 56.0),
+        (-2.0, 58.0),
+        (-5.0, 59.0),
+        (-6.0, 56.0),
+        (-5.0, 55.0),
+        (-3.0, 54.0),
+        (-5.0, 52.0),
+    ],
+    // Ireland
+    &[(-6.0, 52.0), (-6.0, 55.0), (-10.0, 54.0), (-10.0, 52.0)],
+    // Japan
+    &[
+        (130.0, 31.0),
+        (132.0, 34.0),
+        (135.0, 34.0),
+        (140.0, 35.0),
+        (142.0, 40.0),
+        (141.0, 45.0),
+        (145.0, 44.0),
+        (140.0, 42.0),
+        (139.0, 38.0),
+        (136.0, 37.0),
+        (133.0, 35.0),
+        (130.0, 33.0),
+    ],
+    // Africa
+    &[
+        (-17.0, 21.0),
+        (-17.0, 15.0),
+        (-15.0, 11.0),
+        (-8.0, 4.0),
+        (0.0, 5.0),
+        (5.0, 6.0),
+        (9.0, 4.0),
+        (10.0, -2.0),
+        (12.0, -6.0),
+        (13.0, -12.0),
+        (12.0, -17.0),
+        (15.0, -27.0),
+        (18.0, -34.0),
+        (20.0, -35.0),
+        (26.0, -34.0),
+        (33.0, -28.0),
+        (35.0, -24.0),
+        (40.0, -16.0),
+        (40.0, -10.0),
+        (39.0, -5.0),
+        (42.0, -1.0),
+        (47.0, 2.0),
+        (51.0, 11.0),
+        (44.0, 11.0),
+        (43.0, 13.0),
+        (39.0, 15.0),
+        (37.0, 20.0),
+        (35.0, 25.0),
+        (32.0, 30.0),
+        (30.0, 31.0),
+        (20.0, 32.0),
+        (20.0, 30.0),
+        (12.0, 33.0),
+        (10.0, 37.0),
+        (3.0, 37.0),
+        (-2.0, 35.0),
+        (-6.0, 36.0),
+        (-10.0, 30.0),
+        (-13.0, 27.0),
+    ],
+    // Madagascar
+    &[
+        (44.0, -25.0),
+        (47.0, -25.0),
+        (50.0, -15.0),
+        (49.0, -12.0),
+        (44.0, -16.0),
+    ],
+    // Sumatra, Java, Borneo and New Guinea
+    &[(95.0, 5.0), (106.0, -6.0), (102.0, -4.0)],
+    &[(106.0, -6.0), (114.0, -7.0), (114.0, -8.0), (106.0, -7.0)],
+    &[
+        (109.0, 2.0),
+        (117.0, 7.0),
+        (119.0, 1.0),
+        (116.0, -4.0),
+        (110.0, -3.0),
+    ],
+    &[
+        (131.0, -1.0),
+        (141.0, -3.0),
+        (151.0, -10.0),
+        (142.0, -9.0),
+        (138.0, -8.0),
+    ],
+    // Australia
+    &[
+        (114.0, -22.0),
+        (114.0, -34.0),
+        (117.0, -35.0),
+        (124.0, -34.0),
+        (131.0, -31.0),
+        (138.0, -35.0),
+        (140.0, -38.0),
+        (147.0, -39.0),
+        (150.0, -37.0),
+        (153.0, -32.0),
+        (153.0, -25.0),
+        (146.0, -19.0),
+        (142.0, -11.0),
+        (141.0, -17.0),
+        (136.0, -12.0),
+        (131.0, -11.0),
+        (126.0, -14.0),
+        (122.0, -18.0),
+    ],
+    // New Zealand
+    &[
+        (172.0, -34.0),
+        (178.0, -38.0),
+        (175.0, -41.0),
+        (174.0, -39.0),
+    ],
+    &[
+        (172.0, -41.0),
+        (174.0, -42.0),
+        (171.0, -44.0),
+        (167.0, -46.0),
+    ],
+    // Antarctica, closed off around the pole.
+    &[
+        (-180.0, -90.0),
+        (-180.0, -72.0),
+        (-150.0, -75.0),
+        (-120.0, -73.0),
+        (-90.0, -72.0),
+        (-60.0, -65.0),
+        (-30.0, -77.0),
+        (0.0, -70.0),
+        (30.0, -69.0),
+        (60.0, -67.0),
+        (90.0, -66.0),
+        (120.0, -66.0),
+        (150.0, -68.0),
+        (180.0, -72.0),
+        (180.0, -90.0),
+    ],
+];
+
+// The most degrees between the points of a coastline as it is drawn, so its long straight
+// runs still curve with the globe.
+const STEP: f64 = 2.0;
+
+// Projects a point on the globe onto a unit disc looking down on the center, with the points
+// on the far side pushed out to its edge.
+fn project((lng, lat): (f64, f64), center: &Location) -> (f64, f64) {
+    let (lat, dlng) = (lat.to_radians(), (lng - center.lng()).to_radians());
+    let lat0 = center.lat().to_radians();
+    let x = lat.cos() * dlng.sin();
+    let y = lat0.cos() * lat.sin() - lat0.sin() * lat.cos() * dlng.cos();
+    let near = lat0.sin() * lat.sin() + lat0.cos() * lat.cos() * dlng.cos() >= 0.0;
+    match near {
+        true => (x, -y),
+        false => {
+            let d = x.hypot(y).max(f64::EPSILON);
+            (x / d, -y / d)
+        }
+    }
+}
+
+// Renders a small globe turned to face the station, at cx, cy with radius r, with a dot
+// where the station is.
+pub(crate) fn render(
+    ctx: &Context,
+    cx: f64,
+    cy: f64,
+    r: f64,
+    loc: &Location,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    ctx.save()?;
+    ctx.translate(cx, cy);
+
+    ctx.new_path();
+    ctx.arc(0.0, 0.0, r, 0.0, TAU);
+    opts.theme.foreground.with_alpha(0.08).set(ctx);
+    ctx.fill_preserve()?;
+    ctx.clip();
+
+    opts.theme.foreground.with_alpha(0.35).set(ctx);
+    for ring in LAND {
+        ctx.new_path();
+        for (i, a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            let n = ((b.0 - a.0).abs().max((b.1 - a.1).abs()) / STEP)
+                .ceil()
+                .max(1.0) as usize;
+            for j in 0..n {
+                let t = j as f64 / n as f64;
+                let (x, y) = project((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t), loc);
+                ctx.line_to(x * r, y * r);
+            }
+        }
+        ctx.close_path();
+        ctx.fill()?;
+    }
+    ctx.reset_clip();
+
+    opts.theme.foreground.with_alpha(0.4).set(ctx);
+    ctx.set_line_width(1.0);
+    ctx.new_path();
+    ctx.arc(0.0, 0.0, r, 0.0, TAU);
+    ctx.stroke()?;
+
+    opts.theme.temperature_line.set(ctx);
+    ctx.new_path();
+    ctx.arc(0.0, 0.0, (r / 12.0).max(2.0), 0.0, TAU);
+    ctx.fill()?;
+
+    ctx.restore()?;
+    Ok(())
+}
@@ -1,7 +1,16 @@
-use clap::{Parser, Subcommand};
+#[cfg(any(feature = "native", feature = "skia"))]
+use clap::Parser;
+#[cfg(feature = "native")]
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Subcommand};
 use std::error::Error;
-use weather_banner::{list_stations, render, Data};
+#[cfg(feature = "native")]
+use weather_banner::{
+    export,
+    fetch::{ReqwestFetcher, Settings},
+    list_stations, prefetch, render, report, sparkline, stats, trend, update, Data,
+};
 
+#[cfg(feature = "native")]
 #[derive(Parser, Debug)]
 struct Args {
     #[command(subcommand)]
@@ -9,27 +18,103 @@ struct Args {
 
     #[clap(long, default_value_t = String::from("data"))]
     data_dir: String,
+
+    /// Fetch NOAA's files from a mirror, a URL in which {path} is the path of a file on
+    /// ncei.noaa.gov and {file} its name, e.g. file:///srv/gsod/{file}.
+    #[clap(long, env = "WEATHER_BANNER_MIRROR")]
+    mirror: Option<String>,
+
+    #[command(flatten)]
+    network: Settings,
 }
 
+// parsed once at startup, so the size of the render args doesn't matter.
+#[allow(clippy::large_enum_variant)]
+#[cfg(feature = "native")]
 #[derive(Subcommand, Debug)]
 enum Command {
     Render(render::Args),
+    /// Render a PDF of a year: the banner, then a page for each month with a table of its
+    /// days and a ring of their temperatures.
+    Report(report::Args),
     ListStations(list_stations::Args),
+    Stats(stats::Args),
+    /// Print sparklines of a station's year, for a quick look at the data.
+    Sparkline(sparkline::Args),
+    /// Print a station's annual mean temperatures over a span of years and their trend.
+    Trend(trend::Args),
+    Export(export::Args),
+    /// Download the yearly archives ahead of time.
+    #[command(name = "fetch")]
+    Prefetch(prefetch::Args),
+    /// Download newer archives and render again if they changed, for running from cron.
+    Update(update::Args),
+    /// Load the archives into a SQLite database and query it.
+    #[cfg(feature = "store")]
+    Store(weather_banner::store::Args),
+    /// Show a render in a window that renders again when its --config file changes.
+    #[cfg(feature = "gui")]
+    Preview(weather_banner::window::Args),
 }
 
+#[cfg(feature = "native")]
 impl Command {
-    fn execute(&self, data: &Data) -> Result<(), Box<dyn Error>> {
+    // the matches are for the commands that read --config themselves.
+    fn execute(&self, data: &Data, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
         match self {
             Command::Render(args) => render::execute(data, args),
+            Command::Report(args) => report::execute(data, args, matches),
             Command::ListStations(args) => list_stations::execute(data, args),
+            Command::Stats(args) => stats::execute(data, args),
+            Command::Sparkline(args) => sparkline::execute(data, args),
+            Command::Trend(args) => trend::execute(data, args),
+            Command::Export(args) => export::execute(data, args),
+            Command::Prefetch(args) => prefetch::execute(data, args),
+            Command::Update(args) => update::execute(data, args, matches),
+            #[cfg(feature = "store")]
+            Command::Store(args) => weather_banner::store::execute(data, args),
+            #[cfg(feature = "gui")]
+            Command::Preview(args) => weather_banner::window::execute(data, args, matches),
         }
     }
 }
 
+#[cfg(feature = "native")]
 fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches)?;
+    let (_, command_matches) = matches.subcommand().ok_or("no command given")?;
+    if let Command::Render(render_args) = &mut args.command {
+        *render_args = render_args.clone().with_config(command_matches)?;
+    }
 
-    let data = Data::from(&args.data_dir)?;
-    args.command.execute(&data)?;
+    let data = Data::with_fetcher(
+        &args.data_dir,
+        Box::new(ReqwestFetcher::new(&args.network)?),
+    )?
+    .with_mirror(args.mirror.clone());
+    args.command.execute(&data, command_matches)?;
     Ok(())
 }
+
+// Without cairo, the only command is render, which draws a banner from a CSV with tiny-skia.
+#[cfg(all(feature = "skia", not(feature = "native")))]
+#[derive(Parser, Debug)]
+enum Command {
+    Render(weather_banner::raster::Args),
+}
+
+#[cfg(all(feature = "skia", not(feature = "native")))]
+fn main() -> Result<(), Box<dyn Error>> {
+    match Command::parse() {
+        Command::Render(args) => weather_banner::raster::execute(&args),
+    }
+}
+
+#[cfg(not(any(feature = "native", feature = "skia")))]
+fn main() -> Result<(), Box<dyn Error>> {
+    Err(
+        "weather-banner was built without a renderer, build it with the native or skia feature"
+            .into(),
+    )
+}
@@ -0,0 +1,93 @@
+use super::{
+    gsod::{Day, Observations, Station},
+    source::{self, DataSource},
+    time, Data,
+};
+use chrono::{Datelike, Local, NaiveDate};
+use flate2::read::GzDecoder;
+use std::error::Error;
+
+// Meteostat's bulk data, a gzipped CSV for each station with every day it has data for.
+// Stations are Meteostat ids, which are WMO ids for most stations (e.g. 72306).
+pub(crate) struct Meteostat<'a> {
+    data: &'a Data,
+}
+
+fn url_for(id: &str) -> String {
+    format!("https://bulk.meteostat.net/v2/daily/{}.csv.gz", id)
+}
+
+impl Meteostat<'_> {
+    pub(crate) fn new(data: &Data) -> Meteostat<'_> {
+        Meteostat { data }
+    }
+
+    fn history(&self, id: &str) -> Result<Station, Box<dyn Error>> {
+        let r = self
+            .data
+            .download_and_open(&url_for(id), format!("meteostat-{}.csv.gz", id))?;
+        let mut r = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(GzDecoder::new(r));
+        let mut days = Vec::new();
+        for record in r.records() {
+            days.push(day_from(&record?)?);
+        }
+        Ok(Station::new(String::from(id), None, None, None, days))
+    }
+}
+
+impl DataSource for Meteostat<'_> {
+    fn name(&self) -> &'static str {
+        "Meteostat"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        Ok(Local::now().year() - 1)
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        source::year_of(&self.history(id)?, year)
+    }
+
+    fn stations(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        Ok(source::years_of(&self.history(id)?, years))
+    }
+}
+
+// Maps a row of date, tavg, tmin, tmax (°C), prcp (mm), snow (depth in mm), wdir, wspd,
+// wpgt (km/h), pres (hPa) and tsun onto a day.
+fn day_from(rec: &csv::StringRecord) -> Result<Day, Box<dyn Error>> {
+    let field = |i: usize| -> Result<Option<f64>, Box<dyn Error>> {
+        match rec.get(i).map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(v) => Ok(Some(v.parse::<f64>()?)),
+        }
+    };
+    let fahrenheit = |c: f64| c * 9.0 / 5.0 + 32.0;
+    let inches = |mm: f64| mm / 25.4;
+    let knots = |kmh: f64| kmh / 1.852;
+
+    let date = NaiveDate::parse_from_str(rec.get(0).ok_or("missing date")?, "%Y-%m-%d")?;
+    Ok(Day::new(
+        date,
+        Observations {
+            mean_temperature: field(1)?.map(fahrenheit),
+            min_temperature: field(2)?.map(fahrenheit),
+            max_temperature: field(3)?.map(fahrenheit),
+            precipitation: field(4)?.map(inches),
+            snow_depth: field(5)?.map(inches),
+            mean_wind: field(7)?.map(knots),
+            // there is no maximum sustained wind, so the peak gust stands in for it and the
+            // wind panel reaches up to the gusts instead.
+            max_sustained_wind: field(8)?.map(knots),
+            max_wind_gust: field(8)?.map(knots),
+            mean_sea_level_pressure: field(9)?,
+            ..Default::default()
+        },
+    ))
+}
@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+// Everything that goes into drawing a radial banner, prepared so that it can be drawn again
+// by something else. Values along a ring are normalized to the range of the panel, so 0 is
+// the inner edge of the ring and 1 the outer edge.
+#[derive(Debug, Serialize)]
+pub(crate) struct Chart {
+    pub(crate) stations: Vec<Station>,
+    pub(crate) year: i32,
+    pub(crate) days: usize,
+    pub(crate) months: Vec<Month>,
+    pub(crate) panels: Vec<Panel>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Station {
+    pub(crate) id: String,
+    pub(crate) name: String,
+}
+
+// A month as a fraction of the way around the year.
+#[derive(Debug, Serialize)]
+pub(crate) struct Month {
+    pub(crate) start: f64,
+    pub(crate) end: f64,
+    pub(crate) label: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Panel {
+    pub(crate) panel: String,
+    pub(crate) title: String,
+    pub(crate) units: String,
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+    pub(crate) scale: Vec<Step>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) band: Option<Band>,
+    pub(crate) series: Vec<Series>,
+    pub(crate) stats: Vec<Stat>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Step {
+    pub(crate) value: f64,
+    pub(crate) position: f64,
+    pub(crate) label: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Band {
+    pub(crate) low: Vec<f64>,
+    pub(crate) high: Vec<f64>,
+}
+
+// A series of a station in a panel. A range is drawn as a band between the low and high
+// values, a line or bars through the values.
+#[derive(Debug, Serialize)]
+pub(crate) struct Series {
+    pub(crate) name: &'static str,
+    pub(crate) station: String,
+    pub(crate) kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) low: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) high: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) values: Option<Vec<f64>>,
+}
+
+impl Series {
+    pub(crate) fn range(
+        name: &'static str,
+        station: &str,
+        low: &super::Series,
+        high: &super::Series,
+    ) -> Series {
+        Series {
+            name,
+            station: String::from(station),
+            kind: "range",
+            low: Some(normalized(low)),
+            high: Some(normalized(high)),
+            values: None,
+        }
+    }
+
+    pub(crate) fn line(name: &'static str, station: &str, series: &super::Series) -> Series {
+        Series {
+            name,
+            station: String::from(station),
+            kind: "line",
+            low: None,
+            high: None,
+            values: Some(normalized(series)),
+        }
+    }
+
+    pub(crate) fn bars(name: &'static str, station: &str, series: &super::Series) -> Series {
+        Series {
+            kind: "bars",
+            ..Series::line(name, station, series)
+        }
+    }
+}
+
+// The labelled values shown in the center of a panel, formatted for the locale.
+#[derive(Debug, Serialize)]
+pub(crate) struct Stat {
+    pub(crate) label: String,
+    pub(crate) value: String,
+}
+
+pub(crate) fn normalized(series: &super::Series) -> Vec<f64> {
+    series.normalize().map(|u| u.value()).collect()
+}
@@ -0,0 +1,147 @@
+use super::{
+    gsod::{Day, Indicators, Location, Observations, Station},
+    source::DataSource,
+    time, Data,
+};
+use chrono::{Datelike, Local, NaiveDate};
+use serde::Deserialize;
+use std::error::Error;
+use std::io;
+
+const URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+
+const DAILY: [&str; 10] = [
+    "temperature_2m_mean",
+    "temperature_2m_max",
+    "temperature_2m_min",
+    "dew_point_2m_mean",
+    "wind_speed_10m_mean",
+    "wind_speed_10m_max",
+    "wind_gusts_10m_max",
+    "precipitation_sum",
+    "snowfall_sum",
+    "weather_code",
+];
+
+// The Open-Meteo historical weather API, which has modelled data for any point on land. A
+// station is a location given as "lat,lng" and the data is requested in GSOD's units.
+pub(crate) struct OpenMeteo<'a> {
+    data: &'a Data,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    latitude: f64,
+    longitude: f64,
+    elevation: Option<f64>,
+    daily: Daily,
+}
+
+#[derive(Debug, Deserialize)]
+struct Daily {
+    time: Vec<NaiveDate>,
+    temperature_2m_mean: Vec<Option<f64>>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    dew_point_2m_mean: Vec<Option<f64>>,
+    wind_speed_10m_mean: Vec<Option<f64>>,
+    wind_speed_10m_max: Vec<Option<f64>>,
+    wind_gusts_10m_max: Vec<Option<f64>>,
+    precipitation_sum: Vec<Option<f64>>,
+    snowfall_sum: Vec<Option<f64>>,
+    weather_code: Vec<Option<u32>>,
+}
+
+impl OpenMeteo<'_> {
+    pub(crate) fn new(data: &Data) -> OpenMeteo<'_> {
+        OpenMeteo { data }
+    }
+}
+
+impl DataSource for OpenMeteo<'_> {
+    fn name(&self) -> &'static str {
+        "Open-Meteo"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        Ok(Local::now().year() - 1)
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        let loc = parse_location(id)?;
+        let url = format!(
+            concat!(
+                "{}?latitude={:.4}&longitude={:.4}&start_date={}&end_date={}&daily={}",
+                "&temperature_unit=fahrenheit&wind_speed_unit=kn&precipitation_unit=inch",
+                "&timezone=auto"
+            ),
+            URL,
+            loc.lat(),
+            loc.lng(),
+            year.start().format("%Y-%m-%d"),
+            (year.end() - chrono::Duration::days(1)).format("%Y-%m-%d"),
+            DAILY.join(","),
+        );
+        let dst = format!("open-meteo-{:.4}_{:.4}-{}.json", loc.lat(), loc.lng(), year);
+        let res: Response =
+            serde_json::from_reader(io::BufReader::new(self.data.download_and_open(&url, dst)?))?;
+        station_from(id, &res)
+    }
+}
+
+fn parse_location(id: &str) -> Result<Location, Box<dyn Error>> {
+    let (lat, lng) = id
+        .split_once(',')
+        .ok_or_else(|| format!("expected a location as lat,lng: {}", id))?;
+    Ok(Location::new(lat.trim().parse()?, lng.trim().parse()?))
+}
+
+fn station_from(id: &str, res: &Response) -> Result<Station, Box<dyn Error>> {
+    let daily = &res.daily;
+    let at = |vals: &[Option<f64>], i: usize| vals.get(i).copied().flatten();
+    let mut days = Vec::with_capacity(daily.time.len());
+    for (i, date) in daily.time.iter().enumerate() {
+        let code = daily.weather_code.get(i).copied().flatten();
+        let snowfall = at(&daily.snowfall_sum, i);
+        days.push(Day::new(
+            *date,
+            Observations {
+                mean_temperature: at(&daily.temperature_2m_mean, i),
+                max_temperature: at(&daily.temperature_2m_max, i),
+                min_temperature: at(&daily.temperature_2m_min, i),
+                mean_dewpoint: at(&daily.dew_point_2m_mean, i),
+                mean_sea_level_pressure: None,
+                mean_wind: at(&daily.wind_speed_10m_mean, i),
+                max_sustained_wind: at(&daily.wind_speed_10m_max, i),
+                max_wind_gust: at(&daily.wind_gusts_10m_max, i),
+                precipitation: at(&daily.precipitation_sum, i),
+                snow_depth: None,
+                indicators: indicators_of(code, snowfall),
+                // the daily values are aggregated from hourly ones.
+                samples: 24,
+            },
+        ));
+    }
+
+    let loc = Location::new(res.latitude, res.longitude);
+    Ok(Station::new(
+        String::from(id),
+        Some(format!("{}", loc)),
+        Some(loc),
+        res.elevation,
+        days,
+    ))
+}
+
+// Maps the most severe WMO weather code of a day onto the GSOD indicators.
+fn indicators_of(code: Option<u32>, snowfall: Option<f64>) -> Indicators {
+    let code = code.unwrap_or(0);
+    Indicators::from_flags([
+        matches!(code, 45 | 48),
+        matches!(code, 51..=67 | 80..=82),
+        matches!(code, 71..=77 | 85 | 86) || snowfall.is_some_and(|s| s > 0.0),
+        matches!(code, 96 | 99),
+        matches!(code, 95..=99),
+        false,
+    ])
+}
@@ -0,0 +1,416 @@
+use super::{
+    chart::{self, Panel},
+    feels_like,
+    gsod::{self, Station},
+    locale::Label,
+    render, time, Color, Font, Range, Scale, Series, TAU,
+};
+use cairo::{Context, FontSlant, FontWeight};
+use std::error::Error;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Style {
+    /// A ring of the days of the month for each of the panels, side by side.
+    Radial,
+    /// A row of the days of the month for each of the panels, one above the other.
+    Linear,
+}
+
+// The days of the month of one of the panels, drawn as a band between two series, a line
+// through another or bars up from nothing, whichever of them the panel has.
+struct Track {
+    panel: Panel,
+    range: Range,
+    band: Option<(Series, Series)>,
+    line: Option<Series>,
+    bars: Option<Series>,
+    units: &'static str,
+    color: Color,
+    stats: Vec<(String, String)>,
+}
+
+fn track(month: time::Month, station: &Station, panel: Panel, opts: &chart::Options) -> Track {
+    let series = |f: fn(&gsod::Day) -> Option<f64>| {
+        Series::for_each_day_of_month(month, station.days().iter(), f)
+    };
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v: f64, units| format!("{}{}", opts.locale.number(v, 1), units);
+    // a panel without any days has nothing to size its scale to, and one whose days are all
+    // the same has a scale without any width.
+    let checked = |range: Range| match range.min() <= range.max() {
+        true => chart::widened(range),
+        false => Range::new(0.0, 1.0),
+    };
+
+    match panel {
+        Panel::Temperature => {
+            let min = series(|day| day.min_temperature().map(|t| t.in_fahrenheit()));
+            let max = series(|day| day.max_temperature().map(|t| t.in_fahrenheit()));
+            let mean = series(|day| day.mean_temperature().map(|t| t.in_fahrenheit()));
+            let range = opts
+                .temperature_range
+                .clone()
+                .unwrap_or_else(|| checked(Range::union(min.range(), max.range())));
+            let stats = vec![
+                (label(Label::Max), num(max.summarize().max(), "°F")),
+                (label(Label::Avg), num(mean.summarize().mean(), "°F")),
+                (label(Label::Min), num(min.summarize().min(), "°F")),
+            ];
+            Track {
+                panel,
+                band: Some((min.with_range(&range), max.with_range(&range))),
+                line: Some(mean.with_range(&range)),
+                bars: None,
+                units: "°F",
+                color: opts.theme.temperature_line,
+                stats,
+                range,
+            }
+        }
+        Panel::FeelsLike => {
+            let mean = series(|day| day.mean_temperature().map(|t| t.in_fahrenheit()));
+            let feels = series(feels_like);
+            let range = opts
+                .temperature_range
+                .clone()
+                .unwrap_or_else(|| checked(Range::union(mean.range(), feels.range())));
+            let summary = feels.summarize();
+            let stats = vec![
+                (label(Label::Max), num(summary.max(), "°F")),
+                (label(Label::Avg), num(summary.mean(), "°F")),
+                (label(Label::Min), num(summary.min(), "°F")),
+            ];
+            Track {
+                panel,
+                band: Some((mean.with_range(&range), feels.with_range(&range))),
+                line: Some(series(feels_like).with_range(&range)),
+                bars: None,
+                units: "°F",
+                color: opts.theme.feels_like,
+                stats,
+                range,
+            }
+        }
+        Panel::Wind => {
+            let mean = series(|day| day.mean_wind().map(|s| s.in_knots()));
+            let max = series(|day| day.max_sustained_wind().map(|s| s.in_knots()));
+            let range = opts
+                .wind_range
+                .clone()
+                .unwrap_or_else(|| checked(Range::union(mean.range(), max.range())));
+            let stats = vec![
+                (label(Label::Max), num(max.summarize().max(), " kts")),
+                (label(Label::Avg), num(mean.summarize().mean(), " kts")),
+            ];
+            Track {
+                panel,
+                line: Some(series(|day| day.mean_wind().map(|s| s.in_knots())).with_range(&range)),
+                band: Some((mean.with_range(&range), max.with_range(&range))),
+                bars: None,
+                units: " kts",
+                color: opts.theme.wind,
+                stats,
+                range,
+            }
+        }
+        Panel::Precipitation => {
+            let precip = series(|day| Some(day.precipitation().map_or(0.0, |p| p.in_inches())));
+            let range = opts
+                .precipitation_range
+                .clone()
+                .unwrap_or_else(|| checked(Range::new(0.0, precip.range().max())));
+            let summary = precip.summarize();
+            let stats = vec![
+                (label(Label::Total), num(summary.sum(), " in")),
+                (label(Label::Max), num(summary.max(), " in")),
+            ];
+            Track {
+                panel,
+                band: None,
+                line: None,
+                bars: Some(precip),
+                units: " in",
+                color: opts.theme.precipitation,
+                stats,
+                range,
+            }
+        }
+    }
+}
+
+// Renders a single month of a station at daily resolution, with a ring or a row of its days
+// for each of the panels.
+pub(crate) fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    month: time::Month,
+    station: &Station,
+    style: Style,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let header_height = render::render_header(
+        ctx,
+        station,
+        &opts.locale.format(month.start(), "%B %Y"),
+        width,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let in_month = |date| date >= month.start() && date < month.end();
+    if !station.days().iter().any(|day| in_month(day.date())) {
+        return Err(format!("no days of station {} in {}", station.id(), month).into());
+    }
+
+    let tracks = opts
+        .panels
+        .iter()
+        .map(|panel| track(month, station, *panel, opts))
+        .collect::<Vec<_>>();
+    let dates = month.days().map(|day| day.date()).collect::<Vec<_>>();
+    match style {
+        Style::Radial => render_rings(ctx, width, height, header_height, &tracks, &dates, opts),
+        Style::Linear => render_rows(ctx, width, height, header_height, &tracks, &dates, opts),
+    }
+}
+
+// Draws each track as a ring of the days clockwise from the top, like a panel of the banner
+// with a day where it has a month.
+fn render_rings(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    header_height: f64,
+    tracks: &[Track],
+    dates: &[chrono::NaiveDate],
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let body_height = height - header_height;
+    let n = tracks.len() as f64;
+    let r = (width / (2.0 * n)).min(body_height / 2.0);
+    let rrange = Range::new(r * opts.radii.min(), r * opts.radii.max());
+    let dt = TAU / dates.len() as f64;
+    let angle = |i: usize| (i as f64 + 0.5) * dt - TAU / 4.0;
+
+    for (j, track) in tracks.iter().enumerate() {
+        ctx.save()?;
+        ctx.translate(
+            (2 * j + 1) as f64 * width / (2.0 * n),
+            header_height + body_height / 2.0,
+        );
+        render::render_title(
+            ctx,
+            opts.locale.label(track.panel.title()),
+            0.0,
+            -rrange.max() - 10.0,
+            opts,
+        )?;
+
+        // the number of the first and every fifth day just inside of the ring.
+        ctx.save()?;
+        Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 9.0).set(ctx);
+        opts.theme.foreground.with_alpha(0.6).set(ctx);
+        for (i, date) in dates.iter().enumerate() {
+            if i != 0 && (i + 1) % 5 != 0 {
+                continue;
+            }
+            let text = opts.locale.format(*date, "%-d");
+            let exts = ctx.text_extents(&text)?;
+            let rl = rrange.min() - 12.0;
+            ctx.move_to(
+                rl * angle(i).cos() - exts.x_advance() / 2.0,
+                rl * angle(i).sin() + exts.height() / 2.0,
+            );
+            ctx.show_text(&text)?;
+        }
+        ctx.restore()?;
+
+        ctx.save()?;
+        let scale = Scale::from_range(&track.range, 5.0)?;
+        render::render_scales(
+            ctx,
+            &scale,
+            &track.range,
+            &rrange,
+            track.units,
+            track.panel,
+            opts,
+        )?;
+        ctx.restore()?;
+
+        // the series are drawn from the middle of their first day, like the bars.
+        ctx.save()?;
+        ctx.rotate(dt / 2.0);
+        if let Some((low, high)) = &track.band {
+            render::render_radial_range(
+                ctx,
+                low,
+                high,
+                &rrange,
+                Some(&track.color.with_alpha(0.1)),
+                Some(&track.color.with_alpha(0.6)),
+                opts.smooth,
+            )?;
+        }
+        if let Some(line) = &track.line {
+            render::render_radial_series(ctx, line, &rrange, &track.color, opts.smooth)?;
+        }
+        ctx.restore()?;
+
+        if let Some(bars) = &track.bars {
+            ctx.save()?;
+            track.color.set(ctx);
+            ctx.set_line_width((rrange.min() * dt * 0.6).max(1.0));
+            for (i, v) in bars.values().iter().enumerate() {
+                let rb = rrange.project(opts.normalize(track.panel, &track.range, *v));
+                if bars.is_filled(i) || rb <= rrange.min() {
+                    continue;
+                }
+                let t = angle(i);
+                ctx.new_path();
+                ctx.move_to(rrange.min() * t.cos(), rrange.min() * t.sin());
+                ctx.line_to(rb * t.cos(), rb * t.sin());
+                ctx.stroke()?;
+            }
+            ctx.restore()?;
+        }
+
+        ctx.save()?;
+        render::render_center_text(
+            ctx,
+            &track.stats,
+            &Font::new(
+                "HelveticaNeue-Medium",
+                FontSlant::Normal,
+                FontWeight::Bold,
+                11.0,
+            ),
+            &Font::new(
+                "HelveticaNeue-Thin",
+                FontSlant::Normal,
+                FontWeight::Normal,
+                24.0,
+            ),
+            &opts.theme.foreground.with_alpha(0.6),
+            opts,
+        )?;
+        ctx.restore()?;
+
+        ctx.restore()?;
+    }
+    Ok(())
+}
+
+// Draws each track as a row of the days left to right, the rows one above the other with the
+// scale of each on its left.
+fn render_rows(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    header_height: f64,
+    tracks: &[Track],
+    dates: &[chrono::NaiveDate],
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let (x0, x1) = (70.0, width - 30.0);
+    let row_height = (height - header_height - 20.0) / tracks.len() as f64;
+    let dx = (x1 - x0) / dates.len() as f64;
+    let x_of = |i: usize| x0 + (i as f64 + 0.5) * dx;
+
+    let label_font = Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 10.0);
+    for (j, track) in tracks.iter().enumerate() {
+        let top = header_height + row_height * j as f64 + 30.0;
+        let bottom = header_height + row_height * (j + 1) as f64 - 20.0;
+        let y_of =
+            |v: f64| bottom - opts.normalize(track.panel, &track.range, v).value() * (bottom - top);
+
+        render::render_title(
+            ctx,
+            opts.locale.label(track.panel.title()),
+            (x0 + x1) / 2.0,
+            top - 12.0,
+            opts,
+        )?;
+
+        ctx.save()?;
+        label_font.set(ctx);
+        let scale = Scale::from_range(&track.range, 4.0)?;
+        for (i, step) in scale.steps().iter().enumerate() {
+            let y = y_of(*step);
+            opts.theme
+                .foreground
+                .with_alpha(opts.theme.grid_opacity)
+                .set(ctx);
+            ctx.set_dash(&opts.theme.grid_dash, 0.0);
+            ctx.new_path();
+            ctx.move_to(x0, y);
+            ctx.line_to(x1, y);
+            ctx.stroke()?;
+            let text = format!(
+                "{}{}",
+                opts.locale.localize(&scale.label_for(i)),
+                track.units
+            );
+            let exts = ctx.text_extents(&text)?;
+            opts.theme.foreground.with_alpha(0.6).set(ctx);
+            ctx.move_to(x0 - exts.x_advance() - 6.0, y + exts.height() / 2.0);
+            ctx.show_text(&text)?;
+        }
+        for (i, date) in dates.iter().enumerate() {
+            if i != 0 && (i + 1) % 5 != 0 {
+                continue;
+            }
+            let text = opts.locale.format(*date, "%-d");
+            let exts = ctx.text_extents(&text)?;
+            ctx.move_to(
+                x_of(i) - exts.x_advance() / 2.0,
+                bottom + 6.0 + exts.height(),
+            );
+            ctx.show_text(&text)?;
+        }
+        ctx.restore()?;
+
+        ctx.save()?;
+        if let Some((low, high)) = &track.band {
+            ctx.new_path();
+            for (i, v) in high.values().iter().enumerate() {
+                ctx.line_to(x_of(i), y_of(*v));
+            }
+            for (i, v) in low.values().iter().enumerate().rev() {
+                ctx.line_to(x_of(i), y_of(*v));
+            }
+            ctx.close_path();
+            track.color.with_alpha(0.1).set(ctx);
+            ctx.fill()?;
+        }
+        if let Some(line) = &track.line {
+            track.color.set(ctx);
+            ctx.set_line_width(1.5);
+            ctx.new_path();
+            for (i, v) in line.values().iter().enumerate() {
+                ctx.line_to(x_of(i), y_of(*v));
+            }
+            ctx.stroke()?;
+        }
+        if let Some(bars) = &track.bars {
+            track.color.set(ctx);
+            for (i, v) in bars.values().iter().enumerate() {
+                if bars.is_filled(i) {
+                    continue;
+                }
+                let y = y_of(*v);
+                ctx.rectangle(x_of(i) - dx * 0.3, y, dx * 0.6, bottom - y);
+                ctx.fill()?;
+            }
+        }
+        ctx.restore()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use super::{gsod::Station, source::DataSource, time, TAU};
+use chrono::prelude::*;
+use std::error::Error;
+
+const HEADER: [&str; 28] = [
+    "STATION",
+    "DATE",
+    "LATITUDE",
+    "LONGITUDE",
+    "ELEVATION",
+    "NAME",
+    "TEMP",
+    "TEMP_ATTRIBUTES",
+    "DEWP",
+    "DEWP_ATTRIBUTES",
+    "SLP",
+    "SLP_ATTRIBUTES",
+    "STP",
+    "STP_ATTRIBUTES",
+    "VISIB",
+    "VISIB_ATTRIBUTES",
+    "WDSP",
+    "WDSP_ATTRIBUTES",
+    "MXSPD",
+    "GUST",
+    "MAX",
+    "MAX_ATTRIBUTES",
+    "MIN",
+    "MIN_ATTRIBUTES",
+    "PRCP",
+    "PRCP_ATTRIBUTES",
+    "SNDP",
+    "FRSHTT",
+];
+
+// Builds a station with a year of smooth, made up data so that layouts can be previewed
+// without downloading any archives. The data is written out as GSOD CSV and parsed back
+// so that it goes through exactly the same path as the real thing. Each station id gets
+// its own phase so that comparisons of placeholders are still distinguishable.
+pub(crate) fn station(year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+    let phase = id.bytes().map(|b| b as f64).sum::<f64>() % 30.0;
+    let num_days = year.duration().num_days() as f64;
+
+    let mut w = csv::WriterBuilder::new()
+        .quote_style(csv::QuoteStyle::Always)
+        .from_writer(Vec::new());
+    w.write_record(HEADER)?;
+    for day in year.days() {
+        let t = (day.date().ordinal0() as f64 + phase) / num_days * TAU;
+        let mean = 60.0 - 20.0 * (t - 0.25).cos() + 3.0 * (t * 9.0).sin();
+        let spread = 9.0 + 2.0 * (t * 5.0).cos();
+        let wind = 7.0 + 2.0 * (t * 2.0).cos() + (t * 13.0).sin();
+        let gust = wind + 6.0 + 2.0 * (t * 7.0).sin();
+        let rain = ((t * 23.0).sin() - 0.6).max(0.0) * 2.0;
+        w.write_record([
+            id,
+            &day.date().format("%Y-%m-%d").to_string(),
+            "40.0000",
+            "-75.0000",
+            "100.0",
+            "LOREM IPSUM INTERNATIONAL AIRPORT, XX US",
+            &format!("{:.1}", mean),
+            "24",
+            &format!("{:.1}", mean - 10.0),
+            "24",
+            "1015.0",
+            "24",
+            "999.9",
+            "0",
+            "10.0",
+            "24",
+            &format!("{:.1}", wind),
+            "24",
+            &format!("{:.1}", gust),
+            "999.9",
+            &format!("{:.1}", mean + spread),
+            " ",
+            &format!("{:.1}", mean - spread),
+            " ",
+            &format!("{:.2}", rain),
+            "G",
+            "999.9",
+            match (rain > 0.0, mean - spread < 32.0) {
+                (true, true) => "001000",
+                (true, false) => "010000",
+                (false, _) => "000000",
+            },
+        ])?;
+    }
+
+    Station::from_reader(w.into_inner()?.as_slice())
+}
+
+// The year a placeholder defaults to when none is given, which doesn't need to look at
+// what archives are available.
+pub(crate) fn default_year() -> i32 {
+    Local::now().year() - 1
+}
+
+// Made up data for any station id.
+pub(crate) struct Placeholder;
+
+impl DataSource for Placeholder {
+    fn name(&self) -> &'static str {
+        "placeholder"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        Ok(default_year())
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        station(year, id)
+    }
+}
@@ -0,0 +1,136 @@
+use super::{
+    canvas::{self, Anchor, Canvas, Path, Text, Weight},
+    Color,
+};
+use plotters::backend::DrawingBackend;
+use plotters::coord::Shift;
+use plotters::drawing::DrawingArea;
+use plotters::element::{DashedPathElement, Drawable, PathElement, PointCollection, Polygon};
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use plotters::style::{Color as _, FontStyle, IntoFont, RGBAColor};
+use std::error::Error;
+
+// Draws banners onto a plotters drawing area, so the rings can be laid out among the other
+// charts of a plotters dashboard:
+//
+//   let root = SVGBackend::new("dashboard.svg", (1600, 1200)).into_drawing_area();
+//   let (top, bottom) = root.split_vertically(600);
+//   weather_banner::plot::draw_banner(&top, &csv, "Home", 2023)?;
+//
+// The banner fills the area it is given. Plotters can only turn text by quarter turns, so
+// the month labels stay upright.
+pub fn draw_banner<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    csv: &str,
+    name: &str,
+    year: i32,
+) -> Result<(), Box<dyn Error>> {
+    let (model, theme) = canvas::banner(csv, name, year)?;
+    let (width, height) = area.dim_in_pixel();
+    let mut plot = Plot { area, error: None };
+    canvas::draw(&mut plot, &model, width as f64, height as f64, &theme);
+    match plot.error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+// the canvas can't fail, so the first error of the backend is kept for the end.
+struct Plot<'a, DB: DrawingBackend> {
+    area: &'a DrawingArea<DB, Shift>,
+    error: Option<String>,
+}
+
+impl<DB: DrawingBackend> Plot<'_, DB> {
+    fn draw<'b, E>(&mut self, element: &'b E)
+    where
+        &'b E: PointCollection<'b, (i32, i32)>,
+        E: Drawable<DB>,
+    {
+        if let (Err(e), None) = (self.area.draw(element), &self.error) {
+            self.error = Some(e.to_string());
+        }
+    }
+}
+
+impl<DB: DrawingBackend> Canvas for Plot<'_, DB> {
+    // plotters fills a polygon at a time, so the subpaths are joined into one, every other
+    // one reversed. The band between two lines around the year becomes a ring with a seam
+    // of no width, which is filled the same way the even-odd rule would fill it.
+    fn fill(&mut self, path: &Path, color: &Color) {
+        let mut points = Vec::new();
+        for (i, subpath) in path.subpaths.iter().enumerate() {
+            let mut subpath = closed(&subpath.points, subpath.closed);
+            if i % 2 == 1 {
+                subpath.reverse();
+            }
+            points.extend(subpath);
+        }
+        self.draw(&Polygon::new(points, rgba(color).filled()));
+    }
+
+    fn stroke(&mut self, path: &Path, color: &Color, dots: Option<(f64, f64)>) {
+        for subpath in &path.subpaths {
+            let points = closed(&subpath.points, subpath.closed);
+            let style = rgba(color).stroke_width(2);
+            match dots {
+                Some((on, off)) => self.draw(&DashedPathElement::new(
+                    points,
+                    on.round() as i32,
+                    off.round() as i32,
+                    style,
+                )),
+                None => self.draw(&PathElement::new(points, style)),
+            }
+        }
+    }
+
+    fn text(&mut self, text: &Text, color: &Color) {
+        let style = match text.weight {
+            Weight::Bold => FontStyle::Bold,
+            Weight::Thin | Weight::Normal => FontStyle::Normal,
+        };
+        let pos = Pos::new(
+            match text.anchor {
+                Anchor::Start => HPos::Left,
+                Anchor::Middle => HPos::Center,
+                Anchor::End => HPos::Right,
+            },
+            if text.centered {
+                VPos::Center
+            } else {
+                VPos::Bottom
+            },
+        );
+        let color = rgba(color);
+        let font = ("sans-serif", text.size)
+            .into_font()
+            .style(style)
+            .color(&color)
+            .pos(pos);
+        self.draw(&plotters::element::Text::new(
+            text.text.clone(),
+            coord(text.x, text.y),
+            font,
+        ));
+    }
+}
+
+fn closed(points: &[(f64, f64)], closed: bool) -> Vec<(i32, i32)> {
+    let mut coords = points
+        .iter()
+        .map(|(x, y)| coord(*x, *y))
+        .collect::<Vec<_>>();
+    if let (true, Some(first)) = (closed, coords.first().copied()) {
+        coords.push(first);
+    }
+    coords
+}
+
+fn coord(x: f64, y: f64) -> (i32, i32) {
+    (x.round() as i32, y.round() as i32)
+}
+
+fn rgba(c: &Color) -> RGBAColor {
+    RGBAColor(c.r, c.g, c.b, c.a as f64 / 255.0)
+}
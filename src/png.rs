@@ -0,0 +1,42 @@
+use flate2::Crc;
+use std::error::Error;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+// Inserts text chunks right after the IHDR of an encoded PNG. Values that are plain ASCII
+// are written as tEXt, anything else as an uncompressed iTXt since tEXt is Latin-1.
+pub(crate) fn add_text(png: &[u8], entries: &[(&str, String)]) -> Result<Vec<u8>, Box<dyn Error>> {
+    if png.len() < 33 || png[..8] != SIGNATURE || &png[12..16] != b"IHDR" {
+        return Err("not a png".into());
+    }
+
+    // signature, then IHDR's length, type, 13 bytes of data and crc.
+    let (head, tail) = png.split_at(33);
+    let mut out = Vec::with_capacity(png.len() + 64 * entries.len());
+    out.extend_from_slice(head);
+    for (key, val) in entries {
+        let mut data = Vec::from(key.as_bytes());
+        data.push(0);
+        if val.is_ascii() {
+            data.extend_from_slice(val.as_bytes());
+            write_chunk(&mut out, b"tEXt", &data);
+        } else {
+            // no compression, then empty language and translated keyword.
+            data.extend_from_slice(&[0, 0, 0, 0]);
+            data.extend_from_slice(val.as_bytes());
+            write_chunk(&mut out, b"iTXt", &data);
+        }
+    }
+    out.extend_from_slice(tail);
+    Ok(out)
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut crc = Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&crc.sum().to_be_bytes());
+}
@@ -0,0 +1,66 @@
+use super::{gsod, time, Data};
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The span of years to download, e.g. 2000..2023.
+    #[clap(long)]
+    years: time::Years,
+
+    /// How many archives to download at once.
+    #[clap(long, default_value_t = 4)]
+    jobs: usize,
+}
+
+// Downloads the yearly archives into the data directory ahead of the renders that need
+// them, a few at a time. Archives that are already there are left alone.
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    // the queue is taken from the back, so the years go out oldest first.
+    let mut years = args.years.iter().collect::<Vec<_>>();
+    years.reverse();
+    let queue = Mutex::new(years);
+    let total = args.years.len();
+    let (done, failed) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+    thread::scope(|s| {
+        for _ in 0..args.jobs.clamp(1, total) {
+            s.spawn(|| loop {
+                let year = match queue.lock().unwrap().pop() {
+                    Some(year) => year,
+                    None => break,
+                };
+                let name = format!("{}.tar.gz", year);
+                let cached = data.path(&name).exists();
+                let start = Instant::now();
+                let res = data
+                    .download_and_open(&gsod::url_for(year.ordinal()), &name)
+                    .and_then(|f| Ok(f.metadata()?.len()));
+                let n = done.fetch_add(1, Ordering::SeqCst) + 1;
+                match res {
+                    Ok(_) if cached => eprintln!("[{}/{}] {}: already downloaded", n, total, name),
+                    Ok(size) => eprintln!(
+                        "[{}/{}] {}: {:.1} MB in {:.1}s",
+                        n,
+                        total,
+                        name,
+                        size as f64 / 1e6,
+                        start.elapsed().as_secs_f64()
+                    ),
+                    Err(e) => {
+                        failed.fetch_add(1, Ordering::SeqCst);
+                        eprintln!("[{}/{}] {}: {}", n, total, name, e);
+                    }
+                }
+            });
+        }
+    });
+
+    match failed.into_inner() {
+        0 => Ok(()),
+        n => Err(format!("{} of {} archives failed to download", n, total).into()),
+    }
+}
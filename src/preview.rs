@@ -0,0 +1,153 @@
+use cairo::ImageSurface;
+use std::env;
+use std::error::Error;
+use std::io::{self, IsTerminal, Write};
+
+// How --preview shows the render in the terminal.
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum Preview {
+    /// kitty's graphics protocol when the terminal looks like one that speaks it, sixel
+    /// otherwise.
+    Auto,
+    /// kitty's graphics protocol, which kitty, WezTerm, Ghostty and Konsole support.
+    Kitty,
+    /// Sixel graphics, which xterm, foot, mlterm, iTerm2 and Windows Terminal support.
+    Sixel,
+}
+
+impl Preview {
+    fn resolve(self) -> Preview {
+        match self {
+            Preview::Auto if speaks_kitty() => Preview::Kitty,
+            Preview::Auto => Preview::Sixel,
+            p => p,
+        }
+    }
+}
+
+fn speaks_kitty() -> bool {
+    let var = |name| env::var(name).unwrap_or_default();
+    env::var_os("KITTY_WINDOW_ID").is_some()
+        || var("TERM").contains("kitty")
+        || var("TERM").contains("ghostty")
+        || matches!(var("TERM_PROGRAM").as_str(), "WezTerm" | "ghostty")
+        || env::var_os("KONSOLE_VERSION").is_some()
+}
+
+// Draws the PNG inline in the terminal on stdout.
+pub fn show(png: &[u8], preview: Preview) -> Result<(), Box<dyn Error>> {
+    let stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return Err("--preview needs stdout to be a terminal".into());
+    }
+    let mut w = io::BufWriter::new(stdout.lock());
+    match preview.resolve() {
+        Preview::Kitty => write_kitty(&mut w, png)?,
+        _ => write_sixel(&mut w, png)?,
+    }
+    writeln!(w)?;
+    w.flush()?;
+    Ok(())
+}
+
+// kitty takes the PNG itself, base64 encoded and sent in chunks of at most 4096 bytes.
+fn write_kitty<W: Write>(w: &mut W, png: &[u8]) -> Result<(), Box<dyn Error>> {
+    let data = base64(png);
+    let chunks = data.as_bytes().chunks(4096).collect::<Vec<_>>();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        match i {
+            0 => write!(w, "\x1b_Gf=100,a=T,m={};", more)?,
+            _ => write!(w, "\x1b_Gm={};", more)?,
+        }
+        w.write_all(chunk)?;
+        write!(w, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// Sixel wants the pixels, which are mapped onto a palette of 6 levels of each of red, green
+// and blue. Each band of 6 rows is drawn a color at a time, as runs of sixels.
+fn write_sixel<W: Write>(w: &mut W, png: &[u8]) -> Result<(), Box<dyn Error>> {
+    let mut surface = ImageSurface::create_from_png(&mut &png[..])?;
+    let (width, height, stride) = (
+        surface.width() as usize,
+        surface.height() as usize,
+        surface.stride() as usize,
+    );
+    let data = surface.data()?;
+    // pixels are native endian ARGB; the banners are opaque, so alpha is ignored.
+    let index = |x: usize, y: usize| {
+        let i = y * stride + x * 4;
+        let px = u32::from_ne_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        let level = |c: u32| ((c & 0xff) * 6 / 256) as usize;
+        level(px >> 16) * 36 + level(px >> 8) * 6 + level(px)
+    };
+
+    write!(w, "\x1bPq\"1;1;{};{}", width, height)?;
+    for i in 0..216 {
+        let pct = |level: usize| level * 100 / 5;
+        write!(
+            w,
+            "#{};2;{};{};{}",
+            i,
+            pct(i / 36),
+            pct(i / 6 % 6),
+            pct(i % 6)
+        )?;
+    }
+
+    let mut sixels = vec![0u8; width];
+    for top in (0..height).step_by(6) {
+        let rows = (height - top).min(6);
+        let mut colors = vec![false; 216];
+        for y in top..top + rows {
+            for x in 0..width {
+                colors[index(x, y)] = true;
+            }
+        }
+        for color in (0..216).filter(|c| colors[*c]) {
+            for (x, sixel) in sixels.iter_mut().enumerate() {
+                *sixel = (0..rows)
+                    .filter(|dy| index(x, top + dy) == color)
+                    .fold(0, |bits, dy| bits | 1 << dy);
+            }
+            write!(w, "#{}", color)?;
+            let mut x = 0;
+            while x < width {
+                let run = sixels[x..].iter().take_while(|s| **s == sixels[x]).count();
+                let c = (63 + sixels[x]) as char;
+                match run {
+                    1..=3 => write!(w, "{}", c.to_string().repeat(run))?,
+                    _ => write!(w, "!{}{}", run, c)?,
+                }
+                x += run;
+            }
+            write!(w, "$")?;
+        }
+        write!(w, "-")?;
+    }
+    write!(w, "\x1b\\")?;
+    Ok(())
+}
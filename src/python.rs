@@ -0,0 +1,228 @@
+// pyo3's macros convert the results of the functions even when they are already PyResults.
+#![allow(clippy::useless_conversion)]
+
+use super::{
+    gsod::{Day, Station},
+    isd, render, Data,
+};
+use chrono::Datelike;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDate};
+use std::error::Error;
+
+// The Python module, for rendering banners and reading GSOD data from notebooks:
+//
+//   import weather_banner as wb
+//   station = wb.find_station(2022, "KRDU")
+//   png = wb.render_png_bytes(["--station-id", "KRDU", "--year", "2022"])
+//
+// Both take a data_dir, which is the same cache of archives as the command's --data-dir.
+#[pymodule]
+fn weather_banner(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyStation>()?;
+    m.add_class::<PyDay>()?;
+    m.add_function(wrap_pyfunction!(find_station, m)?)?;
+    m.add_function(wrap_pyfunction!(render_png_bytes, m)?)?;
+    Ok(())
+}
+
+// errors are turned into strings before they leave the threads that released the GIL, as
+// they aren't Send.
+fn to_py_err(e: String) -> PyErr {
+    PyRuntimeError::new_err(e)
+}
+
+// Loads a station's year from the archive, by GSOD id or airport code.
+#[pyfunction]
+#[pyo3(signature = (year, station_id, data_dir = "data"))]
+fn find_station(
+    py: Python<'_>,
+    year: i32,
+    station_id: &str,
+    data_dir: &str,
+) -> PyResult<PyStation> {
+    py.allow_threads(|| {
+        let res = (|| {
+            let data = Data::from(data_dir)?;
+            let id = isd::resolve_station(&data, station_id)?;
+            render::find_station_for_year(&data, year, &id, render::StrayDays::Drop)
+        })();
+        res.map_err(|e: Box<dyn Error>| e.to_string())
+    })
+    .map(PyStation)
+    .map_err(to_py_err)
+}
+
+// Renders a banner as PNG bytes. The args are those of the render command, e.g.
+// ["--station-id", "KBOS", "--chart", "spiral"].
+#[pyfunction]
+#[pyo3(signature = (args = Vec::new(), data_dir = "data"))]
+fn render_png_bytes<'py>(
+    py: Python<'py>,
+    args: Vec<String>,
+    data_dir: &str,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let png = py
+        .allow_threads(|| {
+            let res = (|| -> Result<Vec<u8>, Box<dyn Error>> {
+                render::render_png_bytes(&Data::from(data_dir)?, &render::Args::parse_from(args)?)
+            })();
+            res.map_err(|e| e.to_string())
+        })
+        .map_err(to_py_err)?;
+    Ok(PyBytes::new_bound(py, &png))
+}
+
+#[pyclass(name = "Station", module = "weather_banner", frozen)]
+struct PyStation(Station);
+
+#[pymethods]
+impl PyStation {
+    #[getter]
+    fn id(&self) -> &str {
+        self.0.id()
+    }
+
+    #[getter]
+    fn name(&self) -> Option<&str> {
+        self.0.name()
+    }
+
+    // (latitude, longitude)
+    #[getter]
+    fn location(&self) -> Option<(f64, f64)> {
+        self.0.location().map(|loc| (loc.lat(), loc.lng()))
+    }
+
+    // in meters.
+    #[getter]
+    fn elevation(&self) -> Option<f64> {
+        self.0.elevation().map(|e| e.in_meters())
+    }
+
+    #[getter]
+    fn days(&self) -> Vec<PyDay> {
+        self.0.days().iter().cloned().map(PyDay).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.days().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Station(id={:?}, name={:?}, days={})",
+            self.0.id(),
+            self.0.name().unwrap_or(""),
+            self.0.days().len()
+        )
+    }
+}
+
+// A day of a station, in the units GSOD uses: °F, millibars, miles, knots and inches.
+#[pyclass(name = "Day", module = "weather_banner", frozen)]
+struct PyDay(Day);
+
+#[pymethods]
+impl PyDay {
+    #[getter]
+    fn date<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDate>> {
+        let date = self.0.date();
+        PyDate::new_bound(py, date.year(), date.month() as u8, date.day() as u8)
+    }
+
+    #[getter]
+    fn mean_temperature(&self) -> Option<f64> {
+        self.0.mean_temperature().map(|t| t.in_fahrenheit())
+    }
+
+    #[getter]
+    fn max_temperature(&self) -> Option<f64> {
+        self.0.max_temperature().map(|t| t.in_fahrenheit())
+    }
+
+    #[getter]
+    fn min_temperature(&self) -> Option<f64> {
+        self.0.min_temperature().map(|t| t.in_fahrenheit())
+    }
+
+    #[getter]
+    fn mean_dewpoint(&self) -> Option<f64> {
+        self.0.mean_dewpoint().map(|t| t.in_fahrenheit())
+    }
+
+    #[getter]
+    fn mean_sea_level_pressure(&self) -> Option<f64> {
+        self.0.mean_sea_level_pressure().map(|p| p.in_millibars())
+    }
+
+    #[getter]
+    fn mean_station_pressure(&self) -> Option<f64> {
+        self.0.mean_station_pressure().map(|p| p.in_millibars())
+    }
+
+    #[getter]
+    fn mean_visibility(&self) -> Option<f64> {
+        self.0.mean_visibility().map(|d| d.in_miles())
+    }
+
+    #[getter]
+    fn mean_wind(&self) -> Option<f64> {
+        self.0.mean_wind().map(|s| s.in_knots())
+    }
+
+    #[getter]
+    fn max_sustained_wind(&self) -> Option<f64> {
+        self.0.max_sustained_wind().map(|s| s.in_knots())
+    }
+
+    #[getter]
+    fn max_wind_gust(&self) -> Option<f64> {
+        self.0.max_wind_gust().map(|s| s.in_knots())
+    }
+
+    #[getter]
+    fn precipitation(&self) -> Option<f64> {
+        self.0.precipitation().map(|p| p.in_inches())
+    }
+
+    #[getter]
+    fn snow_depth(&self) -> Option<f64> {
+        self.0.snow_depth().map(|d| d.in_inches())
+    }
+
+    #[getter]
+    fn fog(&self) -> bool {
+        self.0.indicators().fog()
+    }
+
+    #[getter]
+    fn rain(&self) -> bool {
+        self.0.indicators().rain()
+    }
+
+    #[getter]
+    fn snow(&self) -> bool {
+        self.0.indicators().snow()
+    }
+
+    #[getter]
+    fn hail(&self) -> bool {
+        self.0.indicators().hail()
+    }
+
+    #[getter]
+    fn thunder(&self) -> bool {
+        self.0.indicators().thunder()
+    }
+
+    #[getter]
+    fn tornado(&self) -> bool {
+        self.0.indicators().tornado()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Day(date={})", self.0.date())
+    }
+}
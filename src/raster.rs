@@ -0,0 +1,341 @@
+use super::{
+    canvas::{self, Anchor, Canvas, FontArgs, Path, Text, Weight},
+    input, model,
+    theme::Theme,
+    Color,
+};
+use chrono::Datelike;
+use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder};
+use rustybuzz::{Face, UnicodeBuffer};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, StrokeDash, Transform};
+
+// A renderer in pure Rust, drawing with tiny-skia and shaping text with rustybuzz, for the
+// places where cairo is a pain to build, like Windows and slim containers. It draws the same
+// chart model as the SVG renderer. The command line tool draws with it for
+// render --backend skia, and without cairo at all the tool is built with:
+//
+//   cargo build --release --no-default-features --features skia
+//
+// which leaves only the render command, drawing a banner from a CSV in the format of
+// --source file. There are no fonts in the crate, so they're given with --font or looked for
+// where the common platforms keep a sans-serif one.
+
+/// Renders a station's year from a CSV in the format of --source file, without cairo.
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The CSV of the station's days, or - for stdin.
+    #[clap(long)]
+    input: String,
+
+    /// The year to draw, by default the last one in the file.
+    #[clap(long)]
+    year: Option<i32>,
+
+    /// What the header calls the station, by default the name of the file.
+    #[clap(long)]
+    name: Option<String>,
+
+    /// Where to write the PNG, or - for stdout.
+    #[clap(long, default_value_t = String::from("banner.png"))]
+    destination: String,
+
+    #[clap(long, default_value_t = 1600)]
+    width: u32,
+
+    #[clap(long, default_value_t = 600)]
+    height: u32,
+
+    #[command(flatten)]
+    fonts: FontArgs,
+}
+
+pub fn execute(args: &Args) -> Result<(), Box<dyn Error>> {
+    let mut csv = String::new();
+    match args.input.as_str() {
+        "-" => io::stdin().read_to_string(&mut csv),
+        path => fs::File::open(path).and_then(|mut f| f.read_to_string(&mut csv)),
+    }
+    .map_err(|e| format!("unable to read {}: {}", args.input, e))?;
+
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => std::path::Path::new(&args.input)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| args.input.clone()),
+    };
+    let year = match args.year {
+        Some(year) => year,
+        None => input::read(csv.as_bytes(), &args.input, name.clone())?
+            .days()
+            .last()
+            .map(|day| day.date().year())
+            .ok_or_else(|| format!("there are no days in {}", args.input))?,
+    };
+
+    let (model, theme) = canvas::banner(&csv, &name, year)?;
+    let fonts = Fonts::load(&args.fonts)?;
+    let png = draw_png(&model, &theme, args.width, args.height, &fonts)?;
+    match args.destination.as_str() {
+        "-" => io::Write::write_all(&mut io::stdout().lock(), &png)?,
+        dst => {
+            fs::write(dst, png)?;
+            println!("{}", dst);
+        }
+    }
+    Ok(())
+}
+
+// Renders the year of a station in CSV, in the format of --source file, to a PNG of the
+// given size. The name is what the header calls the station.
+pub fn render_png(
+    csv: &str,
+    name: &str,
+    year: i32,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (model, theme) = canvas::banner(csv, name, year)?;
+    draw_png(
+        &model,
+        &theme,
+        width,
+        height,
+        &Fonts::load(&FontArgs::default())?,
+    )
+}
+
+// Draws a radial banner of the model to a PNG of the given size.
+pub(crate) fn draw_png(
+    model: &model::Chart,
+    theme: &Theme,
+    width: u32,
+    height: u32,
+    fonts: &Fonts,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut raster = Raster {
+        pixmap: Pixmap::new(width, height).ok_or("invalid size")?,
+        fonts,
+    };
+    canvas::draw(&mut raster, model, width as f64, height as f64, theme);
+    Ok(raster.pixmap.encode_png()?)
+}
+
+const REGULAR: [&str; 4] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+const THIN: [&str; 3] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans-ExtraLight.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Narrow.ttf",
+    "C:\\Windows\\Fonts\\arialn.ttf",
+];
+
+const BOLD: [&str; 4] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Bold.ttf",
+    "/Library/Fonts/Arial Bold.ttf",
+    "C:\\Windows\\Fonts\\arialbd.ttf",
+];
+
+// The font files for each weight. A weight that isn't given is looked for among the system's
+// fonts when no font is given at all, and is the regular font otherwise.
+pub(crate) struct Fonts {
+    regular: Vec<u8>,
+    thin: Option<Vec<u8>>,
+    bold: Option<Vec<u8>>,
+}
+
+impl Fonts {
+    pub(crate) fn load(args: &FontArgs) -> Result<Fonts, Box<dyn Error>> {
+        let read = |path: &str| -> Result<Vec<u8>, Box<dyn Error>> {
+            let data = fs::read(path).map_err(|e| format!("unable to read {}: {}", path, e))?;
+            match Face::from_slice(&data, 0) {
+                Some(_) => Ok(data),
+                None => Err(format!("{} isn't a font", path).into()),
+            }
+        };
+        let find = |paths: &[&str]| {
+            paths
+                .iter()
+                .filter_map(|path| fs::read(path).ok())
+                .find(|data| Face::from_slice(data, 0).is_some())
+        };
+        let given = |path: &Option<String>| path.as_deref().map(read).transpose();
+        match given(&args.font)? {
+            Some(regular) => Ok(Fonts {
+                regular,
+                thin: given(&args.font_thin)?,
+                bold: given(&args.font_bold)?,
+            }),
+            None => Ok(Fonts {
+                regular: find(&REGULAR)
+                    .ok_or("unable to find a font, give one with --font or WEATHER_BANNER_FONT")?,
+                thin: given(&args.font_thin)?.or_else(|| find(&THIN)),
+                bold: given(&args.font_bold)?.or_else(|| find(&BOLD)),
+            }),
+        }
+    }
+
+    // every font was parsed when it was loaded, so they all make a face.
+    fn get(&self, weight: Weight) -> Face<'_> {
+        let data = match weight {
+            Weight::Thin => self.thin.as_ref().unwrap_or(&self.regular),
+            Weight::Normal => &self.regular,
+            Weight::Bold => self.bold.as_ref().unwrap_or(&self.regular),
+        };
+        Face::from_slice(data, 0).unwrap()
+    }
+}
+
+struct Raster<'a> {
+    pixmap: Pixmap,
+    fonts: &'a Fonts,
+}
+
+impl Canvas for Raster<'_> {
+    fn fill(&mut self, path: &Path, color: &Color) {
+        if let Some(path) = to_skia(path) {
+            self.pixmap.fill_path(
+                &path,
+                &paint(color),
+                FillRule::EvenOdd,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn stroke(&mut self, path: &Path, color: &Color, dots: Option<(f64, f64)>) {
+        let stroke = Stroke {
+            width: 2.0,
+            dash: dots.and_then(|(on, off)| StrokeDash::new(vec![on as f32, off as f32], 0.0)),
+            ..Stroke::default()
+        };
+        if let Some(path) = to_skia(path) {
+            self.pixmap
+                .stroke_path(&path, &paint(color), &stroke, Transform::identity(), None);
+        }
+    }
+
+    // the text is shaped into glyphs, whose outlines are filled as paths, which lets the text
+    // turn with everything else.
+    fn text(&mut self, text: &Text, color: &Color) {
+        let face = self.fonts.get(text.weight);
+        let k = text.size as f32 / face.units_per_em() as f32;
+
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(&text.text);
+        let glyphs = rustybuzz::shape(&face, &[], buffer);
+        let width = glyphs
+            .glyph_positions()
+            .iter()
+            .map(|pos| pos.x_advance as f32 * k)
+            .sum::<f32>();
+        let dx = match text.anchor {
+            Anchor::Start => 0.0,
+            Anchor::Middle => -width / 2.0,
+            Anchor::End => -width,
+        };
+        let dy = match text.centered {
+            true => (face.ascender() + face.descender()) as f32 * k / 2.0,
+            false => 0.0,
+        };
+
+        let mut outline = Outline {
+            pb: PathBuilder::new(),
+            x: dx,
+            y: dy,
+            k,
+        };
+        for (info, pos) in glyphs.glyph_infos().iter().zip(glyphs.glyph_positions()) {
+            let (x, y) = (outline.x, outline.y);
+            outline.x += pos.x_offset as f32 * k;
+            outline.y -= pos.y_offset as f32 * k;
+            face.outline_glyph(GlyphId(info.glyph_id as u16), &mut outline);
+            outline.x = x + pos.x_advance as f32 * k;
+            outline.y = y - pos.y_advance as f32 * k;
+        }
+
+        if let Some(path) = outline.pb.finish() {
+            self.pixmap.fill_path(
+                &path,
+                &paint(color),
+                FillRule::Winding,
+                Transform::from_translate(text.x as f32, text.y as f32)
+                    .pre_rotate(text.angle as f32),
+                None,
+            );
+        }
+    }
+}
+
+// Adds the outlines of glyphs to a path, each at the pen's position and scaled from the
+// font's units, which run upwards, to pixels, which run down.
+struct Outline {
+    pb: PathBuilder,
+    x: f32,
+    y: f32,
+    k: f32,
+}
+
+impl Outline {
+    fn at(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.k, self.y - y * self.k)
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.at(x, y);
+        self.pb.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.at(x, y);
+        self.pb.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let ((x1, y1), (x, y)) = (self.at(x1, y1), self.at(x, y));
+        self.pb.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let ((x1, y1), (x2, y2), (x, y)) = (self.at(x1, y1), self.at(x2, y2), self.at(x, y));
+        self.pb.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.pb.close();
+    }
+}
+
+fn to_skia(path: &Path) -> Option<tiny_skia::Path> {
+    let mut pb = PathBuilder::new();
+    for subpath in &path.subpaths {
+        for (i, (x, y)) in subpath.points.iter().enumerate() {
+            match i {
+                0 => pb.move_to(*x as f32, *y as f32),
+                _ => pb.line_to(*x as f32, *y as f32),
+            }
+        }
+        if subpath.closed {
+            pb.close();
+        }
+    }
+    pb.finish()
+}
+
+fn paint(color: &Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.r, color.g, color.b, color.a);
+    paint.anti_alias = true;
+    paint
+}
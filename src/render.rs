@@ -1,19 +1,83 @@
+#[cfg(feature = "skia")]
+use super::raster;
 use super::{
-    gsod, gsod::Station, time, Color, Data, Direction, Font, Range, Scale, Series, Unit, TAU,
+    boxplot,
+    canvas::FontArgs,
+    chart::{
+        self, center_stats, check_panels, feels_like_range_of, feels_like_series, feels_like_stats,
+        mean_temperature_series, month_spans, precipitation_range_of, precipitation_series,
+        precipitation_stats, snow_days, temperature_range_with_band, temperature_series,
+        temperature_stats, wind_range_of, wind_series, wind_stats, Center, Header, Layout,
+        MonthLabels, Options, Panel, PrecipScale, Rotation, ScaleSide, StartMonth, TemperatureBand,
+    },
+    check_point, check_radius, check_unit,
+    climatology::Climatology,
+    delta, filename,
+    geocode::{self, Gazetteer, Geocoder, Nominatim},
+    gsod,
+    gsod::Station,
+    heatmap, histogram, html, index, isd,
+    locale::{Label, Locale},
+    locator, model, period,
+    placeholder::Placeholder,
+    png,
+    preview::{self, Preview},
+    report,
+    repro::Repro,
+    source::{self, DataSource, Source, StationFile},
+    spiral, stats, stripes, svg,
+    theme::Theme,
+    time, Color, Data, Direction, Font, Gradient, Range, Scale, Series, Unit, YearSpec, TAU,
 };
-use cairo::{Context, FontSlant, FontWeight, Format, ImageSurface};
-use chrono::prelude::*;
-use flate2::read::GzDecoder;
+use cairo::{Context, FontSlant, FontWeight, Format, ImageSurface, PdfSurface, SvgSurface};
+use chrono::{Datelike, Local, NaiveDate};
+use clap::{parser::ValueSource, FromArgMatches, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::error::Error;
 use std::f64::consts::PI;
+use std::ffi::OsString;
 use std::fs;
-use std::io;
-use tar::Archive;
+use std::io::{self, Write};
+use std::iter;
+use std::path::Path;
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Clone, Deserialize, Serialize)]
 pub struct Args {
-    #[clap(long, default_value_t = String::from("72309693727"))]
-    station_id: String,
+    /// The station to render, by GSOD id or by ICAO or IATA airport code (e.g. KBOS or
+    /// BOS). Repeat to compare several stations in one banner.
+    #[clap(long, alias = "station", default_value = "72309693727")]
+    station_id: Vec<String>,
+
+    /// Render the station nearest to a place, e.g. "Portland, OR", that has data for most
+    /// of the year.
+    #[clap(long, conflicts_with = "station_id")]
+    place: Option<String>,
+
+    /// Average the stations into one before drawing them. With --place, this averages all
+    /// of the complete stations within --average-radius of it.
+    #[clap(long, default_value_t = false)]
+    average: bool,
+
+    /// How far away, in km, the stations averaged for --place can be.
+    #[clap(long, default_value_t = 50.0)]
+    average_radius: f64,
+
+    /// When the station is missing more than a tenth of the days of the year, take the
+    /// days from the nearest complete station within --fallback-radius instead, either
+    /// just the missing ones or all of them.
+    #[clap(long, value_enum)]
+    fallback: Option<Fallback>,
+
+    /// How far away, in km, a station can be to stand in for one with gaps.
+    #[clap(long, default_value_t = 50.0)]
+    fallback_radius: f64,
+
+    /// Look up --place in this CSV file of name, lat and lng instead of asking
+    /// OpenStreetMap's Nominatim.
+    #[clap(long, requires = "place")]
+    gazetteer: Option<String>,
 
     #[clap(long, default_value_t = 1600)]
     width: i32,
@@ -21,9 +85,17 @@ pub struct Args {
     #[clap(long, default_value_t = 600)]
     height: i32,
 
-    #[clap(long, default_value_t = Local::now().year()-1)]
-    year: i32,
-
+    /// The year to use, "latest" for the most recent complete year or "current" for the
+    /// year so far.
+    #[clap(long, default_value = "latest")]
+    year: YearSpec,
+
+    /// Where to write the image, as an SVG, HTML page or JSON chart model when it ends with
+    /// .svg, .html or .json and a PNG otherwise.
+    /// {station}, {station_name}, {year} and {date} are replaced with the station ids, the
+    /// station names, the year and the day of the render. Missing directories are created.
+    /// An s3://, gs:// or http(s):// URL uploads the image there instead, with the upload
+    /// feature, and - writes it to stdout.
     #[clap(long, default_value_t = String::from(""))]
     destination: String,
 
@@ -35,174 +107,2486 @@ pub struct Args {
 
     #[clap(long, default_value_t = true)]
     smooth: bool,
+
+    #[clap(long, value_enum, default_value_t = Chart::Radial)]
+    chart: Chart,
+
+    #[clap(long, value_enum, default_value_t = heatmap::Metric::Temperature)]
+    metric: heatmap::Metric,
+
+    /// The year a delta chart compares each day of the year with.
+    #[clap(long)]
+    baseline_year: Option<i32>,
+
+    /// Span of years, e.g. 2014..2023. Multi-year charts draw all of them, defaulting to the
+    /// decade ending with --year; single year charts render one file for each.
+    #[clap(long)]
+    years: Option<time::Years>,
+
+    /// A single month to render at daily resolution in place of the year, e.g. 2023-07.
+    #[clap(long, conflicts_with_all = ["year", "years"])]
+    period: Option<time::Month>,
+
+    /// Render the 365 days ending with the station's latest day of data in place of a
+    /// calendar year, from the archives of both of the years they span. The latest day is
+    /// looked for in the year so far first, unless --year says which year's archive to end
+    /// in.
+    #[clap(long, default_value_t = false, conflicts_with_all = ["years", "period"])]
+    trailing_year: bool,
+
+    /// How --period draws the days of the month.
+    #[clap(long, value_enum, default_value_t = period::Style::Radial)]
+    period_style: period::Style,
+
+    #[clap(long, value_enum, default_value_t = stripes::Resolution::Annual)]
+    resolution: stripes::Resolution,
+
+    #[clap(long, value_enum, default_value_t = Layout::Banner)]
+    layout: Layout,
+
+    /// The panels of a radial chart, in order, e.g. temperature,precipitation.
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "temperature,wind,precipitation"
+    )]
+    panels: Vec<Panel>,
+
+    /// The side of each of --panels to label its scale on, in the same order, or none for
+    /// no scale. The last one is used for the panels past the end, e.g. right for all of
+    /// them.
+    #[clap(long, value_enum, value_delimiter = ',', default_value = "left")]
+    scale_side: Vec<ScaleSide>,
+
+    /// Where the data of each panel is drawn, from the inner to the outer radius as fractions
+    /// of the panel's radius. A lower inner radius fattens the ring of data, a higher one
+    /// leaves more room for the stats in the middle.
+    #[clap(long, default_value = "0.6:0.9")]
+    radii: Bounds,
+
+    /// How far inside of the data the month ring is drawn, from its outer to its inner edge
+    /// in pixels.
+    #[clap(long, default_value = "5:40")]
+    month_band: Bounds,
+
+    /// The month at the top of the rings, e.g. jul to put the southern summer at the
+    /// bottom.
+    #[clap(long, value_enum, default_value_t = StartMonth::Jan)]
+    start_month: StartMonth,
+
+    /// Which way the rings run from --start-month.
+    #[clap(long, value_enum, default_value_t = Rotation::Cw)]
+    direction: Rotation,
+
+    /// Shade the meteorological seasons faintly behind the data of these panels, e.g.
+    /// temperature,precipitation. Winter is December to February north of the equator and
+    /// June to August south of it, going by the first station.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    seasons: Vec<Panel>,
+
+    /// Mark where each week starts, on Mondays, with a fine tick around the month ring.
+    #[clap(long, default_value_t = false)]
+    week_ticks: bool,
+
+    /// Draw a full ring at each step of the scales, in the theme's grid_dash and
+    /// grid_opacity, rather than the arcs that lead to their labels.
+    #[clap(long, default_value_t = false)]
+    grid_rings: bool,
+
+    /// Draw the temperature band from the min/max over a rolling window of this many days.
+    #[clap(long, default_value_t = 1)]
+    envelope_window: u32,
+
+    /// Which band is drawn around the mean temperature.
+    #[clap(long, value_enum, default_value_t = TemperatureBand::MinMax)]
+    temperature_band: TemperatureBand,
+
+    /// How many days around each day the standard deviation of the sigma band is taken
+    /// over.
+    #[clap(long, default_value_t = 15)]
+    sigma_window: u32,
+
+    /// Fade the days of the temperature and wind panels whose means come from fewer than
+    /// this many observations, so that sparse days don't look as sure as the rest.
+    #[clap(long, default_value_t = 0)]
+    min_samples: u32,
+
+    /// Pin the temperature scale to these bounds in °F, e.g. -10:100, rather than fitting it
+    /// to the stations, so that banners of different stations or years compare at a glance.
+    /// Values outside of them are drawn at their edges.
+    #[clap(long, allow_hyphen_values = true)]
+    temp_range: Option<Bounds>,
+
+    /// Pin the wind scale to these bounds in knots, e.g. 0:30.
+    #[clap(long, allow_hyphen_values = true)]
+    wind_range: Option<Bounds>,
+
+    /// Pin the precipitation scale to these bounds in inches, e.g. 0:3.
+    #[clap(long, allow_hyphen_values = true)]
+    precip_range: Option<Bounds>,
+
+    /// When rendering a file for each of --years, fit the scales to all of the years rather
+    /// than to each one, so that the banners compare at a glance. Scales pinned with
+    /// --temp-range, --wind-range or --precip-range are left as they are.
+    #[clap(long, default_value_t = false)]
+    shared_scales: bool,
+
+    /// How to show multiple stations: overlaid on the same panels or in separate rows.
+    #[clap(long, value_enum, default_value_t = Compare::Overlay)]
+    compare: Compare,
+
+    /// What to do with rows whose date falls outside of the requested year.
+    #[clap(long, value_enum, default_value_t = StrayDays::Drop)]
+    stray_days: StrayDays,
+
+    /// Render made up data instead of a real station, to preview the layout without any
+    /// archives.
+    #[clap(long, default_value_t = false)]
+    placeholder: bool,
+
+    /// Where to get the data from. The station ids are locations, like "35.89,-78.78",
+    /// for sources that aren't made of stations.
+    #[clap(long, value_enum, default_value_t = Source::Gsod)]
+    source: Source,
+
+    /// The CSV file to read with --source file. It has a header row and columns of date
+    /// (YYYY-MM-DD), tmin, tmax and tmean (°F), wind (knots) and precip (inches), any of
+    /// which but date can be left out or empty.
+    #[clap(long)]
+    input: Option<String>,
+
+    /// Read the station from this CSV file in GSOD's format, or from stdin for -, instead of
+    /// the archives. GSOD's older fixed-width .op files, gzipped or not, are read too. The
+    /// station id is the one in the file.
+    #[clap(long, conflicts_with_all = ["place", "input", "placeholder", "fallback"])]
+    station_file: Option<String>,
+
+    /// Shade the 10th to 90th percentile of the mean temperature over the ten years before
+    /// the one being rendered behind the temperature.
+    #[clap(long, default_value_t = false)]
+    percentile_band: bool,
+
+    /// Show counts of frost, ice and summer days next to the temperature panel.
+    #[clap(long, default_value_t = false)]
+    callouts: bool,
+
+    /// Mark the days with fog, snow or thunder in a thin ring around the precipitation
+    /// panel.
+    #[clap(long, default_value_t = false)]
+    weather_ring: bool,
+
+    /// Draw what the temperature felt like, the heat index or wind chill, as a line on the
+    /// temperature panel.
+    #[clap(long, default_value_t = false)]
+    feels_like: bool,
+
+    /// Draw the wind panel as a rose of how often the wind blew from each direction, split
+    /// by speed, from the station's hourly ISD observations.
+    #[clap(long, default_value_t = false)]
+    wind_rose: bool,
+
+    /// Show this in the header in place of the station name.
+    #[clap(long)]
+    title: Option<String>,
+
+    /// Show this under the title in place of the station id and coordinates.
+    #[clap(long)]
+    subtitle: Option<String>,
+
+    /// Move the station id and coordinates from under the title to the bottom left corner.
+    #[clap(long, default_value_t = false)]
+    details_in_footer: bool,
+
+    /// Put a small globe turned to the station at the right of the header, with a dot where
+    /// the station is.
+    #[clap(long, default_value_t = false)]
+    locator: bool,
+
+    /// Credit the source of the data and give the day of the render along the bottom.
+    #[clap(long, default_value_t = false)]
+    footer: bool,
+
+    /// Add this to the credits along the bottom, e.g. a URL or a handle. Implies --footer.
+    #[clap(long)]
+    footer_text: Option<String>,
+
+    /// Put this PNG or SVG logo in a corner of the image. An SVG logo only goes on SVG and
+    /// HTML renders.
+    #[clap(long)]
+    logo: Option<String>,
+
+    /// The corner of the image the --logo goes in.
+    #[clap(long, value_enum, default_value_t = Corner::BottomRight)]
+    logo_corner: Corner,
+
+    /// How opaque the --logo is, from 0 to 1.
+    #[clap(long, default_value_t = 1.0)]
+    logo_opacity: f64,
+
+    /// The size of the square that the --logo is fit into, in pixels.
+    #[clap(long, default_value_t = 48.0)]
+    logo_size: f64,
+
+    /// The language of month names and labels, and the style of numbers and dates, e.g.
+    /// de-DE.
+    #[clap(long, default_value = "en-US")]
+    locale: Locale,
+
+    /// How much of the header to draw above the charts.
+    #[clap(long, value_enum, default_value_t = Header::Full)]
+    header: Header,
+
+    /// The kind of file to write. By default, this follows the extension of --destination.
+    #[clap(long, value_enum)]
+    format: Option<Output>,
+
+    /// What draws a PNG.
+    #[clap(long, value_enum, default_value_t = Backend::Cairo)]
+    backend: Backend,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    fonts: FontArgs,
+
+    /// When writing an SVG, show the date and values of each day of a radial chart when
+    /// it is hovered.
+    #[clap(long, default_value_t = false)]
+    tooltips: bool,
+
+    /// Show the render in the terminal instead of writing it, using kitty's graphics
+    /// protocol or sixel. Without a value, the protocol is picked for the terminal.
+    #[clap(long, value_enum, num_args = 0..=1, default_missing_value = "auto")]
+    #[serde(skip)]
+    preview: Option<Preview>,
+
+    /// Print a JSON object for each file written instead of its path, with the station,
+    /// stats of its year and how many of the days it reported.
+    #[clap(long, default_value_t = false)]
+    #[serde(skip)]
+    output_json: bool,
+
+    /// Leave out the text chunks describing the station, year and version of the
+    /// generator that are otherwise written into the PNG.
+    #[clap(long, default_value_t = false)]
+    no_metadata: bool,
+
+    /// Write everything needed to reproduce this render, including the parsed station
+    /// data, to a JSON file.
+    #[clap(long)]
+    #[serde(skip)]
+    dump_repro: Option<String>,
+
+    /// Render from a file written by --dump-repro instead of the archives. Every other
+    /// option but --destination is taken from the file.
+    #[clap(long)]
+    #[serde(skip)]
+    from_repro: Option<String>,
+
+    /// Which months get a label on the month ring, and how they are written.
+    #[clap(long, value_enum, default_value_t = MonthLabels::Auto)]
+    month_labels: MonthLabels,
+
+    /// Keep the month labels upright rather than turning them with the ring, which reads
+    /// better for the months at the bottom.
+    #[clap(long, default_value_t = false)]
+    upright_month_labels: bool,
+
+    /// Collapse each panel to a wedge for each month, from its low to its high, with its
+    /// mean marked. Precipitation is the month's total. Cleaner than the days at small sizes.
+    #[clap(long, default_value_t = false)]
+    monthly: bool,
+
+    /// Color the wind band by Beaufort force rather than with a single color, with a legend
+    /// of the forces under the panel.
+    #[clap(long, default_value_t = false)]
+    beaufort: bool,
+
+    /// How the precipitation bars are scaled. A sqrt or log scale keeps one big storm from
+    /// flattening every other bar.
+    #[clap(long, value_enum, default_value_t = PrecipScale::Linear)]
+    precip_scale: PrecipScale,
+
+    /// Draw the precipitation of days with snow, by their snow flag or deeper snow on the
+    /// ground, apart from the days of rain, and count each of them.
+    #[clap(long, default_value_t = false)]
+    snow_split: bool,
+
+    /// Read settings from a TOML file. Keys are the long names of these options with
+    /// underscores, e.g. station_id = ["72309693727"], plus a [theme] table of colors and a
+    /// [center] table of the stats in the center of each panel, e.g.
+    /// temperature = [{ stat = "median" }, { stat = "summer-days", label = "HOT" }], from max,
+    /// mean, median, p10, p90, min, total, days, rain-days, snow-days, summer-days, frost-days
+    /// and ice-days. Options given on the command line take precedence over the file.
+    #[clap(long)]
+    #[serde(skip)]
+    config: Option<String>,
+
+    #[clap(skip)]
+    #[serde(default)]
+    theme: Theme,
+
+    #[clap(skip)]
+    #[serde(default)]
+    center: Center,
+
+    // whether a PDF gets a page for each month after the banner, for the report command.
+    #[clap(skip)]
+    #[serde(skip)]
+    report: bool,
+}
+
+impl Args {
+    // Parses the args of a render as they'd be given to the render command, for renders that
+    // don't come from the command line.
+    pub fn parse_from<I, T>(args: I) -> Result<Args, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = <Args as clap::Args>::augment_args(clap::Command::new("render"))
+            .try_get_matches_from(
+                iter::once(OsString::from("render")).chain(args.into_iter().map(Into::into)),
+            )?;
+        Args::from_arg_matches(&matches)?.with_config(&matches)
+    }
+
+    // the files a render reads other than the archives, which the preview window watches.
+    #[cfg(feature = "gui")]
+    pub(crate) fn watched_files(&self) -> Vec<String> {
+        self.config
+            .iter()
+            .chain(self.input.iter())
+            .cloned()
+            .collect()
+    }
+
+    // The years of NOAA's archives that a render reads, which update checks for newer
+    // copies. A chart over a span of years without --years reads older years too, but only
+    // the last of them can still change.
+    pub(crate) fn archive_years(&self, data: &Data) -> Result<Vec<i32>, Box<dyn Error>> {
+        if self.placeholder || self.from_repro.is_some() || !matches!(self.source, Source::Gsod) {
+            return Err("only renders from the GSOD archives can be updated".into());
+        }
+        let mut years = match (self.period, self.years) {
+            (Some(month), _) => vec![month.year().ordinal()],
+            (None, Some(years)) => years.iter().map(|year| year.ordinal()).collect(),
+            (None, None) => vec![self.year.resolve(data)?],
+        };
+        if let (Chart::Delta, Some(year)) = (self.chart, self.baseline_year) {
+            years.push(year);
+        }
+        Ok(years)
+    }
+
+    // the args of a render as a PDF report, see src/report.rs.
+    pub(crate) fn for_report(self) -> Args {
+        Args {
+            format: Some(Output::Pdf),
+            report: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    pub(crate) fn config(&self) -> Option<&str> {
+        self.config.as_deref()
+    }
+
+    // fills in every setting from the --config file that wasn't given on the command line.
+    // the merge happens on the serialized form of the args so that the file is read with
+    // the same names and types as a repro.
+    pub fn with_config(self, matches: &clap::ArgMatches) -> Result<Args, Box<dyn Error>> {
+        let path = match &self.config {
+            Some(path) => path.clone(),
+            None => return Ok(self),
+        };
+
+        let table: toml::Table = toml::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| format!("unable to read {}: {}", path, e))?;
+        let mut value = serde_json::to_value(&self)?;
+        let fields = value.as_object_mut().unwrap();
+        for (key, val) in table {
+            if !fields.contains_key(&key) {
+                return Err(format!("unknown setting in {}: {}", path, key).into());
+            }
+            let from_cli = matches.try_contains_id(&key).unwrap_or(false)
+                && matches.value_source(&key) == Some(ValueSource::CommandLine);
+            if !from_cli {
+                fields.insert(key, serde_json::to_value(val)?);
+            }
+        }
+
+        let args: Args = serde_json::from_value(value)
+            .map_err(|e| format!("invalid setting in {}: {}", path, e))?;
+        Ok(Args {
+            dump_repro: self.dump_repro,
+            from_repro: self.from_repro,
+            preview: self.preview,
+            output_json: self.output_json,
+            config: self.config,
+            ..args
+        })
+    }
+}
+
+// Bounds given on the command line as min:max, e.g. -10:100, like those that pin a scale
+// rather than fitting it to the data.
+#[derive(Debug, Clone, Copy)]
+pub struct Bounds {
+    min: f64,
+    max: f64,
+}
+
+impl Bounds {
+    pub(crate) fn range(self) -> Range {
+        Range::new(self.min, self.max)
+    }
+}
+
+impl std::str::FromStr for Bounds {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid bound: {}", v))
+        };
+        let (min, max) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected min:max, e.g. -10:100, not {}", s))?;
+        let (min, max) = (parse(min)?, parse(max)?);
+        if !min.is_finite() || !max.is_finite() || min >= max {
+            return Err(format!("{} isn't below {}", min, max));
+        }
+        Ok(Bounds { min, max })
+    }
+}
+
+impl std::fmt::Display for Bounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.min, self.max)
+    }
+}
+
+impl Serialize for Bounds {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bounds {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrayDays {
+    /// Drop the rows with a warning.
+    Drop,
+    /// Refuse to use the station.
+    Error,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fallback {
+    /// Fill the missing days from the other station.
+    Fill,
+    /// Use the other station in place of this one.
+    Replace,
+}
+
+impl MonthLabels {
+    // picks a concrete style for auto given the length of arc that the shortest month spans
+    // at the radius where its label is drawn.
+    fn resolve(
+        self,
+        ctx: &Context,
+        year: time::Year,
+        arc: f64,
+        locale: &Locale,
+    ) -> Result<Self, Box<dyn Error>> {
+        if !matches!(self, MonthLabels::Auto) {
+            return Ok(self);
+        }
+
+        let widest = |style: MonthLabels| -> Result<f64, Box<dyn Error>> {
+            let mut max = 0.0f64;
+            for (i, month) in year.months().enumerate() {
+                if let Some(label) = style.label_for(i, &month, locale) {
+                    max = max.max(ctx.text_extents(&label)?.x_advance());
+                }
+            }
+            Ok(max + 4.0)
+        };
+
+        Ok(if widest(MonthLabels::All)? <= arc {
+            MonthLabels::All
+        } else if widest(MonthLabels::Initials)? <= arc {
+            MonthLabels::Initials
+        } else if widest(MonthLabels::Alternate)? <= 2.0 * arc {
+            MonthLabels::Alternate
+        } else {
+            MonthLabels::Quarters
+        })
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compare {
+    Overlay,
+    Rows,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Chart {
+    Radial,
+    Heatmap,
+    Spiral,
+    Stripes,
+    BoxPlot,
+    Histogram,
+    Delta,
+}
+
+// Drops any days in the station that belong to a different year than the archive they were
+// found in, or fails if the policy does not allow it.
+pub(crate) fn check_year(
+    mut station: Station,
+    year: i32,
+    policy: StrayDays,
+) -> Result<Station, Box<dyn Error>> {
+    let n = station.retain_year(year);
+    if n > 0 {
+        match policy {
+            StrayDays::Drop => eprintln!(
+                "dropped {} rows outside of {} for station {}",
+                n,
+                year,
+                station.id()
+            ),
+            StrayDays::Error => {
+                return Err(format!(
+                    "station {} has {} rows outside of {}",
+                    station.id(),
+                    n,
+                    year
+                )
+                .into())
+            }
+        }
+    }
+    Ok(station)
+}
+
+pub(crate) fn find_station_for_year(
+    data: &Data,
+    year: i32,
+    station_id: &str,
+    policy: StrayDays,
+) -> Result<Station, Box<dyn Error>> {
+    let station = index::find_station(data, year, station_id)?
+        .ok_or_else(|| source::not_found(format!("uknown station: {}", station_id)))?;
+    check_year(station, year, policy)
+}
+
+// Loads the station from each year's archive, skipping the years in which the station did
+// not report.
+pub(crate) fn find_station_for_years(
+    data: &Data,
+    years: time::Years,
+    station_id: &str,
+    policy: StrayDays,
+) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+    let mut stations = Vec::new();
+    for year in years.iter() {
+        match index::find_station(data, year.ordinal(), station_id)? {
+            Some(station) => stations.push((year, check_year(station, year.ordinal(), policy)?)),
+            None => eprintln!("no data for station {} in {}", station_id, year),
+        }
+    }
+
+    if stations.is_empty() {
+        return Err(format!("uknown station: {}", station_id).into());
+    }
+
+    Ok(stations)
+}
+
+// A station as --fallback left it, along with the notice of what it did.
+type Settled = (Station, Option<String>);
+
+// Loads the stations for a render from the archives, a repro or made up data, recording
+// them in a new repro when one is being dumped. The name and location of each station are
+// kept for the destination and metadata.
+struct Loader<'a> {
+    data: &'a Data,
+    source: &'a dyn DataSource,
+    args: &'a Args,
+    dump: Option<RefCell<Repro>>,
+    // the repro being rendered again, which already holds the stations that --fallback
+    // settled on.
+    replay: Option<&'a Repro>,
+    stations: RefCell<HashMap<String, (String, Option<gsod::Location>)>>,
+    // the station that --fallback settled on for each year and id, and what it did.
+    fallbacks: RefCell<HashMap<(i32, String), Settled>>,
+    // what was done to the stations of the file being rendered that it should own up to,
+    // like standing in another station for one with gaps.
+    notices: RefCell<Vec<String>>,
+}
+
+impl Loader<'_> {
+    fn year(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        if !year.is_calendar() {
+            return self.trailing(year, id);
+        }
+        let station = self.with_fallback(year, id, self.source.station(year, id)?)?;
+        self.record(year, id, &station);
+        Ok(station)
+    }
+
+    // Stitches the days of a trailing year together from the calendar years it spans. The
+    // station is named as it is in the later of them.
+    fn trailing(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        let last = time::Day::new(year.end()).prev().date();
+        let years = time::Years::new(
+            time::Year::from_ordinal(year.start().year()),
+            time::Year::from_ordinal(last.year()),
+        );
+        source::stitch(&self.years(years, id)?, year.start(), last)
+            .ok_or_else(|| format!("no data for station {} in {}", id, year).into())
+    }
+
+    fn years(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        self.load_years(years, id, true)
+    }
+
+    // The years before the one drawn, which only go into its percentile band and so are
+    // taken as they are, without --fallback.
+    fn history(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        self.load_years(years, id, false)
+    }
+
+    fn load_years(
+        &self,
+        years: time::Years,
+        id: &str,
+        fallback: bool,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        let stations = self
+            .source
+            .stations(years, id)?
+            .into_iter()
+            .map(|(year, station)| match fallback {
+                true => Ok((year, self.with_fallback(year, id, station)?)),
+                false => Ok((year, station)),
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        if stations.is_empty() {
+            return Err(format!("uknown station: {}", id).into());
+        }
+        for (year, station) in &stations {
+            self.record(*year, id, station);
+        }
+        Ok(stations)
+    }
+
+    // Loads every station of the render, averaging them into one when --average asks for
+    // it.
+    fn stations(&self, year: time::Year) -> Result<Vec<Station>, Box<dyn Error>> {
+        let stations = self
+            .args
+            .station_id
+            .iter()
+            .map(|id| self.year(year, id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !self.args.average || stations.len() < 2 {
+            return Ok(stations);
+        }
+        let name = match &self.args.place {
+            Some(place) => format!("Average of {} stations near {}", stations.len(), place),
+            None => format!("Average of {} stations", stations.len()),
+        };
+        Ok(vec![Station::average(
+            self.args.station_id.join("+"),
+            name,
+            &stations,
+        )])
+    }
+
+    // Stands in the nearest complete station for one that is missing too many days, when
+    // --fallback asks for it. Each year of each station is only settled once, and its
+    // notice goes to the file being rendered each time the station is loaded for it.
+    fn with_fallback(
+        &self,
+        year: time::Year,
+        id: &str,
+        station: Station,
+    ) -> Result<Station, Box<dyn Error>> {
+        if self.args.fallback.is_none() {
+            return Ok(station);
+        }
+        let key = (year.ordinal(), String::from(id));
+        let cached = self.fallbacks.borrow().get(&key).cloned();
+        let (station, notice) = match cached {
+            Some(settled) => settled,
+            None => {
+                let settled = match self.replay {
+                    Some(repro) => (station, repro.notice(year.ordinal(), id)),
+                    None => self.fall_back(year, station)?,
+                };
+                if let Some(notice) = &settled.1 {
+                    eprintln!("{}", notice);
+                }
+                self.fallbacks.borrow_mut().insert(key, settled.clone());
+                settled
+            }
+        };
+        if let Some(notice) = notice {
+            let mut notices = self.notices.borrow_mut();
+            if !notices.contains(&notice) {
+                notices.push(notice);
+            }
+        }
+        Ok(station)
+    }
+
+    // Finds the station to show for one with gaps, along with a notice of what was done.
+    fn fall_back(&self, year: time::Year, station: Station) -> Result<Settled, Box<dyn Error>> {
+        let (fallback, loc) = match (self.args.fallback, station.location()) {
+            (Some(fallback), Some(loc)) => (fallback, loc),
+            _ => return Ok((station, None)),
+        };
+        let days = Series::for_each_day(year, station.days().iter(), |day| {
+            day.mean_temperature().map(|t| t.in_fahrenheit())
+        })
+        .summarize()
+        .count();
+        if days >= geocode::days_needed(year.ordinal()) {
+            return Ok((station, None));
+        }
+
+        let neighbor = geocode::complete_stations_near(self.data, loc, year.ordinal())?
+            .into_iter()
+            .find(|n| n.info.id() != station.id() && n.km <= self.args.fallback_radius);
+        let (km, info) = match neighbor {
+            Some(n) => (n.km, n.info),
+            None => {
+                eprintln!(
+                    "station {} only has {} days in {}, but there's no complete station within {} km",
+                    station.id(),
+                    days,
+                    year,
+                    self.args.fallback_radius
+                );
+                return Ok((station, None));
+            }
+        };
+        let other =
+            find_station_for_year(self.data, year.ordinal(), info.id(), self.args.stray_days)?;
+        let name = other.name().unwrap_or(other.id()).to_owned();
+        Ok(match fallback {
+            Fallback::Replace => (
+                other,
+                Some(format!(
+                    "{} had {} days of data in {}, showing {} ({:.0} km away) instead",
+                    station.id(),
+                    days,
+                    year,
+                    name,
+                    km
+                )),
+            ),
+            // every day of the station is kept, the other only fills in the days that it
+            // didn't report at all.
+            Fallback::Fill => {
+                let have = station
+                    .days()
+                    .iter()
+                    .map(|day| day.date())
+                    .collect::<std::collections::HashSet<_>>();
+                let filled = other
+                    .days()
+                    .iter()
+                    .filter(|day| !have.contains(&day.date()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if filled.is_empty() {
+                    return Ok((station, None));
+                }
+                let notice = format!(
+                    "{} days filled from {} ({:.0} km away)",
+                    filled.len(),
+                    name,
+                    km
+                );
+                let mut days = station.days().to_vec();
+                days.extend(filled);
+                days.sort_by_key(|day| day.date());
+                (station.with_days(days), Some(notice))
+            }
+        })
+    }
+
+    // Keeps the station loaded for an id, which --fallback may have swapped for another,
+    // under that id.
+    fn record(&self, year: time::Year, id: &str, station: &Station) {
+        if let Some(dump) = &self.dump {
+            let notice = self
+                .fallbacks
+                .borrow()
+                .get(&(year.ordinal(), String::from(id)))
+                .and_then(|(_, notice)| notice.clone());
+            dump.borrow_mut().add(year.ordinal(), id, station, notice);
+        }
+        self.stations
+            .borrow_mut()
+            .entry(String::from(id))
+            .or_insert_with(|| {
+                (
+                    String::from(station.name().unwrap_or(station.id())),
+                    station.location().cloned(),
+                )
+            });
+    }
+
+    fn name_of(&self, id: &str) -> String {
+        self.stations
+            .borrow()
+            .get(id)
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| String::from(id))
+    }
+
+    fn details_of(&self, id: &str) -> String {
+        describe_details(
+            id,
+            self.stations
+                .borrow()
+                .get(id)
+                .and_then(|(_, loc)| loc.as_ref()),
+        )
+    }
+
+    // the text chunks that describe where a render came from.
+    fn metadata(&self, year: time::Year) -> Vec<(&'static str, String)> {
+        let mut entries = vec![(
+            "Software",
+            format!("weather-banner {}", env!("CARGO_PKG_VERSION")),
+        )];
+        for id in &self.args.station_id {
+            entries.push(("Station ID", id.clone()));
+            entries.push(("Station Name", self.name_of(id)));
+            if let Some((_, Some(loc))) = self.stations.borrow().get(id) {
+                entries.push(("Location", format!("{:.4}, {:.4}", loc.lat(), loc.lng())));
+            }
+        }
+        entries.push(("Year", year.to_string()));
+        entries.push(("Units", String::from("°F, kts, in")));
+        entries.push(("Source", String::from(self.source.name())));
+        entries
+    }
+}
+
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    if let Some(preview) = args.preview {
+        return preview::show(&render_png_bytes(data, args)?, preview);
+    }
+    if args.output_json && args.destination == "-" {
+        return Err("--output-json and --destination - would both write to stdout".into());
+    }
+    run(data, args, |dst, output, report| {
+        // the image itself is the output, so there's no path to print after it.
+        if dst == "-" {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(&output)?;
+            stdout.flush()?;
+            return Ok(());
+        }
+        if is_upload(dst) {
+            upload(dst, output)?;
+        } else {
+            if let Some(dir) = Path::new(dst).parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(dst, output)?;
+        }
+        match report {
+            Some(report) => println!("{}", report),
+            None => println!("{}", dst),
+        }
+        Ok(())
+    })
+}
+
+// destinations that are URLs are uploaded rather than written, see src/upload.rs.
+fn is_upload(dst: &str) -> bool {
+    ["s3://", "gs://", "http://", "https://"]
+        .iter()
+        .any(|scheme| dst.starts_with(scheme))
+}
+
+#[cfg(feature = "upload")]
+fn upload(dst: &str, output: Vec<u8>) -> Result<(), Box<dyn Error>> {
+    super::upload::put(dst, output)
+}
+
+#[cfg(not(feature = "upload"))]
+fn upload(dst: &str, _: Vec<u8>) -> Result<(), Box<dyn Error>> {
+    Err(format!("uploading to {} needs the upload feature", dst).into())
+}
+
+// Renders the banner for the args as a PNG, for embedding the renderer elsewhere. The args
+// are used as they are for a render, except that nothing is written. Charts that render a
+// file for each of --years return the first.
+pub fn render_png_bytes(data: &Data, args: &Args) -> Result<Vec<u8>, Box<dyn Error>> {
+    let args = Args {
+        format: Some(Output::Png),
+        destination: String::new(),
+        dump_repro: None,
+        ..args.clone()
+    };
+    let mut png = None;
+    run(data, &args, |_, output, _| {
+        png.get_or_insert(output);
+        Ok(())
+    })?;
+    png.ok_or_else(|| "nothing was rendered".into())
+}
+
+// Renders each of the files of the args, passing them to emit along with their destination
+// and, for --output-json, the report of the file.
+fn run<F>(data: &Data, args: &Args, mut emit: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(&str, Vec<u8>, Option<String>) -> Result<(), Box<dyn Error>>,
+{
+    let repro = match &args.from_repro {
+        Some(path) => Some(Repro::read(path)?),
+        None => None,
+    };
+    let (args, destination, dump_repro, output_json) = (
+        repro.as_ref().map(|r| r.args()).unwrap_or(args),
+        &args.destination,
+        &args.dump_repro,
+        args.output_json,
+    );
+
+    // a station file is read before anything else, since it names the station and stdin can
+    // only be read once.
+    let station_file = match (&repro, &args.station_file, args.source) {
+        (None, Some(path), Source::Gsod) => Some(StationFile::read(path)?),
+        (None, Some(_), _) => return Err("--station-file is read in place of --source".into()),
+        _ => None,
+    };
+
+    // places and airport codes are looked up in ISD's station list, which only covers GSOD
+    // stations.
+    let resolved;
+    let args = match (&repro, args.placeholder, args.source, &station_file) {
+        (None, false, _, Some(file)) => {
+            resolved = Args {
+                station_id: vec![String::from(file.id())],
+                ..args.clone()
+            };
+            &resolved
+        }
+        (None, false, Source::Gsod | Source::GsodLegacy, None) => {
+            let station_id = match &args.place {
+                Some(place) => {
+                    let geocoder: Box<dyn Geocoder> = match &args.gazetteer {
+                        Some(path) => Box::new(Gazetteer::from_path(path)?),
+                        None => Box::new(Nominatim::new(data)),
+                    };
+                    let loc = geocoder.locate(place)?;
+                    let year = args.year.resolve(data)?;
+                    if args.average {
+                        let ids = geocode::complete_stations_near(data, &loc, year)?
+                            .into_iter()
+                            .filter(|n| n.km <= args.average_radius)
+                            .map(|n| String::from(n.info.id()))
+                            .collect::<Vec<_>>();
+                        if ids.is_empty() {
+                            return Err(format!(
+                                "no complete stations within {} km of {}",
+                                args.average_radius, place
+                            )
+                            .into());
+                        }
+                        eprintln!("averaging stations {}", ids.join(", "));
+                        ids
+                    } else {
+                        let station = geocode::nearest_station(data, &loc, year)?;
+                        vec![String::from(station.id())]
+                    }
+                }
+                None => args
+                    .station_id
+                    .iter()
+                    .map(|id| isd::resolve_station(data, id))
+                    .collect::<Result<_, _>>()?,
+            };
+            resolved = Args {
+                station_id,
+                ..args.clone()
+            };
+            &resolved
+        }
+        (_, _, _, _) if args.place.is_some() => {
+            return Err("--place only finds stations for --source gsod".into())
+        }
+        (None, false, _, _) if args.fallback.is_some() => {
+            return Err("--fallback only finds stations for --source gsod".into())
+        }
+        _ => args,
+    };
+
+    let mut opts = Options {
+        debug: args.debug,
+        downsample_by: args.downsample_by,
+        smooth: args.smooth,
+        envelope_window: args.envelope_window,
+        temperature_band: args.temperature_band,
+        sigma_window: args.sigma_window,
+        min_samples: args.min_samples,
+        temperature_range: args.temp_range.map(Bounds::range),
+        wind_range: args.wind_range.map(Bounds::range),
+        precipitation_range: args.precip_range.map(Bounds::range),
+        month_labels: args.month_labels,
+        callouts: args.callouts,
+        weather_ring: args.weather_ring,
+        feels_like: args.feels_like,
+        theme: args.theme.clone(),
+        center: args.center.clone(),
+        title: args.title.clone(),
+        subtitle: args.subtitle.clone(),
+        details_in_footer: args.details_in_footer,
+        locator: args.locator,
+        locale: args.locale,
+        header: args.header,
+        layout: args.layout,
+        panels: args.panels.clone(),
+        scale_sides: args.scale_side.clone(),
+        grid_rings: args.grid_rings,
+        radii: args.radii.range(),
+        month_band: args.month_band.range(),
+        start_month: args.start_month,
+        rotation: args.direction,
+        seasons: args.seasons.clone(),
+        week_ticks: args.week_ticks,
+        upright_month_labels: args.upright_month_labels,
+        monthly: args.monthly,
+        beaufort: args.beaufort,
+        precip_scale: args.precip_scale,
+        snow_split: args.snow_split,
+        hotspots: (args.tooltips || matches!(args.format, Some(Output::Html)))
+            .then(|| RefCell::new(Vec::new())),
+    };
+    if opts.radii.min() <= 0.0 || opts.radii.max() > 1.0 {
+        return Err("--radii must be fractions of the panel, between 0 and 1".into());
+    }
+    if opts.month_band.min() < 0.0 {
+        return Err("--month-band can't reach outside of the data".into());
+    }
+    if !(0.0..=1.0).contains(&args.logo_opacity) || args.logo_size <= 0.0 {
+        return Err("--logo-opacity must be between 0 and 1 and --logo-size above 0".into());
+    }
+    opts.center.check()?;
+    match (args.period, args.chart, args.station_id.len()) {
+        (None, _, _) | (Some(_), Chart::Radial, 1) => {}
+        (Some(_), Chart::Radial, _) => return Err("--period draws a single station".into()),
+        (Some(_), _, _) => return Err("--period only draws --chart radial".into()),
+    }
+    match (args.trailing_year, args.chart, args.layout) {
+        (false, _, _) | (true, Chart::Radial, Layout::Banner | Layout::Single) => {}
+        _ => return Err("--trailing-year only draws a radial banner or single panel".into()),
+    }
+    if args.trailing_year && args.wind_rose {
+        return Err("--wind-rose doesn't support --trailing-year".into());
+    }
+    match (args.layout, args.panels.len(), args.compare) {
+        (_, 0, _) => return Err("--panels needs at least one panel".into()),
+        (Layout::Single, 1, Compare::Rows) => {
+            return Err("--layout single overlays the stations, use --compare overlay".into())
+        }
+        (Layout::Single, 1, _) | (Layout::Banner | Layout::Grid, _, _) => {}
+        (Layout::Single, _, _) => {
+            return Err("--layout single needs exactly one of --panels".into())
+        }
+    }
+    let opened;
+    let source: &dyn DataSource = match (&repro, args.placeholder) {
+        (Some(repro), _) => repro,
+        (None, true) => &Placeholder,
+        (None, false) => match &station_file {
+            Some(file) => file,
+            None => {
+                opened = args
+                    .source
+                    .open(data, args.stray_days, args.input.as_deref())?;
+                opened.as_ref()
+            }
+        },
+    };
+    let year_num = match (args.period, args.year) {
+        (Some(month), _) => month.year().ordinal(),
+        (None, YearSpec::Latest) => source.latest_year()?,
+        (None, YearSpec::Current) => Local::now().year(),
+        (None, YearSpec::Year(year)) => year,
+    };
+
+    // a trailing year ends with the station's latest day, which is in the year so far
+    // unless the current archive doesn't have the station yet.
+    let trailing = match args.trailing_year {
+        true => Some(trailing_year(source, args, year_num)?),
+        false => None,
+    };
+
+    let loader = Loader {
+        data,
+        source,
+        args,
+        dump: dump_repro.as_ref().map(|_| {
+            // the year is pinned so that the repro doesn't drift once a newer archive is out.
+            RefCell::new(Repro::new(Args {
+                year: YearSpec::Year(year_num),
+                ..args.clone()
+            }))
+        }),
+        replay: repro.as_ref(),
+        stations: RefCell::new(HashMap::new()),
+        fallbacks: RefCell::new(HashMap::new()),
+        notices: RefCell::new(Vec::new()),
+    };
+
+    // charts of a single year render a file for each year in --years, the others draw all
+    // of the years together.
+    let batch = match (args.chart, args.layout) {
+        (Chart::Radial, Layout::Banner | Layout::Single) | (Chart::Heatmap | Chart::BoxPlot, _) => {
+            args.years
+        }
+        _ => None,
+    };
+    let ext = args.format.unwrap_or(Output::Png).extension();
+    let template = match (destination.as_str(), batch) {
+        ("", Some(_)) => format!("{{station}}-{{year}}.{}", ext),
+        ("", None) => format!("{{station}}.{}", ext),
+        (template, _) => String::from(template),
+    };
+    if batch.is_some() && !template.contains("{year}") {
+        return Err("--destination needs a {year} when rendering more than one year".into());
+    }
+
+    let years = match (batch, trailing) {
+        (Some(years), _) => years.iter().collect(),
+        (None, Some(year)) => vec![year],
+        (None, None) => vec![time::Year::from_ordinal(year_num)],
+    };
+    if args.shared_scales && batch.is_some() {
+        share_scales(&loader, &years, &mut opts)?;
+    }
+    let date = Local::now().date_naive();
+    for year in years {
+        let names = args
+            .station_id
+            .iter()
+            .map(|id| loader.name_of(id))
+            .collect::<Vec<_>>();
+        let dst = expand_destination(&template, &args.station_id, &names, year, date);
+        let output = args.format.unwrap_or_else(|| Output::for_destination(&dst));
+        loader.notices.borrow_mut().clear();
+        let rendered = write(&loader, year, output, &opts)?;
+        let report = match output_json {
+            true => {
+                let stations = loader.stations(year)?;
+                Some(serde_json::to_string(&report(
+                    &loader, &dst, output, year, &stations,
+                ))?)
+            }
+            false => None,
+        };
+        emit(&dst, rendered, report)?;
+    }
+
+    if let (Some(path), Some(dump)) = (dump_repro, loader.dump) {
+        dump.into_inner().write(path)?;
+    }
+    Ok(())
+}
+
+// The trailing year ending with the latest day of the first station, looking in the archive
+// of the current year before the latest complete one when the year isn't given.
+fn trailing_year(
+    source: &dyn DataSource,
+    args: &Args,
+    year_num: i32,
+) -> Result<time::Year, Box<dyn Error>> {
+    let id = args.station_id.first().ok_or("no station given")?;
+    let candidates = match args.year {
+        YearSpec::Latest => vec![Local::now().year(), year_num],
+        _ => vec![year_num],
+    };
+    for year in candidates {
+        let station = match source.station(time::Year::from_ordinal(year), id) {
+            Ok(station) => station,
+            Err(e) if source::is_not_found(e.as_ref()) => continue,
+            Err(e) => return Err(e),
+        };
+        if let Some(last) = station.days().iter().map(|day| day.date()).max() {
+            return Ok(time::Year::trailing(last));
+        }
+    }
+    Err(format!("no days of station {} to end a trailing year with", id).into())
+}
+
+// Pins the scales that aren't pinned already to the union of their ranges over all of the
+// years, for --shared-scales. The temperature scale also covers the feels like panel, which
+// is drawn on it once it is pinned.
+fn share_scales(
+    loader: &Loader,
+    years: &[time::Year],
+    opts: &mut Options,
+) -> Result<(), Box<dyn Error>> {
+    let mut shared: Option<(Range, Range, Range)> = None;
+    for &year in years {
+        let stations = loader.stations(year)?;
+        let history = load_history(loader, year)?;
+        let (temperature, _) = temperature_range_with_band(year, &stations, history.as_ref(), opts);
+        let temperature = match opts.panels.iter().any(|p| matches!(p, Panel::FeelsLike)) {
+            true => Range::union(&temperature, &feels_like_range_of(year, &stations, opts)),
+            false => temperature,
+        };
+        let wind = wind_range_of(year, &stations, opts);
+        let precipitation = precipitation_range_of(year, &stations, opts);
+        shared = Some(match shared {
+            Some((t, w, p)) => (
+                Range::union(&t, &temperature),
+                Range::union(&w, &wind),
+                Range::union(&p, &precipitation),
+            ),
+            None => (temperature, wind, precipitation),
+        });
+    }
+    if let Some((temperature, wind, precipitation)) = shared {
+        opts.temperature_range.get_or_insert(temperature);
+        opts.wind_range.get_or_insert(wind);
+        opts.precipitation_range.get_or_insert(precipitation);
+    }
+    Ok(())
+}
+
+// What --output-json prints for each file of a render.
+#[derive(Serialize)]
+struct Report<'a> {
+    output: &'a str,
+    format: Output,
+    source: &'static str,
+    year: i32,
+    stations: Vec<StationReport<'a>>,
+    // what was done to the stations, like filling their gaps from another station.
+    notices: Vec<String>,
+}
+
+// The stats of a station in a report, along with where it is and how complete its year is.
+#[derive(Serialize)]
+struct StationReport<'a> {
+    location: Option<&'a gsod::Location>,
+    days: usize,
+    days_reported: usize,
+    #[serde(flatten)]
+    stats: stats::Stats<'a>,
+}
+
+fn report<'a>(
+    loader: &Loader,
+    dst: &'a str,
+    output: Output,
+    year: time::Year,
+    stations: &'a [Station],
+) -> Report<'a> {
+    Report {
+        output: dst,
+        format: output,
+        source: loader.source.name(),
+        year: year.ordinal(),
+        stations: stations
+            .iter()
+            .map(|station| StationReport {
+                location: station.location(),
+                days: year.days().count(),
+                days_reported: station.days().len(),
+                // degree days from the stats command's default base.
+                stats: stats::stats_of(year, station, 65.0),
+            })
+            .collect(),
+        notices: loader.notices.borrow().clone(),
+    }
+}
+
+// Fills in the placeholders of a destination. {date} is the day of the render, which keeps
+// the output of scheduled renders apart.
+fn expand_destination(
+    template: &str,
+    station_ids: &[String],
+    station_names: &[String],
+    year: time::Year,
+    date: NaiveDate,
+) -> String {
+    template
+        .replace("{station}", &filename::sanitize(&station_ids.join("-")))
+        .replace(
+            "{station_name}",
+            &filename::sanitize(&station_names.join("-")),
+        )
+        .replace("{year}", &year.to_string())
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    // where a box of the given size goes in the corner of the image, inset from its edges.
+    fn place(self, w: f64, h: f64, width: f64, height: f64) -> (f64, f64) {
+        let inset = 20.0;
+        match self {
+            Corner::TopLeft => (inset, inset),
+            Corner::TopRight => (width - inset - w, inset),
+            Corner::BottomLeft => (inset, height - inset - h),
+            Corner::BottomRight => (width - inset - w, height - inset - h),
+        }
+    }
+}
+
+// Whether the --logo is an SVG, which cairo can't draw and so is spliced into SVG renders.
+fn is_svg(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    Cairo,
+    /// tiny-skia, which draws the radial banner of --format json without cairo, when built
+    /// with the skia feature.
+    Skia,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Output {
+    Png,
+    Svg,
+    /// A page embedding the SVG, with days highlighted on hover and a table of the data.
+    Html,
+    /// The prepared chart (series, scales, months and stats) for drawing it elsewhere.
+    Json,
+    Pdf,
+}
+
+impl Output {
+    // picks the kind of file by the extension of the destination.
+    fn for_destination(dst: &str) -> Output {
+        match Path::new(dst).extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("svg") => Output::Svg,
+            Some(ext) if ext.eq_ignore_ascii_case("html") => Output::Html,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Output::Json,
+            Some(ext) if ext.eq_ignore_ascii_case("pdf") => Output::Pdf,
+            _ => Output::Png,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Output::Png => "png",
+            Output::Svg => "svg",
+            Output::Html => "html",
+            Output::Json => "json",
+            Output::Pdf => "pdf",
+        }
+    }
+}
+
+fn write(
+    loader: &Loader,
+    year: time::Year,
+    output: Output,
+    opts: &Options,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let args = loader.args;
+    if let Some(hotspots) = &opts.hotspots {
+        hotspots.borrow_mut().clear();
+    }
+
+    match output {
+        Output::Png | Output::Pdf if args.logo.as_deref().is_some_and(is_svg) => {
+            Err("an SVG --logo only goes on --format svg or html".into())
+        }
+        Output::Png if matches!(args.backend, Backend::Skia) => {
+            let mut png = skia_png(loader, year, opts)?;
+            if !args.no_metadata {
+                png = png::add_text(&png, &loader.metadata(year))?;
+            }
+            Ok(png)
+        }
+        _ if matches!(args.backend, Backend::Skia) => Err("--backend skia only draws a PNG".into()),
+        Output::Png => {
+            let surface = ImageSurface::create(Format::ARgb32, args.width, args.height)?;
+            draw(loader, year, &Context::new(&surface)?, opts)?;
+            let mut png = Vec::new();
+            surface.write_to_png(&mut png)?;
+            if !args.no_metadata {
+                png = png::add_text(&png, &loader.metadata(year))?;
+            }
+            Ok(png)
+        }
+        Output::Svg | Output::Html => {
+            let surface =
+                SvgSurface::for_stream(args.width as f64, args.height as f64, Vec::new())?;
+            draw(loader, year, &Context::new(&surface)?, opts)?;
+            let svg = surface
+                .finish_output_stream()
+                .map_err(|e| e.error)?
+                .downcast::<Vec<u8>>()
+                .map_err(|_| "unable to finish svg")?;
+            let svg = match args.logo.as_deref().filter(|path| is_svg(path)) {
+                Some(path) => {
+                    let size = args.logo_size;
+                    let (x, y) =
+                        args.logo_corner
+                            .place(size, size, args.width as f64, args.height as f64);
+                    let align = match args.logo_corner {
+                        Corner::TopLeft => "xMinYMin",
+                        Corner::TopRight => "xMaxYMin",
+                        Corner::BottomLeft => "xMinYMax",
+                        Corner::BottomRight => "xMaxYMax",
+                    };
+                    svg::add_logo(
+                        &svg,
+                        &fs::read_to_string(path)
+                            .map_err(|e| format!("unable to read {}: {}", path, e))?,
+                        (x, y, size),
+                        align,
+                        args.logo_opacity,
+                    )?
+                }
+                None => *svg,
+            };
+            let svg = match &opts.hotspots {
+                Some(hotspots) => svg::add_hotspots(&svg, &hotspots.borrow())?,
+                None => svg,
+            };
+            match output {
+                Output::Html => {
+                    let names = args
+                        .station_id
+                        .iter()
+                        .map(|id| loader.name_of(id))
+                        .collect::<Vec<_>>();
+                    html::page(
+                        &format!("{} {}", names.join(" vs "), year),
+                        &svg,
+                        &opts.theme.background,
+                        &opts.theme.foreground,
+                    )
+                }
+                _ => Ok(svg),
+            }
+        }
+        Output::Json => {
+            let model = chart_model(loader, year, opts, "--format json")?;
+            let mut json = serde_json::to_vec_pretty(&model)?;
+            json.push(b'\n');
+            Ok(json)
+        }
+        Output::Pdf => {
+            let surface =
+                PdfSurface::for_stream(args.width as f64, args.height as f64, Vec::new())?;
+            let ctx = Context::new(&surface)?;
+            draw(loader, year, &ctx, opts)?;
+            if args.report {
+                let stations = loader.stations(year)?;
+                let station = stations.first().ok_or("no station given")?;
+                report::render_months(&surface, &ctx, year, station, opts)?;
+            }
+            drop(ctx);
+            let pdf = surface
+                .finish_output_stream()
+                .map_err(|e| e.error)?
+                .downcast::<Vec<u8>>()
+                .map_err(|_| "unable to finish pdf")?;
+            Ok(*pdf)
+        }
+    }
+}
+
+// Prepares the same chart that render draws, without drawing it, for the output named by
+// what.
+fn chart_model(
+    loader: &Loader,
+    year: time::Year,
+    opts: &Options,
+    what: &str,
+) -> Result<model::Chart, Box<dyn Error>> {
+    let args = loader.args;
+    match (args.chart, args.layout) {
+        (Chart::Radial, Layout::Banner | Layout::Single) => {}
+        _ => return Err(format!("{} only supports a radial banner or single panel", what).into()),
+    }
+    if args.wind_rose {
+        return Err(format!("{} doesn't support --wind-rose", what).into());
+    }
+    if args.period.is_some() {
+        return Err(format!("{} doesn't support --period", what).into());
+    }
+    if args.monthly {
+        return Err(format!("{} doesn't support --monthly", what).into());
+    }
+    if !matches!(args.precip_scale, PrecipScale::Linear) {
+        return Err(format!("{} only supports a linear --precip-scale", what).into());
+    }
+
+    let stations = loader.stations(year)?;
+    let history = load_history(loader, year)?;
+    let names = stations
+        .iter()
+        .map(|s| loader.name_of(s.id()))
+        .collect::<Vec<_>>();
+    chart::model(year, &stations, &names, history.as_ref(), opts)
+}
+
+// Draws the radial banner of the chart model with tiny-skia, as the renderers without cairo
+// draw it.
+#[cfg(feature = "skia")]
+fn skia_png(loader: &Loader, year: time::Year, opts: &Options) -> Result<Vec<u8>, Box<dyn Error>> {
+    let args = loader.args;
+    if !matches!(args.layout, Layout::Banner) {
+        return Err("--backend skia only draws a banner".into());
+    }
+    let model = chart_model(loader, year, opts, "--backend skia")?;
+    let fonts = raster::Fonts::load(&args.fonts)?;
+    let size = |n: i32| u32::try_from(n).map_err(|_| "invalid size");
+    raster::draw_png(
+        &model,
+        &opts.theme,
+        size(args.width)?,
+        size(args.height)?,
+        &fonts,
+    )
+}
+
+#[cfg(not(feature = "skia"))]
+fn skia_png(_: &Loader, _: time::Year, _: &Options) -> Result<Vec<u8>, Box<dyn Error>> {
+    Err("--backend skia needs the skia feature".into())
+}
+
+fn draw(
+    loader: &Loader,
+    year: time::Year,
+    ctx: &Context,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let args = loader.args;
+    let station_id = args.station_id.first().ok_or("no station given")?;
+    let years = args
+        .years
+        .unwrap_or_else(|| time::Years::new(time::Year::from_ordinal(year.ordinal() - 9), year));
+
+    let load_year = |id: &str| loader.year(year, id);
+    let load_years = |years: time::Years, id: &str| loader.years(years, id);
+
+    match args.chart {
+        Chart::Radial => match (args.period, args.layout) {
+            (Some(month), _) => period::render(
+                ctx,
+                args.width as f64,
+                args.height as f64,
+                month,
+                &load_year(station_id)?,
+                args.period_style,
+                opts,
+            )?,
+            (None, Layout::Banner | Layout::Single) => {
+                let history = load_history(loader, year)?;
+                let roses = load_wind_roses(loader, year)?;
+                render(
+                    ctx,
+                    args.width as f64,
+                    args.height as f64,
+                    year,
+                    &loader.stations(year)?,
+                    history.as_ref(),
+                    roses.as_deref(),
+                    args.compare,
+                    opts,
+                )?
+            }
+            (None, Layout::Grid) => render_grid(
+                ctx,
+                args.width as f64,
+                args.height as f64,
+                years,
+                &load_years(years, station_id)?,
+                opts,
+            )?,
+        },
+        Chart::Heatmap => heatmap::render(
+            ctx,
+            args.width as f64,
+            args.height as f64,
+            year,
+            &load_year(station_id)?,
+            args.metric,
+            opts,
+        )?,
+        Chart::Spiral => spiral::render(
+            ctx,
+            args.width as f64,
+            args.height as f64,
+            &load_years(years, station_id)?,
+            args.metric,
+            opts,
+        )?,
+        Chart::BoxPlot => boxplot::render(
+            ctx,
+            args.width as f64,
+            args.height as f64,
+            year,
+            &load_year(station_id)?,
+            args.metric,
+            opts,
+        )?,
+        Chart::Histogram => histogram::render(
+            ctx,
+            args.width as f64,
+            args.height as f64,
+            year,
+            &load_year(station_id)?,
+            args.metric,
+            opts,
+        )?,
+        Chart::Delta => {
+            let base_year = args
+                .baseline_year
+                .map(time::Year::from_ordinal)
+                .ok_or("--chart delta needs a --baseline-year")?;
+            delta::render(
+                ctx,
+                args.width as f64,
+                args.height as f64,
+                year,
+                &load_year(station_id)?,
+                base_year,
+                &loader.year(base_year, station_id)?,
+                args.metric,
+                opts,
+            )?
+        }
+        Chart::Stripes => stripes::render(
+            ctx,
+            args.width as f64,
+            args.height as f64,
+            years,
+            &load_years(years, station_id)?,
+            args.resolution,
+            opts,
+        )?,
+    }
+
+    let mut left = 0.0;
+    if opts.details_in_footer {
+        let details = args
+            .station_id
+            .iter()
+            .map(|id| loader.details_of(id))
+            .collect::<Vec<_>>()
+            .join("    ");
+        ctx.identity_matrix();
+        left = render_footer(ctx, &details, args.height as f64, opts)?;
+    }
+
+    let mut right = 0.0;
+    let notices = loader.notices.borrow();
+    if !notices.is_empty() {
+        ctx.identity_matrix();
+        right = render_notice(
+            ctx,
+            &notices.join("; "),
+            args.width as f64,
+            args.height as f64,
+            opts,
+        )?;
+    }
+
+    if args.footer || args.footer_text.is_some() {
+        let credits = [
+            Some(String::from(loader.source.name())),
+            Some(opts.locale.date(Local::now().date_naive())),
+            args.footer_text.clone(),
+        ];
+        ctx.identity_matrix();
+        render_credits(
+            ctx,
+            &credits,
+            left,
+            right,
+            args.width as f64,
+            args.height as f64,
+            opts,
+        )?;
+    }
+
+    if let Some(path) = args.logo.as_deref().filter(|path| !is_svg(path)) {
+        ctx.identity_matrix();
+        render_logo(ctx, path, args)?;
+    }
+
+    Ok(())
+}
+
+// Loads the ten years before the given one for the percentile band, when it is asked for.
+fn load_history(loader: &Loader, year: time::Year) -> Result<Option<Climatology>, Box<dyn Error>> {
+    let args = loader.args;
+    Ok(match (args.percentile_band, &args.station_id[..]) {
+        (false, _) => None,
+        (true, [id]) => Some(Climatology::from_stations(
+            &loader.history(
+                time::Years::new(
+                    time::Year::from_ordinal(year.ordinal() - 10),
+                    time::Year::from_ordinal(year.ordinal() - 1),
+                ),
+                id,
+            )?,
+            |day| day.mean_temperature().map(|t| t.in_fahrenheit()),
+        )),
+        (true, _) => return Err("--percentile-band needs a single --station-id".into()),
+    })
+}
+
+// Loads the hours of each station for the wind roses, when they are asked for. Each rose
+// takes up a whole panel, so there is no overlaying them.
+fn load_wind_roses(
+    loader: &Loader,
+    year: time::Year,
+) -> Result<Option<Vec<isd::WindRose>>, Box<dyn Error>> {
+    let args = loader.args;
+    match (args.wind_rose, args.compare, &args.station_id[..]) {
+        (false, _, _) => return Ok(None),
+        (true, Compare::Overlay, [_, _, ..]) => {
+            return Err("--wind-rose can't overlay stations, use --compare rows".into())
+        }
+        (true, _, _) => {}
+    }
+    args.station_id
+        .iter()
+        .map(|id| {
+            let rose =
+                isd::WindRose::from_hours(&isd::hours_for_year(loader.data, year.ordinal(), id)?);
+            if rose.is_empty() {
+                return Err(format!("station {} has no hourly winds in {}", id, year).into());
+            }
+            Ok(rose)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+// The radius of a panel in a default banner.
+const SINGLE_RADIUS: f64 = 250.0;
+
+#[allow(clippy::too_many_arguments)]
+fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    year: time::Year,
+    stations: &[Station],
+    history: Option<&Climatology>,
+    roses: Option<&[isd::WindRose]>,
+    compare: Compare,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    if opts.debug {
+        let n = opts.panels.len() as f64;
+        let dx = width / n;
+        ctx.save()?;
+        opts.theme.foreground.with_alpha(0.2).set(ctx);
+        for i in 0..n as usize {
+            if i % 2 != 0 {
+                continue;
+            }
+
+            ctx.new_path();
+            let x = dx * i as f64;
+            ctx.rectangle(x, 0.0, dx, height);
+            ctx.fill()?;
+        }
+        ctx.restore()?;
+    }
+
+    ctx.save()?;
+    let header_height = match stations {
+        [station] => render_header(
+            ctx,
+            station,
+            &describe_year(year, &opts.locale),
+            width,
+            opts,
+        )?,
+        _ => render_comparison_header(
+            ctx,
+            stations,
+            &describe_year(year, &opts.locale),
+            width,
+            compare,
+            opts,
+        )?,
+    };
+    ctx.restore()?;
+
+    let body_height = height - header_height;
+
+    if opts.debug {
+        ctx.save()?;
+        opts.theme.foreground.with_alpha(0.2).set(ctx);
+        ctx.new_path();
+        ctx.rectangle(0.0, 0.0, width, header_height);
+        ctx.fill()?;
+        ctx.restore()?;
+    }
+
+    check_panels(stations, opts)?;
+
+    // every station is drawn on the same scale for each metric.
+    let (temperature_range, band) = temperature_range_with_band(year, stations, history, opts);
+    let feels_like_range = feels_like_range_of(year, stations, opts);
+    let wind_range = wind_range_of(year, stations, opts);
+    let precipitation_range = precipitation_range_of(year, stations, opts);
+
+    let rows: Vec<&[Station]> = match compare {
+        Compare::Overlay => vec![stations],
+        Compare::Rows => stations.chunks(1).collect(),
+    };
+    let row_height = body_height / rows.len() as f64;
+    let n = opts.panels.len() as f64;
+    let r = (width / (2.0 * n)).min(row_height / 2.0);
+
+    // a single panel is laid out at the size it would have in a default banner and then
+    // scaled up, so that its text and lines grow along with it.
+    let (r, k) = match opts.layout {
+        Layout::Single => (SINGLE_RADIUS, r / SINGLE_RADIUS),
+        _ => (r, 1.0),
+    };
+    let rrange = Range::new(r * opts.radii.min(), r * opts.radii.max());
+
+    for (i, row) in rows.iter().enumerate() {
+        let cy = header_height + row_height * i as f64 + row_height / 2.0;
+
+        for (j, panel) in opts.panels.iter().enumerate() {
+            ctx.save()?;
+            ctx.translate((2 * j + 1) as f64 * width / (2.0 * n), cy);
+            ctx.scale(k, k);
+            if i == 0 {
+                render_title(
+                    ctx,
+                    opts.locale.label(panel.title()),
+                    0.0,
+                    -rrange.max() - 10.0,
+                    opts,
+                )?;
+            }
+            match panel {
+                Panel::Temperature if opts.monthly => {
+                    render_monthly(ctx, year, row, *panel, &rrange, &temperature_range, opts)?
+                }
+                Panel::FeelsLike if opts.monthly => {
+                    render_monthly(ctx, year, row, *panel, &rrange, &feels_like_range, opts)?
+                }
+                Panel::Wind if opts.monthly && roses.is_none() => {
+                    render_monthly(ctx, year, row, *panel, &rrange, &wind_range, opts)?
+                }
+                Panel::Precipitation if opts.monthly => {
+                    render_monthly(ctx, year, row, *panel, &rrange, &precipitation_range, opts)?
+                }
+                Panel::Temperature => render_temperature(
+                    ctx,
+                    year,
+                    row,
+                    band.as_ref(),
+                    &rrange,
+                    &temperature_range,
+                    opts,
+                )?,
+                Panel::FeelsLike => {
+                    render_feels_like(ctx, year, row, &rrange, &feels_like_range, opts)?
+                }
+                Panel::Wind => match roses {
+                    Some(roses) => render_wind_rose(ctx, &roses[i], &rrange, opts)?,
+                    None => render_wind(ctx, year, row, &rrange, &wind_range, opts)?,
+                },
+                Panel::Precipitation => {
+                    render_precipitation(ctx, year, row, &rrange, &precipitation_range, opts)?
+                }
+            }
+            if let (true, Panel::Precipitation) = (opts.weather_ring, panel) {
+                render_weather_ring(ctx, year, row, &rrange, opts)?;
+            }
+            // the days don't lie around a wind rose.
+            if roses.is_none() || !matches!(panel, Panel::Wind) {
+                record_hotspots(ctx, year, row, &rrange, *panel, opts);
+            }
+            ctx.restore()?;
+        }
+
+        if rows.len() > 1 {
+            ctx.save()?;
+            render_station_label(ctx, &row[0], 20.0, cy, opts)?;
+            ctx.restore()?;
+        }
+    }
+
+    Ok(())
+}
+
+// Colors that tell stations apart when they are overlaid on the same panels.
+const STATION_COLORS: [u32; 4] = [0xe45f91, 0x2fcbcc, 0xf2b134, 0x9f83c3];
+
+fn station_color(i: usize) -> Color {
+    Color::from_u32(STATION_COLORS[i % STATION_COLORS.len()])
+}
+
+// The wind speeds, in knots, at which each Beaufort force ends. Anything past the last is a
+// hurricane, force 12.
+const BEAUFORT: [f64; 12] = [
+    1.0, 4.0, 7.0, 11.0, 17.0, 22.0, 28.0, 34.0, 41.0, 48.0, 56.0, 64.0,
+];
+
+fn beaufort_force(kts: f64) -> usize {
+    BEAUFORT.iter().take_while(|end| kts >= **end).count()
+}
+
+// calm is a pale blue, breezes turn to purple, gales to orange and storms to red.
+fn beaufort_color(force: usize) -> Color {
+    Gradient::new(
+        [0xc7f0f0, 0x2fcbcc, 0x9f83c3, 0xf2b134, 0xe45f91, 0xd7191c]
+            .iter()
+            .map(|c| Color::from_u32(*c))
+            .collect(),
+    )
+    .at(Unit::new(force as f64 / BEAUFORT.len() as f64))
+}
+
+// Fills the band of the path on the context, with a ring of each Beaufort force's color
+// between the speeds where it starts and ends.
+fn fill_beaufort(ctx: &Context, rrange: &Range, range: &Range) -> Result<(), Box<dyn Error>> {
+    ctx.save()?;
+    ctx.clip();
+    let (first, last) = (beaufort_force(range.min()), beaufort_force(range.max()));
+    for force in first..=last {
+        let lo = match force {
+            0 => range.min(),
+            _ => BEAUFORT[force - 1].max(range.min()),
+        };
+        let hi = BEAUFORT
+            .get(force)
+            .map_or(range.max(), |end| end.min(range.max()));
+        let (ra, rb) = (
+            rrange.project(range.normalize_clamped(lo)),
+            rrange.project(range.normalize_clamped(hi)),
+        );
+        beaufort_color(force).with_alpha(0.5).set(ctx);
+        ctx.new_path();
+        ctx.arc(0.0, 0.0, rb, 0.0, TAU);
+        ctx.arc_negative(0.0, 0.0, ra, TAU, 0.0);
+        ctx.fill()?;
+    }
+    ctx.restore()?;
+    Ok(())
+}
+
+// A swatch for each force the wind range covers, labeled with its number, centered under
+// the ring.
+fn render_beaufort_legend(
+    ctx: &Context,
+    rrange: &Range,
+    range: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let (first, last) = (beaufort_force(range.min()), beaufort_force(range.max()));
+    let size = 12.0;
+    let x0 = -size * (last - first + 1) as f64 / 2.0;
+    let y = rrange.max() + 6.0;
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(8.0);
+    for (i, force) in (first..=last).enumerate() {
+        let x = x0 + size * i as f64;
+        beaufort_color(force).set(ctx);
+        ctx.new_path();
+        ctx.rectangle(x, y, size, size);
+        ctx.fill()?;
+        let label = force.to_string();
+        let exts = ctx.text_extents(&label)?;
+        opts.theme.background.set(ctx);
+        ctx.move_to(
+            x + (size - exts.x_advance()) / 2.0,
+            y + (size + exts.height()) / 2.0,
+        );
+        ctx.show_text(&label)?;
+    }
+    let label = "Bft";
+    let exts = ctx.text_extents(label)?;
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.move_to(
+        x0 - exts.x_advance() - 4.0,
+        y + (size + exts.height()) / 2.0,
+    );
+    ctx.show_text(label)?;
+    Ok(())
 }
 
-fn find_station<F, R: io::Read>(r: R, f: F) -> Result<Option<Station>, Box<dyn Error>>
-where
-    F: Fn(&Station) -> bool,
-{
-    let mut r = Archive::new(GzDecoder::new(r));
-    for entry in r.entries()? {
-        let station = gsod::Station::from_entry(&mut entry?)?;
-        if f(&station) {
-            return Ok(Some(station));
-        }
+fn render_comparison_header(
+    ctx: &Context,
+    stations: &[Station],
+    time_desc: &str,
+    width: f64,
+    compare: Compare,
+    opts: &Options,
+) -> Result<f64, Box<dyn Error>> {
+    let title = opts.title.clone().unwrap_or_else(|| {
+        stations
+            .iter()
+            .map(|s| shorten_station_name(s.name().unwrap_or("UNKNOWN")))
+            .collect::<Vec<_>>()
+            .join(" vs ")
+    });
+    let height = render_header_text(ctx, &title, "", time_desc, width, opts)?;
+    if !matches!(opts.header, Header::Full) {
+        return Ok(height);
     }
-    Ok(None)
-}
 
-pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
-    let station = find_station(
-        data.download_and_open(&gsod::url_for(args.year), format!("{}.tar.gz", args.year))?,
-        |s| s.id() == args.station_id,
-    )?
-    .ok_or(format!("uknown station: {}", args.station_id))?;
-
-    let surface = ImageSurface::create(Format::ARgb32, args.width, args.height)?;
-    let ctx = Context::new(&surface)?;
-    render(
-        &ctx,
-        args.width as f64,
-        args.height as f64,
-        time::Year::from_ordinal(args.year),
-        &station,
-        &Options {
-            debug: args.debug,
-            downsample_by: args.downsample_by,
-            smooth: args.smooth,
-        },
-    )?;
+    // the legend takes the place of the station details.
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(16.0);
+    let mut x = 20.0;
+    let y = height - 20.0;
+    for (i, station) in stations.iter().enumerate() {
+        let label = describe_station_details(station);
+        let exts = ctx.text_extents(&label)?;
+        if let Compare::Overlay = compare {
+            station_color(i).set(ctx);
+            ctx.new_path();
+            ctx.rectangle(x, y - exts.height(), 12.0, 12.0);
+            ctx.fill()?;
+            x += 18.0;
+        }
+        opts.theme.foreground.with_alpha(0.9).set(ctx);
+        ctx.new_path();
+        ctx.move_to(x, y - exts.height() - exts.y_bearing());
+        ctx.show_text(&label)?;
+        x += exts.x_advance() + 30.0;
+    }
 
-    let dst = if args.destination.is_empty() {
-        format!("{}.png", args.station_id)
-    } else {
-        args.destination.clone()
-    };
-    surface.write_to_png(&mut fs::File::create(&dst)?)?;
-    println!("{}", &dst);
-    Ok(())
+    Ok(height)
 }
 
-struct Options {
-    debug: bool,
-    downsample_by: u32,
-    smooth: bool,
+fn render_station_label(
+    ctx: &Context,
+    station: &gsod::Station,
+    x: f64,
+    y: f64,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let label = station.id();
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(10.0);
+    let exts = ctx.text_extents(label)?;
+    ctx.translate(x + exts.height(), y + exts.x_advance() / 2.0);
+    ctx.rotate(-TAU / 4.0);
+    ctx.new_path();
+    ctx.move_to(0.0, 0.0);
+    ctx.show_text(label)?;
+    Ok(())
 }
 
-fn render(
+// Renders a small temperature ring for each year, arranged in the grid that gives the
+// largest rings. All of the rings share a temperature range so they can be compared.
+fn render_grid(
     ctx: &Context,
     width: f64,
     height: f64,
-    year: time::Year,
-    station: &Station,
+    years: time::Years,
+    stations: &[(time::Year, Station)],
     opts: &Options,
 ) -> Result<(), Box<dyn Error>> {
-    Color::from_u32(0x3b3938).set(ctx);
+    let (_, station) = stations.last().ok_or("no stations to render")?;
+
+    opts.theme.background.set(ctx);
     ctx.rectangle(0.0, 0.0, width, height);
     ctx.fill()?;
 
-    let lx = width / 6.0;
-    let rx = 5.0 * width / 6.0;
-    let cx = width / 2.0;
+    ctx.save()?;
+    let period = format!("{} – {}", years.first(), years.last());
+    let header_height = render_header(ctx, station, &period, width, opts)?;
+    ctx.restore()?;
 
-    if opts.debug {
-        let n = 3.0;
-        let dx = width / n;
-        ctx.save()?;
-        Color::from_u32_with_alpha(0xffffff, 0.2).set(ctx);
-        for i in 0..n as usize {
-            if i % 2 != 0 {
-                continue;
-            }
+    let range = opts.temperature_range.clone().unwrap_or_else(|| {
+        stations
+            .iter()
+            .flat_map(|(_, station)| station.days().iter())
+            .flat_map(|day| [day.min_temperature(), day.max_temperature()])
+            .flatten()
+            .fold(Range::new(f64::MAX, f64::MIN), |r, t| {
+                Range::new(
+                    r.min().min(t.in_fahrenheit()),
+                    r.max().max(t.in_fahrenheit()),
+                )
+            })
+    });
 
-            ctx.new_path();
-            let x = dx * i as f64;
-            ctx.rectangle(x, 0.0, dx, height);
-            ctx.fill()?;
-        }
+    let footer_height = 30.0;
+    let body_height = height - header_height - footer_height;
+    let n = stations.len();
+    let (cols, rows) = (1..=n)
+        .map(|cols| (cols, n.div_ceil(cols)))
+        .max_by(|(ac, ar), (bc, br)| {
+            let a = (width / *ac as f64).min(body_height / *ar as f64);
+            let b = (width / *bc as f64).min(body_height / *br as f64);
+            a.total_cmp(&b)
+        })
+        .unwrap_or((1, 1));
+    let dx = width / cols as f64;
+    let dy = body_height / rows as f64;
+    let r = dx.min(dy) / 2.0;
+    let rrange = Range::new(r * 0.35, r * 0.85);
+
+    for (i, (year, station)) in stations.iter().enumerate() {
+        ctx.save()?;
+        ctx.translate(
+            dx * (i % cols) as f64 + dx / 2.0,
+            header_height + dy * (i / cols) as f64 + dy / 2.0,
+        );
+        render_mini_temperature(ctx, *year, station, &rrange, &range, opts)?;
         ctx.restore()?;
     }
 
     ctx.save()?;
-    let header_height = render_header(ctx, station, year, width, opts)?;
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(10.0);
+    let label = format!(
+        "{}  {}°F – {}°F",
+        opts.locale.label(Label::Temperature),
+        opts.locale.number(range.min(), 0),
+        opts.locale.number(range.max(), 0)
+    );
+    let exts = ctx.text_extents(&label)?;
+    ctx.new_path();
+    ctx.move_to(
+        width - 20.0 - exts.x_advance(),
+        height - footer_height / 2.0 + exts.height() / 2.0,
+    );
+    ctx.show_text(&label)?;
     ctx.restore()?;
 
-    let body_height = height - header_height;
+    Ok(())
+}
 
-    if opts.debug {
-        ctx.save()?;
-        Color::from_u32_with_alpha(0xffffff, 0.2).set(ctx);
+fn render_mini_temperature(
+    ctx: &Context,
+    year: time::Year,
+    station: &gsod::Station,
+    rrange: &Range,
+    range: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let temps = temperature_series(year, station, Some(range), opts);
+
+    // faint spokes at the month boundaries stand in for the month labels.
+    ctx.save()?;
+    orient(ctx, year, opts);
+    opts.theme.foreground.with_alpha(0.08).set(ctx);
+    ctx.set_line_width(1.0);
+    let num_days = year.duration().num_days() as f64;
+    for month in year.months() {
+        let t = month.start().signed_duration_since(year.start()).num_days() as f64 / num_days
+            * TAU
+            - TAU / 4.0;
         ctx.new_path();
-        ctx.rectangle(0.0, 0.0, width, header_height);
-        ctx.fill()?;
-        ctx.restore()?;
+        ctx.move_to(rrange.min() * t.cos(), rrange.min() * t.sin());
+        ctx.line_to(rrange.max() * t.cos(), rrange.max() * t.sin());
+        ctx.stroke()?;
     }
-
-    let r = lx.min(body_height / 2.0);
-    let rrange = Range::new(r * 0.6, r * 0.9);
+    ctx.restore()?;
 
     ctx.save()?;
-    ctx.translate(lx, header_height + body_height / 2.0);
-    render_title(ctx, "TEMPERATURE", 0.0, -rrange.max() - 10.0)?;
-    render_temperature(ctx, year, station, &rrange, opts)?;
+    orient(ctx, year, opts);
+    render_radial_range(
+        ctx,
+        &temps.min,
+        &temps.max,
+        rrange,
+        Some(&opts.theme.temperature_band.with_alpha(0.1)),
+        Some(&opts.theme.temperature_band),
+        opts.smooth,
+    )?;
     ctx.restore()?;
 
     ctx.save()?;
-    ctx.translate(cx, header_height + body_height / 2.0);
-    render_title(ctx, "WIND", 0.0, -rrange.max() - 10.0)?;
-    render_wind(ctx, year, station, &rrange, opts)?;
+    orient(ctx, year, opts);
+    render_radial_series(
+        ctx,
+        &temps.mean,
+        rrange,
+        &opts.theme.temperature_line,
+        opts.smooth,
+    )?;
     ctx.restore()?;
 
     ctx.save()?;
-    ctx.translate(rx, header_height + body_height / 2.0);
-    render_title(ctx, "PRECIPITATION", 0.0, -rrange.max() - 10.0)?;
-    render_precipitation(ctx, year, station, &rrange, opts)?;
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.select_font_face("HelveticaNeue-Thin", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size((rrange.min() * 0.5).max(8.0));
+    let label = format!("{}", year);
+    let exts = ctx.text_extents(&label)?;
+    ctx.new_path();
+    ctx.move_to(-exts.x_advance() / 2.0, exts.height() / 2.0);
+    ctx.show_text(&label)?;
     ctx.restore()?;
 
     Ok(())
 }
 
-fn render_header(
+pub(crate) fn render_header(
     ctx: &Context,
     station: &gsod::Station,
-    year: time::Year,
+    time_desc: &str,
+    width: f64,
+    opts: &Options,
+) -> Result<f64, Box<dyn Error>> {
+    let title = match &opts.title {
+        Some(title) => title.clone(),
+        None => shorten_station_name(station.name().unwrap_or("UNKNOWN")),
+    };
+    let details = match (&opts.subtitle, opts.details_in_footer) {
+        (Some(subtitle), _) => subtitle.clone(),
+        (None, true) => String::new(),
+        (None, false) => describe_station_details(station),
+    };
+    let d = match opts.header {
+        Header::Full => 64.0,
+        _ => 32.0,
+    };
+    match (opts.locator, station.location(), opts.header) {
+        (true, Some(loc), Header::Minimal | Header::Full) => {
+            let height =
+                render_header_text(ctx, &title, &details, time_desc, width - d - 20.0, opts)?;
+            locator::render(
+                ctx,
+                width - 20.0 - d / 2.0,
+                height / 2.0,
+                d / 2.0,
+                loc,
+                opts,
+            )?;
+            Ok(height)
+        }
+        _ => render_header_text(ctx, &title, &details, time_desc, width, opts),
+    }
+}
+
+// The station details in small print along the bottom of the image, for when the header
+// has been given over to a friendlier title.
+fn render_footer(
+    ctx: &Context,
+    details: &str,
+    height: f64,
+    opts: &Options,
+) -> Result<f64, Box<dyn Error>> {
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(12.0);
+    ctx.new_path();
+    ctx.move_to(20.0, height - 12.0);
+    ctx.show_text(details)?;
+    Ok(ctx.text_extents(details)?.x_advance())
+}
+
+// Owns up to what was done to the data in the bottom right corner, out of the way of the
+// details that can go in the footer.
+fn render_notice(
+    ctx: &Context,
+    notice: &str,
+    width: f64,
+    height: f64,
+    opts: &Options,
+) -> Result<f64, Box<dyn Error>> {
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(12.0);
+    let exts = ctx.text_extents(notice)?;
+    ctx.new_path();
+    ctx.move_to(width - 20.0 - exts.x_advance(), height - 12.0);
+    ctx.show_text(notice)?;
+    Ok(exts.x_advance())
+}
+
+// Paints a PNG logo into the --logo-corner, fit to --logo-size.
+fn render_logo(ctx: &Context, path: &str, args: &Args) -> Result<(), Box<dyn Error>> {
+    let logo = ImageSurface::create_from_png(&mut fs::File::open(path)?)
+        .map_err(|e| format!("unable to read {}: {}", path, e))?;
+    let scale = args.logo_size / logo.width().max(logo.height()).max(1) as f64;
+    let (w, h) = (logo.width() as f64 * scale, logo.height() as f64 * scale);
+    let (x, y) = args
+        .logo_corner
+        .place(w, h, args.width as f64, args.height as f64);
+    ctx.save()?;
+    ctx.translate(x, y);
+    ctx.scale(scale, scale);
+    ctx.set_source_surface(&logo, 0.0, 0.0)?;
+    ctx.paint_with_alpha(args.logo_opacity)?;
+    ctx.restore()?;
+    Ok(())
+}
+
+// Centers the credits along the bottom of the image, or as near to the center as they go
+// between whatever is in the corners, which reaches in left and right of the sides. When they
+// don't fit, the day of the render goes first and then the text shrinks, down to a size
+// that's still legible.
+#[allow(clippy::too_many_arguments)]
+fn render_credits(
+    ctx: &Context,
+    credits: &[Option<String>; 3],
+    left: f64,
+    right: f64,
+    width: f64,
+    height: f64,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let inset = |w: f64| if w > 0.0 { w + 40.0 } else { 20.0 };
+    let (x0, x1) = (inset(left), width - inset(right));
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(12.0);
+    let join = |skip: Option<usize>| {
+        credits
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != skip)
+            .filter_map(|(_, c)| c.as_deref())
+            .collect::<Vec<_>>()
+            .join("  ·  ")
+    };
+    let mut text = join(None);
+    if ctx.text_extents(&text)?.x_advance() > x1 - x0 {
+        text = join(Some(1));
+    }
+    let w = ctx.text_extents(&text)?.x_advance();
+    if w > x1 - x0 {
+        ctx.set_font_size((12.0 * (x1 - x0) / w).max(9.0));
+    }
+
+    let w = ctx.text_extents(&text)?.x_advance();
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.new_path();
+    ctx.move_to(((width - w) / 2.0).min(x1 - w).max(x0), height - 12.0);
+    ctx.show_text(&text)?;
+    Ok(())
+}
+
+fn render_header_text(
+    ctx: &Context,
+    title: &str,
+    details: &str,
+    time_desc: &str,
     width: f64,
     opts: &Options,
 ) -> Result<f64, Box<dyn Error>> {
+    let title_size = match opts.header {
+        Header::None => return Ok(0.0),
+        Header::Minimal => 24.0,
+        Header::Full => 42.0,
+    };
     let xoff = 20.0;
     let yoff = 20.0;
 
-    Color::from_u32_with_alpha(0xffffff, 0.9).set(ctx);
+    opts.theme.foreground.with_alpha(0.9).set(ctx);
+
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(24.0);
+    let time_desc_exts = ctx.text_extents(time_desc)?;
 
-    let title = shorten_station_name(station.name().unwrap_or("UNKNOWN"));
+    // shrink the title when it would otherwise run into the time description.
     ctx.select_font_face("HelveticaNeue-Thin", FontSlant::Normal, FontWeight::Normal);
-    ctx.set_font_size(42.0);
-    let title_exts = ctx.text_extents(&title)?;
+    ctx.set_font_size(title_size);
+    let avail = width - 2.0 * xoff - time_desc_exts.width() - 40.0;
+    let title_width = ctx.text_extents(title)?.width();
+    if title_width > avail && avail > 0.0 {
+        ctx.set_font_size(title_size * avail / title_width);
+    }
+    let title_exts = ctx.text_extents(title)?;
     ctx.new_path();
     ctx.move_to(xoff, yoff - title_exts.y_bearing());
-    ctx.show_text(&title)?;
+    ctx.show_text(title)?;
 
-    let time_desc = describe_year(year);
     ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
     ctx.set_font_size(24.0);
-    let time_desc_exts = ctx.text_extents(&time_desc)?;
     ctx.new_path();
     ctx.move_to(
         width - yoff - time_desc_exts.width(),
         yoff - title_exts.y_bearing(),
     );
-    ctx.show_text(&time_desc)?;
+    ctx.show_text(time_desc)?;
+
+    if let Header::Minimal = opts.header {
+        return Ok(2.0 * yoff + title_exts.height());
+    }
 
-    let details = describe_station_details(station);
     ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
     ctx.set_font_size(16.0);
-    let details_exts = ctx.text_extents(&details)?;
+    let details_exts = ctx.text_extents(if details.is_empty() { "X" } else { details })?;
     ctx.new_path();
     ctx.move_to(
         xoff,
         yoff + title_exts.height() * 1.3 - details_exts.y_bearing(),
     );
-    ctx.show_text(&details)?;
+    ctx.show_text(details)?;
 
     if opts.debug {
         Color::from_u32(0xff9900).set(ctx);
@@ -221,7 +2605,13 @@ fn render_header(
     Ok(2.0 * yoff + title_exts.height() * 1.3 + details_exts.height())
 }
 
-fn render_title(ctx: &Context, title: &str, x: f64, y: f64) -> Result<(), Box<dyn Error>> {
+pub(crate) fn render_title(
+    ctx: &Context,
+    title: &str,
+    x: f64,
+    y: f64,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
     ctx.save()?;
     let font = Font::new(
         "HelveticaNeue-Medium",
@@ -230,7 +2620,7 @@ fn render_title(ctx: &Context, title: &str, x: f64, y: f64) -> Result<(), Box<dy
         12.0,
     );
     font.set(ctx);
-    Color::from_u32_with_alpha(0xffffff, 0.6).set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
     let exts = ctx.text_extents(title)?;
     ctx.new_path();
     ctx.move_to(x - exts.width() / 2.0, y);
@@ -242,103 +2632,207 @@ fn render_title(ctx: &Context, title: &str, x: f64, y: f64) -> Result<(), Box<dy
 fn render_temperature(
     ctx: &Context,
     year: time::Year,
-    station: &gsod::Station,
+    stations: &[Station],
+    band: Option<&(Series, Series)>,
     rrange: &Range,
+    range: &Range,
     opts: &Options,
 ) -> Result<(), Box<dyn Error>> {
-    let min_temps = Series::for_each_day(year, station.days().iter(), |day| {
-        day.min_temperature().map(|t| t.in_fahrenheit())
-    });
+    let temps = stations
+        .iter()
+        .map(|station| temperature_series(year, station, Some(range), opts))
+        .collect::<Vec<_>>();
 
-    let max_temps = Series::for_each_day(year, station.days().iter(), |day| {
-        day.max_temperature().map(|t| t.in_fahrenheit())
-    });
+    // let's draw the months
+    ctx.save()?;
+    render_months(ctx, year, &opts.month_range(rrange), opts)?;
+    ctx.restore()?;
 
-    let mean_temps = Series::for_each_day(year, station.days().iter(), |day| {
-        day.mean_temperature().map(|t| t.in_fahrenheit())
-    });
+    // let's draw the scales
+    ctx.save()?;
+    let scale = Scale::from_range(range, 5.0)?;
+    render_scales(ctx, &scale, range, rrange, "°F", Panel::Temperature, opts)?;
+    ctx.restore()?;
 
-    let range = Range::intersect(max_temps.range(), min_temps.range());
+    render_seasons(ctx, year, stations, rrange, Panel::Temperature, opts)?;
 
-    let min_temps = min_temps.with_range(&range);
-    let max_temps = max_temps.with_range(&range);
-    let mean_temps = mean_temps.with_range(&range);
+    if let Some((low, high)) = band {
+        ctx.save()?;
+        orient(ctx, year, opts);
+        render_radial_range(
+            ctx,
+            low,
+            high,
+            rrange,
+            Some(&opts.theme.foreground.with_alpha(0.15)),
+            None,
+            opts.smooth,
+        )?;
+        ctx.restore()?;
+    }
 
-    let avg_mean_temp = mean_temps.values().iter().fold(0.0, |sum, val| sum + val)
-        / mean_temps.values().len() as f64;
+    for (i, t) in temps.iter().enumerate() {
+        let (band, line) = match temps.len() {
+            1 => (opts.theme.temperature_band, opts.theme.temperature_line),
+            _ => (station_color(i), station_color(i)),
+        };
 
-    let min_temps = if opts.downsample_by > 1 {
-        min_temps.downsample_by(opts.downsample_by as usize, |vals| {
-            vals.iter().fold(f64::MAX, |min, val| min.min(*val))
-        })
-    } else {
-        min_temps
-    };
+        // temperature range
+        if !matches!(opts.temperature_band, TemperatureBand::Sigma) {
+            ctx.save()?;
+            orient(ctx, year, opts);
+            render_radial_range(
+                ctx,
+                &t.min,
+                &t.max,
+                rrange,
+                Some(&band.with_alpha(0.1)),
+                Some(&band),
+                opts.smooth,
+            )?;
+            ctx.restore()?;
+        }
 
-    let max_temps = if opts.downsample_by > 1 {
-        max_temps.downsample_by(opts.downsample_by as usize, |vals| {
-            vals.iter().fold(f64::MIN, |max, val| max.max(*val))
-        })
-    } else {
-        max_temps
-    };
+        // a standard deviation either side of the mean, which is only outlined when it is
+        // the only band.
+        if let Some((low, high)) = &t.sigma {
+            let outline = match opts.temperature_band {
+                TemperatureBand::Sigma => Some(&band),
+                _ => None,
+            };
+            ctx.save()?;
+            orient(ctx, year, opts);
+            render_radial_range(
+                ctx,
+                low,
+                high,
+                rrange,
+                Some(&band.with_alpha(0.25)),
+                outline,
+                opts.smooth,
+            )?;
+            ctx.restore()?;
+        }
 
-    let mean_temps = if opts.downsample_by > 1 {
-        mean_temps.downsample_by(opts.downsample_by as usize, |vals| {
-            vals.iter().fold(0.0, |sum, val| sum + val) / vals.len() as f64
-        })
-    } else {
-        mean_temps
-    };
+        ctx.save()?;
+        orient(ctx, year, opts);
+        render_radial_series(ctx, &t.mean, rrange, &line, opts.smooth)?;
+        ctx.restore()?;
 
-    let range = min_temps.range();
+        if opts.feels_like {
+            let color = match temps.len() {
+                1 => opts.theme.feels_like,
+                _ => station_color(i).with_alpha(0.5),
+            };
+            ctx.save()?;
+            orient(ctx, year, opts);
+            ctx.set_dash(&[3.0, 2.0], 0.0);
+            render_radial_series(
+                ctx,
+                &feels_like_series(year, &stations[i], range, opts),
+                rrange,
+                &color,
+                opts.smooth,
+            )?;
+            ctx.restore()?;
+        }
+    }
 
-    // let's draw the months
     ctx.save()?;
-    render_months(
+    orient(ctx, year, opts);
+    render_sparse_days(
         ctx,
-        year,
-        &Range::new(rrange.min() - 40.0, rrange.min() - 5.0),
+        &temps.iter().map(|t| &t.samples).collect::<Vec<_>>(),
+        rrange,
+        opts,
     )?;
     ctx.restore()?;
 
-    // let's draw the scales
     ctx.save()?;
-    let scale = Scale::from_range(range, 5.0);
-    render_scales(ctx, &scale, range, rrange, "°F", Direction::Left)?;
+    render_center_text(
+        ctx,
+        &center_stats(year, stations, Panel::Temperature, opts)
+            .unwrap_or_else(|| temperature_stats(stations, &temps, opts)),
+        &Font::new(
+            "HelveticaNeue-Medium",
+            FontSlant::Normal,
+            FontWeight::Bold,
+            11.0,
+        ),
+        &Font::new(
+            "HelveticaNeue-Thin",
+            FontSlant::Normal,
+            FontWeight::Normal,
+            32.0,
+        ),
+        &opts.theme.foreground.with_alpha(0.6),
+        opts,
+    )?;
     ctx.restore()?;
 
-    // temperature range
+    if let (true, [station]) = (opts.callouts, stations) {
+        ctx.save()?;
+        render_callouts(ctx, &stats::indicators_of(year, station), rrange, opts)?;
+        ctx.restore()?;
+    }
+
+    Ok(())
+}
+
+// Draws what the mean temperature felt like over the mean temperature itself.
+fn render_feels_like(
+    ctx: &Context,
+    year: time::Year,
+    stations: &[Station],
+    rrange: &Range,
+    range: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
     ctx.save()?;
-    render_radial_range(
-        ctx,
-        &min_temps,
-        &max_temps,
-        rrange,
-        Some(&Color::from_u32_with_alpha(0x6eb078, 0.1)),
-        Some(&Color::from_u32(0x6eb078)),
-        opts.smooth,
-    )?;
+    render_months(ctx, year, &opts.month_range(rrange), opts)?;
     ctx.restore()?;
 
     ctx.save()?;
-    render_radial_series(
-        ctx,
-        &mean_temps,
-        rrange,
-        &Color::from_u32(0xe45f91),
-        opts.smooth,
-    )?;
+    let scale = Scale::from_range(range, 5.0)?;
+    render_scales(ctx, &scale, range, rrange, "°F", Panel::FeelsLike, opts)?;
     ctx.restore()?;
 
+    render_seasons(ctx, year, stations, rrange, Panel::FeelsLike, opts)?;
+
+    for (i, station) in stations.iter().enumerate() {
+        let color = match stations.len() {
+            1 => opts.theme.feels_like,
+            _ => station_color(i),
+        };
+
+        ctx.save()?;
+        orient(ctx, year, opts);
+        render_radial_series(
+            ctx,
+            &mean_temperature_series(year, station, range, opts),
+            rrange,
+            &color.with_alpha(0.3),
+            opts.smooth,
+        )?;
+        ctx.restore()?;
+
+        ctx.save()?;
+        orient(ctx, year, opts);
+        render_radial_series(
+            ctx,
+            &feels_like_series(year, station, range, opts),
+            rrange,
+            &color,
+            opts.smooth,
+        )?;
+        ctx.restore()?;
+    }
+
     ctx.save()?;
     render_center_text(
         ctx,
-        &[
-            (String::from("MAX"), format!("{:.1}°F", range.max())),
-            (String::from("AVG"), format!("{:.1}°F", avg_mean_temp)),
-            (String::from("MIN"), format!("{:.1}°F", range.min())),
-        ],
+        &center_stats(year, stations, Panel::FeelsLike, opts)
+            .unwrap_or_else(|| feels_like_stats(year, stations, opts)),
         &Font::new(
             "HelveticaNeue-Medium",
             FontSlant::Normal,
@@ -351,7 +2845,7 @@ fn render_temperature(
             FontWeight::Normal,
             32.0,
         ),
-        &Color::from_u32_with_alpha(0xffffff, 0.6),
+        &opts.theme.foreground.with_alpha(0.6),
         opts,
     )?;
     ctx.restore()?;
@@ -359,7 +2853,35 @@ fn render_temperature(
     Ok(())
 }
 
-fn render_center_text(
+// Lists the frost, ice and summer day counts in the lower right corner of the panel, just
+// outside of the ring.
+fn render_callouts(
+    ctx: &Context,
+    indicators: &stats::Indicators,
+    rrange: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(10.0);
+    let lines = [
+        (indicators.summer_days, Label::SummerDays),
+        (indicators.frost_days, Label::FrostDays),
+        (indicators.ice_days, Label::IceDays),
+    ]
+    .map(|(n, label)| format!("{} {}", n, opts.locale.label(label)));
+    let mut y = rrange.max();
+    for line in lines.iter().rev() {
+        let exts = ctx.text_extents(line)?;
+        ctx.new_path();
+        ctx.move_to(rrange.max() * 0.8, y);
+        ctx.show_text(line)?;
+        y -= exts.height() * 1.6;
+    }
+    Ok(())
+}
+
+pub(crate) fn render_center_text(
     ctx: &Context,
     labels: &[(String, String)],
     label_font: &Font,
@@ -424,20 +2946,30 @@ fn render_center_text(
     Ok(())
 }
 
-fn render_months(ctx: &Context, year: time::Year, r: &Range) -> Result<(), Box<dyn Error>> {
+// Turns the context so that the days of the year, drawn clockwise from the top starting with
+// January 1st, start at --start-month and run in the --direction instead. Text drawn this way
+// would be mirrored, so only the rings and marks of the days are.
+pub(crate) fn orient(ctx: &Context, year: time::Year, opts: &Options) {
+    if let Rotation::Ccw = opts.rotation {
+        ctx.scale(-1.0, 1.0);
+    }
+    ctx.rotate(-opts.ring_start(year) * TAU);
+}
+
+pub(crate) fn render_months(
+    ctx: &Context,
+    year: time::Year,
+    r: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
     let num_days = year.duration().num_days();
-    let months: Vec<(f64, f64)> = year
-        .months()
-        .map(|month| {
-            let s = month.start().signed_duration_since(year.start()).num_days();
-            let e = month.end().signed_duration_since(year.start()).num_days();
-            (s as f64 / num_days as f64, e as f64 / num_days as f64)
-        })
-        .collect();
+    let months = month_spans(year);
 
     let dt = 0.5 * TAU / num_days as f64;
 
-    Color::from_u32_with_alpha(0xffffff, 0.05).set(ctx);
+    ctx.save()?;
+    orient(ctx, year, opts);
+    opts.theme.foreground.with_alpha(0.05).set(ctx);
     for (s, e) in months.iter() {
         let s = s * TAU + dt;
         let e = e * TAU - dt;
@@ -447,17 +2979,53 @@ fn render_months(ctx: &Context, year: time::Year, r: &Range) -> Result<(), Box<d
         ctx.fill()?;
     }
 
-    Color::from_u32(0xffffff).set(ctx);
+    // a fine tick just outside of the month ring where each week starts.
+    if opts.week_ticks {
+        opts.theme.foreground.with_alpha(0.3).set(ctx);
+        ctx.set_line_width(0.5);
+        ctx.new_path();
+        let weeks = iter::successors(Some(time::Week::containing(year.start())), |w| {
+            Some(w.next())
+        });
+        for week in weeks
+            .take_while(|w| w.start() < year.end())
+            .filter(|w| w.start() >= year.start())
+        {
+            let t = year.fraction_of(week.start()) * TAU - dt - TAU / 4.0;
+            ctx.move_to((r.max() + 1.0) * t.cos(), (r.max() + 1.0) * t.sin());
+            ctx.line_to((r.max() + 4.0) * t.cos(), (r.max() + 4.0) * t.sin());
+        }
+        ctx.stroke()?;
+    }
+    ctx.restore()?;
+
+    opts.theme.foreground.set(ctx);
     ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
     ctx.set_font_size(10.0);
+    let y = (r.max() + r.min()) / 2.0;
+    let arc = months
+        .iter()
+        .map(|(s, e)| (e - s) * TAU * y)
+        .fold(f64::MAX, f64::min);
+    let labels = opts.month_labels.resolve(ctx, year, arc, &opts.locale)?;
     for (i, month) in year.months().enumerate() {
         let (s, e) = months[i];
-        let y = (r.max() + r.min()) / 2.0;
+        let name = match labels.label_for(i, &month, &opts.locale) {
+            Some(name) => name,
+            None => continue,
+        };
+        let t = opts.angle_of(year, s + (e - s) / 2.0);
         ctx.save()?;
-        ctx.rotate((s + (e - s) / 2.0) * TAU);
-        let name = format!("{}", month.start().format("%b"));
+        // measure after turning, since hinting sizes turned text a little differently.
+        let (x, y) = match opts.upright_month_labels {
+            true => (y * t.sin(), -y * t.cos()),
+            false => {
+                ctx.rotate(t);
+                (0.0, -y)
+            }
+        };
         let exts = ctx.text_extents(&name)?;
-        ctx.move_to(-exts.width() / 2.0, -y + exts.height() / 2.0);
+        ctx.move_to(x - exts.width() / 2.0, y + exts.height() / 2.0);
         ctx.show_text(&name)?;
         ctx.restore()?;
     }
@@ -465,38 +3033,118 @@ fn render_months(ctx: &Context, year: time::Year, r: &Range) -> Result<(), Box<d
     Ok(())
 }
 
-fn render_scales(
+// Shades the meteorological seasons behind the data of the panels in --seasons, in the
+// theme's colors for them.
+fn render_seasons(
+    ctx: &Context,
+    year: time::Year,
+    stations: &[Station],
+    rrange: &Range,
+    panel: Panel,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    if !opts.seasons.contains(&panel) {
+        return Ok(());
+    }
+    let south = stations
+        .first()
+        .and_then(|s| s.location())
+        .is_some_and(|loc| loc.lat() < 0.0);
+    let colors = [
+        &opts.theme.winter,
+        &opts.theme.spring,
+        &opts.theme.summer,
+        &opts.theme.autumn,
+    ];
+
+    // the months of a season are joined into one wedge, so that there are no seams between
+    // them. December is a wedge of its own at the end of the year.
+    let mut wedges: Vec<(usize, f64, f64)> = Vec::new();
+    for (i, (s, e)) in month_spans(year).into_iter().enumerate() {
+        let season = ((i + 1) % 12 / 3 + if south { 2 } else { 0 }) % 4;
+        match wedges.last_mut() {
+            Some((last, _, end)) if *last == season => *end = e,
+            _ => wedges.push((season, s, e)),
+        }
+    }
+
+    ctx.save()?;
+    orient(ctx, year, opts);
+    for (season, s, e) in wedges {
+        let (ta, tb) = (s * TAU - TAU / 4.0, e * TAU - TAU / 4.0);
+        colors[season].with_alpha(0.08).set(ctx);
+        ctx.new_path();
+        ctx.arc(0.0, 0.0, rrange.max(), ta, tb);
+        ctx.arc_negative(0.0, 0.0, rrange.min(), tb, ta);
+        ctx.fill()?;
+    }
+    ctx.restore()?;
+    Ok(())
+}
+
+pub(crate) fn render_scales(
     ctx: &Context,
     scale: &Scale,
     trange: &Range,
     rrange: &Range,
     units: &str,
-    dir: Direction,
+    panel: Panel,
+    opts: &Options,
 ) -> Result<(), Box<dyn Error>> {
+    let first = match scale.steps().first() {
+        Some(first) => *first,
+        None => return Ok(()),
+    };
+    let normalize = |v: f64| opts.normalize(panel, trange, v);
+
+    // grid rings go all of the way around, under the labels and whether or not there are
+    // any.
+    if opts.grid_rings {
+        ctx.save()?;
+        ctx.set_dash(&opts.theme.grid_dash, 0.0);
+        opts.theme
+            .foreground
+            .with_alpha(opts.theme.grid_opacity)
+            .set(ctx);
+        for step in scale.steps() {
+            ctx.new_path();
+            ctx.arc(0.0, 0.0, rrange.project(normalize(*step)), 0.0, TAU);
+            ctx.stroke()?;
+        }
+        ctx.restore()?;
+    }
+
+    let dir = match opts.scale_side(panel) {
+        ScaleSide::Left => Direction::Left,
+        ScaleSide::Right => Direction::Right,
+        ScaleSide::None => return Ok(()),
+    };
     let tb = TAU * 0.75;
 
     // this is the y value of the inner most scale ring
-    // let y = -rrange.project(trange.normalize(*steps.first().unwrap() as f64)) + 10.0;
-    let y = -rrange.project(trange.normalize(*scale.steps().first().unwrap())) + 10.0;
+    let y = -rrange.project(normalize(first)) + 10.0;
 
     ctx.set_dash(&[1.0, 4.0], 0.0);
-    Color::from_u32_with_alpha(0xffffff, 0.6).set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
     ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
     ctx.set_font_size(10.0);
     if let Direction::Right = dir {
         for (i, step) in scale.steps().iter().enumerate() {
-            let r = rrange.project(trange.normalize(*step));
+            let r = rrange.project(normalize(*step));
 
             let ta = (y / r).asin();
             ctx.save()?;
             ctx.new_path();
-            ctx.arc(0.0, 0.0, r, ta, tb);
+            match opts.grid_rings {
+                true => ctx.move_to(r * tb.cos(), r * tb.sin()),
+                false => ctx.arc(0.0, 0.0, r, ta, tb),
+            }
             ctx.line_to(r * tb.cos() + rrange.max(), r * tb.sin());
             ctx.stroke()?;
             ctx.restore()?;
 
             ctx.save()?;
-            let label = format!("{}{}", scale.label_for(i), units);
+            let label = format!("{}{}", opts.locale.localize(&scale.label_for(i)), units);
             let exts = ctx.text_extents(&label)?;
             ctx.move_to(
                 r * tb.cos() + rrange.max() + 5.0,
@@ -507,19 +3155,22 @@ fn render_scales(
         }
     } else {
         for (i, step) in scale.steps().iter().enumerate() {
-            let r = rrange.project(trange.normalize(*step));
+            let r = rrange.project(normalize(*step));
             let ta = PI - (y / r).asin();
             let x = r * tb.cos();
             let y = r * tb.sin();
             ctx.save()?;
             ctx.new_path();
-            ctx.arc_negative(0.0, 0.0, r, ta, tb);
+            match opts.grid_rings {
+                true => ctx.move_to(x, y),
+                false => ctx.arc_negative(0.0, 0.0, r, ta, tb),
+            }
             ctx.line_to(x - rrange.max(), y);
             ctx.stroke()?;
             ctx.restore()?;
 
             ctx.save()?;
-            let label = format!("{}{}", scale.label_for(i), units);
+            let label = format!("{}{}", opts.locale.localize(&scale.label_for(i)), units);
             let exts = ctx.text_extents(&label)?;
             ctx.move_to(
                 x - rrange.max() - exts.x_advance() - 5.0,
@@ -549,20 +3200,22 @@ pub fn render_radial_range(
     let t4 = TAU / 4.0;
 
     ctx.new_path();
-    let r = rrange.project(max.get_normalized(0));
+    let r = radius_of(max, 0, rrange);
+    check_point(r * t0.cos(), r * t0.sin());
     ctx.move_to(r * t0.cos(), r * t0.sin());
 
     for i in 1..=n {
         let ta = i as f64 * dt - dt + t0;
         let tb = i as f64 * dt + t0;
-        let ra = rrange.project(max.get_normalized(i as isize - 1));
-        let rb = rrange.project(max.get_normalized(i as isize));
+        let ra = radius_of(max, i as isize - 1, rrange);
+        let rb = radius_of(max, i as isize, rrange);
         let xa = ra * ta.cos();
         let ya = ra * ta.sin();
         let xb = rb * tb.cos();
         let yb = rb * tb.sin();
         let da = distance_across_arc(ra, dt) * 0.55;
         let db = distance_across_arc(rb, dt) * 0.55;
+        check_point(xb, yb);
         if smooth {
             let ca = ta + t4;
             let cb = tb - t4;
@@ -579,22 +3232,24 @@ pub fn render_radial_range(
         }
     }
 
-    let r = rrange.project(min.get_normalized(n as isize - 1));
+    let r = radius_of(min, n as isize - 1, rrange);
     let t = (n as f64 - 1.0) * dt + t0;
+    check_point(r * t.cos(), r * t.sin());
     ctx.move_to(r * t.cos(), r * t.sin());
 
     for i in 0..=n {
         let i = n as isize - i as isize - 1;
         let ta = i as f64 * dt + t0;
         let tb = i as f64 * dt - dt + t0;
-        let ra = rrange.project(min.get_normalized(i));
-        let rb = rrange.project(min.get_normalized(i - 1));
+        let ra = radius_of(min, i, rrange);
+        let rb = radius_of(min, i - 1, rrange);
         let xa = ra * ta.cos();
         let ya = ra * ta.sin();
         let xb = rb * tb.cos();
         let yb = rb * tb.sin();
         let da = distance_across_arc(ra, dt) * 0.55;
         let db = distance_across_arc(rb, dt) * 0.55;
+        check_point(xb, yb);
         if smooth {
             let ca = ta - t4;
             let cb = tb + t4;
@@ -624,6 +3279,46 @@ pub fn render_radial_range(
     Ok(())
 }
 
+// Washes out the segments of a ring over the days whose means come from fewer than
+// --min-samples observations for any of the stations. Days with a count of zero are from
+// sources that don't count the observations, and are left alone like the missing ones.
+fn render_sparse_days(
+    ctx: &Context,
+    samples: &[&Series],
+    rrange: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let n = match samples.first() {
+        Some(s) if opts.min_samples > 0 => s.values().len(),
+        _ => return Ok(()),
+    };
+    let dt = TAU / n as f64;
+    let t0 = -TAU / 4.0;
+    let min = opts.min_samples as f64;
+
+    ctx.new_path();
+    for i in (0..n).filter(|i| {
+        samples
+            .iter()
+            .any(|s| !s.is_filled(*i) && s.values()[*i] > 0.0 && s.values()[*i] < min)
+    }) {
+        let ta = (i as f64 - 0.5) * dt + t0;
+        let tb = (i as f64 + 0.5) * dt + t0;
+        ctx.new_sub_path();
+        ctx.arc(0.0, 0.0, rrange.max(), ta, tb);
+        ctx.arc_negative(0.0, 0.0, rrange.min(), tb, ta);
+        ctx.close_path();
+    }
+    opts.theme.background.with_alpha(0.6).set(ctx);
+    ctx.fill()?;
+    Ok(())
+}
+
+// The radius at which the i-th value of the series is drawn.
+fn radius_of(series: &Series, i: isize, rrange: &Range) -> f64 {
+    check_radius(rrange.project(check_unit(series.get_normalized(i))), rrange)
+}
+
 pub fn render_radial_series(
     ctx: &Context,
     series: &Series,
@@ -637,20 +3332,22 @@ pub fn render_radial_series(
     let t4 = TAU / 4.0;
 
     ctx.new_path();
-    let r = rrange.project(series.get_normalized(0));
+    let r = radius_of(series, 0, rrange);
+    check_point(r * t0.cos(), r * t0.sin());
     ctx.move_to(r * t0.cos(), r * t0.sin());
 
     for i in 1..=n {
         let ta = i as f64 * dt - dt + t0;
         let tb = i as f64 * dt + t0;
-        let ra = rrange.project(series.get_normalized(i as isize - 1));
-        let rb = rrange.project(series.get_normalized(i as isize));
+        let ra = radius_of(series, i as isize - 1, rrange);
+        let rb = radius_of(series, i as isize, rrange);
         let xa = ra * ta.cos();
         let ya = ra * ta.sin();
         let xb = rb * tb.cos();
         let yb = rb * tb.sin();
         let da = distance_across_arc(ra, dt) * 0.55;
         let db = distance_across_arc(rb, dt) * 0.55;
+        check_point(xb, yb);
         if smooth {
             let ca = ta + t4;
             let cb = tb - t4;
@@ -676,73 +3373,193 @@ pub fn render_radial_series(
 fn render_wind(
     ctx: &Context,
     year: time::Year,
-    station: &gsod::Station,
+    stations: &[Station],
     rrange: &Range,
+    range: &Range,
     opts: &Options,
 ) -> Result<(), Box<dyn Error>> {
-    let mean_wind = Series::for_each_day(year, station.days().iter(), |day| {
-        day.mean_wind().map(|s| s.in_knots())
-    });
-
-    let max_sustained_wind = Series::for_each_day(year, station.days().iter(), |day| {
-        day.max_sustained_wind().map(|s| s.in_knots())
-    });
+    ctx.save()?;
+    render_months(ctx, year, &opts.month_range(rrange), opts)?;
+    ctx.restore()?;
 
-    let range = Range::intersect(mean_wind.range(), max_sustained_wind.range());
+    ctx.save()?;
+    let scale = Scale::from_range(range, 5.0)?;
+    render_scales(ctx, &scale, range, rrange, " kts", Panel::Wind, opts)?;
+    ctx.restore()?;
 
-    let mean_wind = mean_wind.with_range(&range);
-    let max_sustained_wind = max_sustained_wind.with_range(&range);
+    render_seasons(ctx, year, stations, rrange, Panel::Wind, opts)?;
 
-    let avg_mean_wind =
-        mean_wind.values().iter().fold(0.0, |sum, val| sum + val) / mean_wind.values().len() as f64;
+    let winds = stations
+        .iter()
+        .map(|station| wind_series(year, station, range, opts))
+        .collect::<Vec<_>>();
+    for (i, w) in winds.iter().enumerate() {
+        let color = match stations.len() {
+            1 => opts.theme.wind,
+            _ => station_color(i),
+        };
+
+        let fill = color.with_alpha(0.1);
+        ctx.save()?;
+        orient(ctx, year, opts);
+        if opts.beaufort {
+            render_radial_range(ctx, &w.mean, &w.max, rrange, None, None, opts.smooth)?;
+            fill_beaufort(ctx, rrange, range)?;
+        }
+        render_radial_range(
+            ctx,
+            &w.mean,
+            &w.max,
+            rrange,
+            (!opts.beaufort).then_some(&fill),
+            Some(&color),
+            opts.smooth,
+        )?;
+        ctx.restore()?;
+    }
 
-    let mean_wind = if opts.downsample_by > 1 {
-        mean_wind.downsample_by(opts.downsample_by as usize, |vals| {
-            vals.iter().fold(0.0, |sum, val| sum + val) / vals.len() as f64
-        })
-    } else {
-        mean_wind
-    };
+    if opts.beaufort {
+        ctx.save()?;
+        render_beaufort_legend(ctx, rrange, range, opts)?;
+        ctx.restore()?;
+    }
 
-    let max_sustained_wind = if opts.downsample_by > 1 {
-        max_sustained_wind.downsample_by(opts.downsample_by as usize, |vals| {
-            vals.iter().fold(f64::MIN, |max, val| max.max(*val))
-        })
-    } else {
-        max_sustained_wind
-    };
+    ctx.save()?;
+    orient(ctx, year, opts);
+    render_sparse_days(
+        ctx,
+        &winds.iter().map(|w| &w.samples).collect::<Vec<_>>(),
+        rrange,
+        opts,
+    )?;
+    ctx.restore()?;
 
     ctx.save()?;
-    render_months(
+    render_center_text(
         ctx,
-        year,
-        &Range::new(rrange.min() - 40.0, rrange.min() - 5.0),
+        &center_stats(year, stations, Panel::Wind, opts)
+            .unwrap_or_else(|| wind_stats(stations, &winds, opts)),
+        &Font::new(
+            "HelveticaNeue-Medium",
+            FontSlant::Normal,
+            FontWeight::Bold,
+            11.0,
+        ),
+        &Font::new(
+            "HelveticaNeue-Thin",
+            FontSlant::Normal,
+            FontWeight::Normal,
+            32.0,
+        ),
+        &opts.theme.foreground.with_alpha(0.6),
+        opts,
     )?;
     ctx.restore()?;
 
+    Ok(())
+}
+
+// Draws the wind panel as a rose: a petal for each direction the wind blew from, reaching
+// out as far as the share of the hours it blew from there and banded by speed. The compass
+// points take the place of the month ring.
+fn render_wind_rose(
+    ctx: &Context,
+    rose: &isd::WindRose,
+    rrange: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let n = isd::WindRose::SECTORS;
+    let percent = (0..n)
+        .map(|s| rose.sector_frequency(s) * 100.0)
+        .fold(0.0, f64::max);
+    let range = Range::new(0.0, percent.max(1.0));
+
     ctx.save()?;
-    let scale = Scale::from_range(&range, 5.0);
-    render_scales(ctx, &scale, &range, rrange, " kts", Direction::Left)?;
+    let scale = Scale::from_range(&range, 5.0)?;
+    render_scales(ctx, &scale, &range, rrange, "%", Panel::Wind, opts)?;
     ctx.restore()?;
 
+    // faster winds are drawn more solid, out from the slower ones of the same direction.
+    let classes = isd::SPEED_CLASSES.len() + 1;
+    let alpha = |c: usize| 0.2 + 0.8 * c as f64 / (classes - 1) as f64;
+    let dt = TAU / n as f64;
+    for s in 0..n {
+        let t = s as f64 * dt - TAU / 4.0;
+        let (ta, tb) = (t - dt * 0.4, t + dt * 0.4);
+        let mut cum = 0.0;
+        for c in 0..classes {
+            let f = rose.frequency(s, c) * 100.0;
+            if f == 0.0 {
+                continue;
+            }
+            let ra = rrange.project(range.normalize(cum));
+            cum += f;
+            let rb = rrange.project(range.normalize(cum));
+            ctx.new_path();
+            ctx.arc(0.0, 0.0, rb, ta, tb);
+            ctx.arc_negative(0.0, 0.0, ra, tb, ta);
+            ctx.close_path();
+            opts.theme.wind.with_alpha(alpha(c)).set(ctx);
+            ctx.fill()?;
+        }
+    }
+
     ctx.save()?;
-    render_radial_range(
-        ctx,
-        &mean_wind,
-        &max_sustained_wind,
-        rrange,
-        Some(&Color::from_u32_with_alpha(0x9f83c3, 0.1)),
-        Some(&Color::from_u32(0x9f83c3)),
-        opts.smooth,
-    )?;
+    let r = rrange.min() - 20.0;
+    opts.theme.foreground.set(ctx);
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(11.0);
+    let points = [Label::North, Label::East, Label::South, Label::West];
+    for (i, point) in points.iter().enumerate() {
+        let label = opts.locale.label(*point);
+        let t = i as f64 * TAU / 4.0 - TAU / 4.0;
+        let exts = ctx.text_extents(label)?;
+        ctx.move_to(
+            r * t.cos() - exts.width() / 2.0 - exts.x_bearing(),
+            r * t.sin() - exts.height() / 2.0 - exts.y_bearing(),
+        );
+        ctx.show_text(label)?;
+    }
+    ctx.restore()?;
+
+    // a key to the speed classes under the rose.
+    ctx.save()?;
+    ctx.select_font_face("HelveticaNeue", FontSlant::Normal, FontWeight::Normal);
+    ctx.set_font_size(9.0);
+    let mut bounds = vec![String::from("0")];
+    bounds.extend(isd::SPEED_CLASSES.iter().map(|v| format!("{}", v)));
+    let keys = (0..classes)
+        .map(|c| match bounds.get(c + 1) {
+            Some(hi) => format!("{}–{}", bounds[c], hi),
+            None => format!("{}+ kts", bounds[c]),
+        })
+        .collect::<Vec<_>>();
+    let (swatch, gap) = (8.0, 10.0);
+    let widths = keys
+        .iter()
+        .map(|k| Ok(ctx.text_extents(k)?.x_advance()))
+        .collect::<Result<Vec<_>, cairo::Error>>()?;
+    let total = widths.iter().map(|w| swatch + 4.0 + w).sum::<f64>() + gap * (classes - 1) as f64;
+    let (mut x, y) = (-total / 2.0, rrange.max() + 10.0);
+    for (c, (key, w)) in keys.iter().zip(widths.iter()).enumerate() {
+        opts.theme.wind.with_alpha(alpha(c)).set(ctx);
+        ctx.rectangle(x, y, swatch, swatch);
+        ctx.fill()?;
+        opts.theme.foreground.with_alpha(0.6).set(ctx);
+        ctx.move_to(x + swatch + 4.0, y + swatch);
+        ctx.show_text(key)?;
+        x += swatch + 4.0 + w + gap;
+    }
     ctx.restore()?;
 
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v| opts.locale.number(v, 1);
     ctx.save()?;
     render_center_text(
         ctx,
         &[
-            (String::from("MAX"), format!("{:.1} kts", range.max())),
-            (String::from("AVG"), format!("{:.1} kts", avg_mean_wind)),
+            (label(Label::Calm), format!("{}%", num(rose.calm() * 100.0))),
+            (label(Label::Avg), format!("{} kts", num(rose.mean_speed()))),
         ],
         &Font::new(
             "HelveticaNeue-Medium",
@@ -756,7 +3573,7 @@ fn render_wind(
             FontWeight::Normal,
             32.0,
         ),
-        &Color::from_u32_with_alpha(0xffffff, 0.6),
+        &opts.theme.foreground.with_alpha(0.6),
         opts,
     )?;
     ctx.restore()?;
@@ -764,72 +3581,270 @@ fn render_wind(
     Ok(())
 }
 
-fn render_precipitation(
+// Lays a wedge over each day of a panel that shows the values of the day when hovered in
+// an SVG.
+fn record_hotspots(
     ctx: &Context,
     year: time::Year,
-    station: &gsod::Station,
+    stations: &[Station],
     rrange: &Range,
+    panel: Panel,
     opts: &Options,
-) -> Result<(), Box<dyn Error>> {
-    let percipitation = Series::for_each_day(year, station.days().iter(), |day| {
-        match day.precipitation() {
-            Some(p) => Some(p.in_inches()),
-            None => Some(0.0),
-        }
-    });
+) {
+    let hotspots = match &opts.hotspots {
+        Some(hotspots) => hotspots,
+        None => return,
+    };
 
-    let num_days = percipitation
-        .values()
+    let days = stations
         .iter()
-        .fold(0, |sum, val| if *val > 0.0 { sum + 1 } else { sum });
+        .map(|s| {
+            s.days()
+                .iter()
+                .map(|d| (d.date(), d))
+                .collect::<HashMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+    let matrix = ctx.matrix();
+    orient(ctx, year, opts);
+    let n = year.days().count();
+    let dt = TAU / n as f64;
+    for (i, day) in year.days().enumerate() {
+        let date = day.date();
+        let lines = stations
+            .iter()
+            .zip(days.iter())
+            .filter_map(|(station, days)| {
+                let vals = panel.describe(days.get(&date)?, &opts.locale)?;
+                Some(match stations.len() {
+                    1 => vals,
+                    _ => format!("{}  {}", station.id(), vals),
+                })
+            })
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            continue;
+        }
 
-    let total = percipitation.values().iter().sum::<f64>();
+        let t = i as f64 * dt - TAU / 4.0;
+        let (ta, tb) = (t - dt / 2.0, t + dt / 2.0);
+        let points = [
+            (rrange.min(), ta),
+            (rrange.max(), ta),
+            (rrange.max(), tb),
+            (rrange.min(), tb),
+        ]
+        .iter()
+        .map(|(r, t)| ctx.user_to_device(r * t.cos(), r * t.sin()))
+        .collect();
+        hotspots.borrow_mut().push(svg::Hotspot {
+            points,
+            title: format!("{}\n{}", opts.locale.date(date), lines.join("\n")),
+            data: vec![
+                ("date", date.format("%Y-%m-%d").to_string()),
+                (
+                    "panel",
+                    String::from(panel.to_possible_value().unwrap().get_name()),
+                ),
+                ("values", lines.join("; ")),
+            ],
+        });
+    }
+    ctx.set_matrix(matrix);
+}
 
+fn render_precipitation(
+    ctx: &Context,
+    year: time::Year,
+    stations: &[Station],
+    rrange: &Range,
+    range: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
     ctx.save()?;
-    render_months(
-        ctx,
-        year,
-        &Range::new(rrange.min() - 40.0, rrange.min() - 5.0),
-    )?;
+    render_months(ctx, year, &opts.month_range(rrange), opts)?;
     ctx.restore()?;
 
-    let scale = Scale::from_range(percipitation.range(), 4.0);
+    let scale = opts.precip_scale.scale(range)?;
 
     ctx.save()?;
     render_scales(
         ctx,
         &scale,
-        percipitation.range(),
+        range,
         rrange,
         " in",
-        Direction::Left,
+        Panel::Precipitation,
+        opts,
     )?;
     ctx.restore()?;
 
-    let n = percipitation.values().len();
-    let dt = TAU / n as f64;
-    let t0 = -TAU / 4.0;
+    render_seasons(ctx, year, stations, rrange, Panel::Precipitation, opts)?;
 
-    ctx.save()?;
-    let ra = rrange.project(Unit::zero());
-    Color::from_u32(0x2fcbcc).set(ctx);
-    ctx.new_path();
-    for i in 0..n {
-        let t = i as f64 * dt + t0;
-        let rb = rrange.project(percipitation.get_normalized(i as isize));
-        ctx.move_to(ra * t.cos(), ra * t.sin());
-        ctx.line_to(rb * t.cos(), rb * t.sin());
+    for (i, station) in stations.iter().enumerate() {
+        let percipitation = precipitation_series(year, station)
+            .map(|v| opts.precip_scale.apply(v))
+            .with_range(&opts.precip_scale.transform(range));
+
+        let n = percipitation.values().len();
+        let dt = TAU / n as f64;
+        // overlaid stations are nudged apart so their bars do not cover each other.
+        let t0 = -TAU / 4.0 + dt * i as f64 / stations.len() as f64;
+
+        let snow = match opts.snow_split {
+            true => snow_days(year, station),
+            false => vec![false; n],
+        };
+
+        ctx.save()?;
+        orient(ctx, year, opts);
+        let ra = rrange.project(Unit::zero());
+        // the days of rain, then those of snow.
+        for snowy in [false, true] {
+            if !snow.contains(&snowy) {
+                continue;
+            }
+            match (stations.len(), snowy) {
+                (1, false) => opts.theme.precipitation.set(ctx),
+                (1, true) => opts.theme.snow.set(ctx),
+                (_, false) => station_color(i).set(ctx),
+                (_, true) => station_color(i).with_alpha(0.5).set(ctx),
+            }
+            ctx.new_path();
+            for i in (0..n).filter(|i| snow[*i] == snowy) {
+                let t = i as f64 * dt + t0;
+                let rb = radius_of(&percipitation, i as isize, rrange);
+                check_point(rb * t.cos(), rb * t.sin());
+                ctx.move_to(ra * t.cos(), ra * t.sin());
+                ctx.line_to(rb * t.cos(), rb * t.sin());
+            }
+            ctx.stroke()?;
+        }
+        ctx.restore()?;
     }
-    ctx.stroke()?;
+
+    ctx.save()?;
+    render_center_text(
+        ctx,
+        &center_stats(year, stations, Panel::Precipitation, opts)
+            .unwrap_or_else(|| precipitation_stats(year, stations, opts)),
+        &Font::new(
+            "HelveticaNeue-Medium",
+            FontSlant::Normal,
+            FontWeight::Bold,
+            11.0,
+        ),
+        &Font::new(
+            "HelveticaNeue-Thin",
+            FontSlant::Normal,
+            FontWeight::Normal,
+            32.0,
+        ),
+        &opts.theme.foreground.with_alpha(0.6),
+        opts,
+    )?;
+    ctx.restore()?;
+
+    Ok(())
+}
+
+// Draws a panel as a wedge for each month, from its low to its high with a thicker arc at
+// its mean, which reads better than the days when the banner is small.
+fn render_monthly(
+    ctx: &Context,
+    year: time::Year,
+    stations: &[Station],
+    panel: Panel,
+    rrange: &Range,
+    range: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    ctx.save()?;
+    render_months(ctx, year, &opts.month_range(rrange), opts)?;
+    ctx.restore()?;
+
+    let (units, color) = match panel {
+        Panel::Temperature => ("°F", opts.theme.temperature_band),
+        Panel::FeelsLike => ("°F", opts.theme.feels_like),
+        Panel::Wind => (" kts", opts.theme.wind),
+        Panel::Precipitation => (" in", opts.theme.precipitation),
+    };
+    ctx.save()?;
+    let scale = match panel {
+        Panel::Precipitation => opts.precip_scale.scale(range)?,
+        _ => Scale::from_range(range, 5.0)?,
+    };
+    render_scales(ctx, &scale, range, rrange, units, panel, opts)?;
     ctx.restore()?;
 
+    render_seasons(ctx, year, stations, rrange, panel, opts)?;
+
+    let months = month_spans(year);
+    // overlaid stations split each month between them.
+    let gap = 0.1 * TAU / months.len() as f64;
+    for (i, station) in stations.iter().enumerate() {
+        let color = match stations.len() {
+            1 => color,
+            _ => station_color(i),
+        };
+        let monthly = chart::monthly_series(year, station, panel, range, opts);
+
+        ctx.save()?;
+        orient(ctx, year, opts);
+        ctx.set_line_width(1.0);
+        for (j, (s, e)) in months.iter().enumerate() {
+            if monthly.high.is_filled(j) {
+                continue;
+            }
+            let w = ((e - s) * TAU - gap) / stations.len() as f64;
+            let ta = s * TAU + gap / 2.0 + w * i as f64 - TAU / 4.0;
+            let tb = ta + w;
+            let ra = radius_of(&monthly.low, j as isize, rrange);
+            let rb = radius_of(&monthly.high, j as isize, rrange);
+            ctx.new_path();
+            ctx.arc(0.0, 0.0, rb, ta, tb);
+            ctx.arc_negative(0.0, 0.0, ra, tb, ta);
+            ctx.close_path();
+            color.with_alpha(0.25).set(ctx);
+            ctx.fill_preserve()?;
+            color.set(ctx);
+            ctx.stroke()?;
+
+            if let Some(mean) = &monthly.mean {
+                ctx.set_line_width(3.0);
+                ctx.new_path();
+                ctx.arc(0.0, 0.0, radius_of(mean, j as isize, rrange), ta, tb);
+                ctx.stroke()?;
+                ctx.set_line_width(1.0);
+            }
+        }
+        ctx.restore()?;
+    }
+
+    let stats = center_stats(year, stations, panel, opts).unwrap_or_else(|| match panel {
+        Panel::Temperature => temperature_stats(
+            stations,
+            &stations
+                .iter()
+                .map(|station| temperature_series(year, station, Some(range), opts))
+                .collect::<Vec<_>>(),
+            opts,
+        ),
+        Panel::FeelsLike => feels_like_stats(year, stations, opts),
+        Panel::Wind => wind_stats(
+            stations,
+            &stations
+                .iter()
+                .map(|station| wind_series(year, station, range, opts))
+                .collect::<Vec<_>>(),
+            opts,
+        ),
+        Panel::Precipitation => precipitation_stats(year, stations, opts),
+    });
     ctx.save()?;
     render_center_text(
         ctx,
-        &[
-            (String::from("DAYS"), format!("{}", num_days)),
-            (String::from("TOTAL"), format!("{:.1} in", total)),
-        ],
+        &stats,
         &Font::new(
             "HelveticaNeue-Medium",
             FontSlant::Normal,
@@ -842,7 +3857,7 @@ fn render_precipitation(
             FontWeight::Normal,
             32.0,
         ),
-        &Color::from_u32_with_alpha(0xffffff, 0.6),
+        &opts.theme.foreground.with_alpha(0.6),
         opts,
     )?;
     ctx.restore()?;
@@ -850,6 +3865,47 @@ fn render_precipitation(
     Ok(())
 }
 
+// Draws a tick just outside of the ring for each day with fog, snow or thunder, each in a
+// lane of its own. Overlaid stations get lanes further out.
+fn render_weather_ring(
+    ctx: &Context,
+    year: time::Year,
+    stations: &[Station],
+    rrange: &Range,
+    opts: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let lanes = [&opts.theme.fog, &opts.theme.snow, &opts.theme.thunder];
+    let flags = |day: &gsod::Day| {
+        let indicators = day.indicators();
+        [indicators.fog(), indicators.snow(), indicators.thunder()]
+    };
+    let lane_width = 4.0;
+    let dt = TAU / year.days().count() as f64;
+
+    ctx.save()?;
+    orient(ctx, year, opts);
+    ctx.set_line_width(1.0);
+    for (i, station) in stations.iter().enumerate() {
+        for (j, color) in lanes.iter().enumerate() {
+            let ra = rrange.max() + 4.0 + lane_width * (lanes.len() * i + j) as f64;
+            let rb = ra + lane_width - 1.0;
+            color.set(ctx);
+            ctx.new_path();
+            for day in station.days() {
+                if !flags(day)[j] {
+                    continue;
+                }
+                let t = day.date().ordinal0() as f64 * dt - TAU / 4.0;
+                ctx.move_to(ra * t.cos(), ra * t.sin());
+                ctx.line_to(rb * t.cos(), rb * t.sin());
+            }
+            ctx.stroke()?;
+        }
+    }
+    ctx.restore()?;
+    Ok(())
+}
+
 fn distance_across_arc(r: f64, t: f64) -> f64 {
     let dx = r * t.cos() - r;
     let dy = r * t.sin();
@@ -861,16 +3917,82 @@ fn shorten_station_name(name: &str) -> String {
 }
 
 fn describe_station_details(station: &gsod::Station) -> String {
-    let id = station.id();
-    if let Some(location) = station.location() {
+    describe_details(station.id(), station.location())
+}
+
+fn describe_details(id: &str, location: Option<&gsod::Location>) -> String {
+    if let Some(location) = location {
         format!("{}  {}", id, location)
     } else {
         id.to_owned()
     }
 }
 
-fn describe_year(year: time::Year) -> String {
+pub(crate) fn describe_year(year: time::Year, locale: &Locale) -> String {
     let s = year.start();
     let e = time::Day::new(year.end()).prev().date();
-    format!("{} – {}", s.format("%b %-d, %Y"), e.format("%b %-d, %Y"))
+    format!("{} – {}", locale.date(s), locale.date(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input;
+    use clap::Args as _;
+
+    fn parse(argv: &[&str]) -> Args {
+        let matches = Args::augment_args(clap::Command::new("render")).get_matches_from(argv);
+        Args::from_arg_matches(&matches).unwrap()
+    }
+
+    #[test]
+    fn replays_a_repro_made_with_fallback() {
+        let dir = std::env::temp_dir().join(format!("weather-banner-repro-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("repro.json");
+        let path = path.to_str().unwrap();
+
+        // station a was missing too many days, so the render showed b in its place.
+        let stand_in = input::read(
+            "date,tmin,tmax,wind,precip\n2022-06-01,60,80,5,0.2\n2022-06-02,61,81,6,0\n".as_bytes(),
+            "b",
+            String::from("b"),
+        )
+        .unwrap();
+        let notice = String::from("a had 0 days of data in 2022, showing b (10 km away) instead");
+        let mut repro = Repro::new(parse(&[
+            "render",
+            "--station-id",
+            "a",
+            "--year",
+            "2022",
+            "--fallback",
+            "replace",
+        ]));
+        repro.add(2022, "a", &stand_in, Some(notice.clone()));
+        repro.write(path).unwrap();
+
+        let data = Data::from(&dir).unwrap();
+        let args = parse(&[
+            "render",
+            "--from-repro",
+            path,
+            "--output-json",
+            "--format",
+            "svg",
+            "--destination",
+            "banner.svg",
+        ]);
+        let mut reports = Vec::new();
+        run(&data, &args, |_, _, report| {
+            reports.extend(report);
+            Ok(())
+        })
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(reports.len(), 1);
+        let report: serde_json::Value = serde_json::from_str(&reports[0]).unwrap();
+        assert_eq!(report["notices"], serde_json::json!([notice]));
+    }
 }
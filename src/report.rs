@@ -0,0 +1,299 @@
+use super::{
+    chart::{self, Panel},
+    gsod::{self, Station},
+    heatmap,
+    locale::Label,
+    render, time, Data, Font, Range, Scale, TAU,
+};
+use cairo::{Context, FontSlant, FontWeight, PdfSurface};
+use chrono::NaiveDate;
+use clap::ArgMatches;
+use std::collections::HashMap;
+use std::error::Error;
+
+// the render args are flattened in, so they don't need a group of their own.
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    #[command(flatten)]
+    render: render::Args,
+}
+
+// The size of the pages of the months, US letter on its side, in points.
+const PAGE_WIDTH: f64 = 792.0;
+const PAGE_HEIGHT: f64 = 612.0;
+
+// Renders a PDF of a year, with the banner of the render args on the first page and a page
+// for each month after it, with a table of its days and a ring of their temperatures.
+pub fn execute(data: &Data, args: &Args, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    render::execute(
+        data,
+        &args.render.clone().with_config(matches)?.for_report(),
+    )
+}
+
+// Adds a page for each month of the year to the PDF after the banner.
+pub(crate) fn render_months(
+    surface: &PdfSurface,
+    ctx: &Context,
+    year: time::Year,
+    station: &Station,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let days = station
+        .days()
+        .iter()
+        .map(|day| (day.date(), day))
+        .collect::<HashMap<_, _>>();
+    for month in year.months() {
+        ctx.show_page()?;
+        surface.set_size(PAGE_WIDTH, PAGE_HEIGHT)?;
+        ctx.identity_matrix();
+        render_month(ctx, &month, station, &days, opts)?;
+    }
+    Ok(())
+}
+
+fn render_month(
+    ctx: &Context,
+    month: &time::Month,
+    station: &Station,
+    days: &HashMap<NaiveDate, &gsod::Day>,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let header_height = render::render_header(
+        ctx,
+        station,
+        &opts.locale.format(month.start(), "%B %Y"),
+        PAGE_WIDTH,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let month_days = month
+        .days()
+        .map(|day| (day.date(), days.get(&day.date()).copied()))
+        .collect::<Vec<_>>();
+
+    ctx.save()?;
+    render_table(ctx, &month_days, header_height + 20.0, opts)?;
+    ctx.restore()?;
+
+    ctx.save()?;
+    render_ring(
+        ctx,
+        &month_days,
+        (600.0, header_height + (PAGE_HEIGHT - header_height) / 2.0),
+        ((PAGE_HEIGHT - header_height) / 2.0 - 40.0).min(160.0),
+        opts,
+    )?;
+    ctx.restore()?;
+    Ok(())
+}
+
+// The heading of a column of the table and the value of a day under it.
+type Column = (String, fn(&gsod::Day) -> Option<f64>);
+
+// Lists the values of each day of the month, a row to a day, with a dash for what's missing.
+fn render_table(
+    ctx: &Context,
+    days: &[(NaiveDate, Option<&gsod::Day>)],
+    top: f64,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let label = |l| opts.locale.label(l).to_uppercase();
+    let columns: [Column; 6] = [
+        (format!("{} °F", label(Label::Max)), |day| {
+            day.max_temperature().map(|t| t.in_fahrenheit())
+        }),
+        (format!("{} °F", label(Label::Avg)), |day| {
+            day.mean_temperature().map(|t| t.in_fahrenheit())
+        }),
+        (format!("{} °F", label(Label::Min)), |day| {
+            day.min_temperature().map(|t| t.in_fahrenheit())
+        }),
+        (format!("{} kts", label(Label::Wind)), |day| {
+            day.mean_wind().map(|s| s.in_knots())
+        }),
+        (format!("{} kts", label(Label::Max)), |day| {
+            day.max_sustained_wind().map(|s| s.in_knots())
+        }),
+        (String::from("in"), |day| {
+            day.precipitation().map(|p| p.in_inches())
+        }),
+    ];
+
+    let (x0, col_width) = (30.0, 50.0);
+    let row_height = ((PAGE_HEIGHT - top - 30.0) / (days.len() + 1) as f64).min(16.0);
+    let right = |ctx: &Context, text: &str, x: f64, y: f64| -> Result<(), Box<dyn Error>> {
+        let exts = ctx.text_extents(text)?;
+        ctx.move_to(x - exts.x_advance(), y);
+        ctx.show_text(text)?;
+        Ok(())
+    };
+
+    Font::new(
+        "HelveticaNeue-Medium",
+        FontSlant::Normal,
+        FontWeight::Bold,
+        8.0,
+    )
+    .set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    for (i, (heading, _)) in columns.iter().enumerate() {
+        right(ctx, heading, x0 + 40.0 + (i + 1) as f64 * col_width, top)?;
+    }
+
+    Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 9.0).set(ctx);
+    for (i, (date, day)) in days.iter().enumerate() {
+        let y = top + (i + 1) as f64 * row_height;
+        if i % 2 == 0 {
+            opts.theme.foreground.with_alpha(0.05).set(ctx);
+            ctx.rectangle(
+                x0 - 4.0,
+                y - row_height + 4.0,
+                40.0 + columns.len() as f64 * col_width + 8.0,
+                row_height,
+            );
+            ctx.fill()?;
+        }
+        opts.theme.foreground.with_alpha(0.8).set(ctx);
+        ctx.move_to(x0, y);
+        ctx.show_text(&opts.locale.format(*date, "%a %-d"))?;
+        for (j, (_, value_of)) in columns.iter().enumerate() {
+            let text = match day.and_then(value_of) {
+                Some(v) => opts.locale.number(v, if j == 5 { 2 } else { 1 }),
+                None => String::from("–"),
+            };
+            right(ctx, &text, x0 + 40.0 + (j + 1) as f64 * col_width, y)?;
+        }
+    }
+    Ok(())
+}
+
+// Draws a ring of the days of the month clockwise from the top, each day a bar from its low
+// to its high colored by its mean temperature, around the month's stats.
+fn render_ring(
+    ctx: &Context,
+    days: &[(NaiveDate, Option<&gsod::Day>)],
+    (cx, cy): (f64, f64),
+    r: f64,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let temps = days
+        .iter()
+        .map(|(_, day)| {
+            let day = (*day)?;
+            Some((
+                day.min_temperature()?.in_fahrenheit(),
+                day.mean_temperature()?.in_fahrenheit(),
+                day.max_temperature()?.in_fahrenheit(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let metric = heatmap::Metric::Temperature;
+    let range = opts.temperature_range.clone().unwrap_or_else(|| {
+        metric.range_of(temps.iter().flatten().flat_map(|(lo, _, hi)| [*lo, *hi]))
+    });
+    let rrange = Range::new(r * opts.radii.min(), r * opts.radii.max());
+    let radius = |v: f64| rrange.project(range.normalize_clamped(v));
+    let gradient = metric.gradient();
+
+    ctx.translate(cx, cy);
+
+    ctx.save()?;
+    let scale = Scale::from_range(&range, 5.0)?;
+    render::render_scales(
+        ctx,
+        &scale,
+        &range,
+        &rrange,
+        metric.units(),
+        Panel::Temperature,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    let dt = TAU / days.len() as f64;
+    let angle = |i: usize| (i as f64 + 0.5) * dt - TAU / 4.0;
+
+    // the number of every fifth day just inside of the bars.
+    ctx.save()?;
+    Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 8.0).set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    for (i, (date, _)) in days.iter().enumerate() {
+        let n = i + 1;
+        if n != 1 && n % 5 != 0 {
+            continue;
+        }
+        let text = opts.locale.format(*date, "%-d");
+        let exts = ctx.text_extents(&text)?;
+        let rl = rrange.min() - 12.0;
+        ctx.move_to(
+            rl * angle(i).cos() - exts.x_advance() / 2.0,
+            rl * angle(i).sin() + exts.height() / 2.0,
+        );
+        ctx.show_text(&text)?;
+    }
+    ctx.restore()?;
+
+    ctx.save()?;
+    ctx.set_line_width((rrange.min() * dt * 0.5).max(1.0));
+    for (i, t) in temps.iter().enumerate() {
+        let (lo, mean, hi) = match t {
+            Some(t) => *t,
+            None => continue,
+        };
+        let t = angle(i);
+        gradient.at(range.normalize_clamped(mean)).set(ctx);
+        ctx.new_path();
+        ctx.move_to(radius(lo) * t.cos(), radius(lo) * t.sin());
+        ctx.line_to(radius(hi) * t.cos(), radius(hi) * t.sin());
+        ctx.stroke()?;
+    }
+    ctx.restore()?;
+
+    let vals = temps.iter().flatten().collect::<Vec<_>>();
+    if vals.is_empty() {
+        return Ok(());
+    }
+    let high = vals.iter().fold(f64::MIN, |max, (_, _, hi)| max.max(*hi));
+    let low = vals.iter().fold(f64::MAX, |min, (lo, _, _)| min.min(*lo));
+    let mean = vals.iter().map(|(_, mean, _)| mean).sum::<f64>() / vals.len() as f64;
+    let total = days
+        .iter()
+        .filter_map(|(_, day)| day.and_then(|day| day.precipitation()))
+        .map(|p| p.in_inches())
+        .sum::<f64>();
+    let label = |l| String::from(opts.locale.label(l));
+    let num = |v, units| format!("{}{}", opts.locale.number(v, 1), units);
+    render::render_center_text(
+        ctx,
+        &[
+            (label(Label::Max), num(high, "°F")),
+            (label(Label::Avg), num(mean, "°F")),
+            (label(Label::Min), num(low, "°F")),
+            (label(Label::Total), num(total, " in")),
+        ],
+        &Font::new(
+            "HelveticaNeue-Medium",
+            FontSlant::Normal,
+            FontWeight::Bold,
+            8.0,
+        ),
+        &Font::new(
+            "HelveticaNeue-Thin",
+            FontSlant::Normal,
+            FontWeight::Normal,
+            16.0,
+        ),
+        &opts.theme.foreground.with_alpha(0.6),
+        opts,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use super::{
+    gsod::Station,
+    render,
+    source::{self, DataSource},
+    time,
+};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::io;
+
+// Everything that went into a render: the options it was given and every station it
+// loaded. A repro can be rendered again without any of the archives, which makes it a
+// small, self contained attachment for a bug report.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Repro {
+    version: String,
+    args: render::Args,
+    stations: Vec<Entry>,
+}
+
+// A station is kept under the id the render asked for, which is only written out when
+// --fallback stood another station in for it, along with the notice of what was done.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    year: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    station: Station,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notice: Option<String>,
+}
+
+impl Entry {
+    fn id(&self) -> &str {
+        self.id.as_deref().unwrap_or(self.station.id())
+    }
+}
+
+impl Repro {
+    pub(crate) fn new(args: render::Args) -> Repro {
+        Repro {
+            version: String::from(env!("CARGO_PKG_VERSION")),
+            args,
+            stations: Vec::new(),
+        }
+    }
+
+    pub(crate) fn read(path: &str) -> Result<Repro, Box<dyn Error>> {
+        let repro: Repro = serde_json::from_reader(io::BufReader::new(fs::File::open(path)?))?;
+        if repro.version != env!("CARGO_PKG_VERSION") {
+            eprintln!(
+                "repro was made by version {}, this is {}",
+                repro.version,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+        Ok(repro)
+    }
+
+    pub(crate) fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(io::BufWriter::new(fs::File::create(path)?), self)?;
+        Ok(())
+    }
+
+    pub(crate) fn args(&self) -> &render::Args {
+        &self.args
+    }
+
+    pub(crate) fn add(&mut self, year: i32, id: &str, station: &Station, notice: Option<String>) {
+        if self.entry(year, id).is_none() {
+            self.stations.push(Entry {
+                year,
+                id: (id != station.id()).then(|| String::from(id)),
+                station: station.clone(),
+                notice,
+            });
+        }
+    }
+
+    pub(crate) fn station(&self, year: i32, id: &str) -> Option<Station> {
+        self.entry(year, id).map(|e| e.station.clone())
+    }
+
+    // What --fallback did to the station when the repro was made.
+    pub(crate) fn notice(&self, year: i32, id: &str) -> Option<String> {
+        self.entry(year, id).and_then(|e| e.notice.clone())
+    }
+
+    fn entry(&self, year: i32, id: &str) -> Option<&Entry> {
+        self.stations
+            .iter()
+            .find(|e| e.year == year && e.id() == id)
+    }
+}
+
+// A repro serves exactly the stations that it recorded.
+impl DataSource for Repro {
+    fn name(&self) -> &'static str {
+        "repro"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        self.stations
+            .iter()
+            .map(|e| e.year)
+            .max()
+            .ok_or_else(|| "repro has no stations".into())
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        Repro::station(self, year.ordinal(), id).ok_or_else(|| {
+            source::not_found(format!("no data for station {} in {} in repro", id, year))
+        })
+    }
+
+    fn stations(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        Ok(years
+            .iter()
+            .filter_map(|year| Repro::station(self, year.ordinal(), id).map(|s| (year, s)))
+            .collect())
+    }
+}
@@ -0,0 +1,403 @@
+use super::{ghcn, gsod, gsod::Station, input, isd, meteostat, open_meteo, render, time, Data};
+use chrono::{Datelike, NaiveDate};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+
+// Somewhere the days of a station come from. Every source maps its data onto the GSOD
+// model, so the charts draw the same way no matter which one a station was loaded from.
+pub trait DataSource {
+    // describes the source, for the metadata of a render.
+    fn name(&self) -> &'static str;
+
+    // the most recent year that the source has a complete year of data for.
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>>;
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>>;
+
+    // Loads the station for each of the years, skipping any years the source has no data
+    // for.
+    fn stations(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        years
+            .iter()
+            .map(|year| Ok((year, self.station(year, id)?)))
+            .collect()
+    }
+}
+
+// The error of a source that has no data for a station in a year, which tells a year the
+// station is missing from apart from one the source failed to load.
+#[derive(Debug)]
+pub struct NotFound(String);
+
+pub(crate) fn not_found(message: String) -> Box<dyn Error> {
+    Box::new(NotFound(message))
+}
+
+pub fn is_not_found(e: &(dyn Error + 'static)) -> bool {
+    e.is::<NotFound>()
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for NotFound {}
+
+// The days of a station's history that fall in the given year, for sources that serve the
+// whole history of a station at once.
+pub(crate) fn year_of(history: &Station, year: time::Year) -> Result<Station, Box<dyn Error>> {
+    let days = history
+        .days()
+        .iter()
+        .filter(|day| day.date().year() == year.ordinal())
+        .cloned()
+        .collect::<Vec<_>>();
+    if days.is_empty() {
+        return Err(not_found(format!(
+            "no data for station {} in {}",
+            history.id(),
+            year
+        )));
+    }
+    Ok(history.with_days(days))
+}
+
+// Splits a station's history into the given years, skipping the years without data.
+pub(crate) fn years_of(history: &Station, years: time::Years) -> Vec<(time::Year, Station)> {
+    years
+        .iter()
+        .filter_map(|year| match year_of(history, year) {
+            Ok(station) => Some((year, station)),
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+// Concatenates the days of a station's years into one station with a single day for each
+// date from first through last, named as it is in the latest of them, or None when none of
+// the years have any. Where the years overlap, as the stray days of an archive can, the day
+// from the year it falls in wins.
+pub fn stitch(
+    stations: &[(time::Year, Station)],
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Option<Station> {
+    let mut days = BTreeMap::new();
+    for (year, station) in stations {
+        for day in station.days() {
+            if day.date() < first || day.date() > last {
+                continue;
+            }
+            let own = day.date().year() == year.ordinal();
+            match days.get(&day.date()) {
+                Some((true, _)) => {}
+                Some((false, _)) if !own => {}
+                _ => {
+                    days.insert(day.date(), (own, day.clone()));
+                }
+            }
+        }
+    }
+    if days.is_empty() {
+        return None;
+    }
+    let (_, station) = stations.last()?;
+    Some(station.with_days(days.into_values().map(|(_, day)| day).collect()))
+}
+
+// Loads a station's days from first through last out of each of the years they span, see
+// stitch. A station that was renumbered can be given by each of its ids, newest first, and
+// each year is loaded under the first of them that the source has it for. Years that none
+// of them have are left out, but failing to load a year is an error.
+pub fn load_span(
+    source: &dyn DataSource,
+    ids: &[&str],
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<Station, Box<dyn Error>> {
+    let years = time::Years::new(
+        time::Year::from_ordinal(first.year()),
+        time::Year::from_ordinal(last.year()),
+    );
+    let mut stations = Vec::new();
+    for year in years.iter() {
+        for id in ids {
+            match source.station(year, id) {
+                Ok(station) => {
+                    stations.push((year, station));
+                    break;
+                }
+                Err(e) if is_not_found(e.as_ref()) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    stitch(&stations, first, last).ok_or_else(|| {
+        format!(
+            "no data for station {} from {} to {}",
+            ids.join(", "),
+            first,
+            last
+        )
+        .into()
+    })
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    /// NOAA's Global Surface Summary of the Day, by station id.
+    Gsod,
+    /// GSOD's older fixed-width files, by station id, for years the CSV archives are missing.
+    GsodLegacy,
+    /// The Open-Meteo historical weather API, by a location given as "lat,lng".
+    OpenMeteo,
+    /// Meteostat's bulk daily data, by Meteostat station id.
+    Meteostat,
+    /// NOAA's Global Historical Climatology Network daily data, by GHCN station id.
+    Ghcn,
+    /// A CSV file of your own, given by --input.
+    File,
+}
+
+impl Source {
+    pub(crate) fn open<'a>(
+        self,
+        data: &'a Data,
+        stray_days: render::StrayDays,
+        input: Option<&'a str>,
+    ) -> Result<Box<dyn DataSource + 'a>, Box<dyn Error>> {
+        Ok(match (self, input) {
+            (Source::File, Some(path)) => Box::new(input::Input::new(path)),
+            (Source::File, None) => return Err("--source file needs an --input".into()),
+            (_, Some(_)) => return Err("--input is only read with --source file".into()),
+            (Source::Gsod, None) => Box::new(Gsod { data, stray_days }),
+            (Source::GsodLegacy, None) => Box::new(GsodLegacy { data, stray_days }),
+            (Source::OpenMeteo, None) => Box::new(open_meteo::OpenMeteo::new(data)),
+            (Source::Meteostat, None) => Box::new(meteostat::Meteostat::new(data)),
+            (Source::Ghcn, None) => Box::new(ghcn::Ghcn::new(data)),
+        })
+    }
+}
+
+// The yearly GSOD archives.
+pub(crate) struct Gsod<'a> {
+    data: &'a Data,
+    stray_days: render::StrayDays,
+}
+
+impl DataSource for Gsod<'_> {
+    fn name(&self) -> &'static str {
+        "NOAA GSOD"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        self.data.latest_year()
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        render::find_station_for_year(self.data, year.ordinal(), id, self.stray_days)
+    }
+
+    fn stations(
+        &self,
+        years: time::Years,
+        id: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        render::find_station_for_years(self.data, years, id, self.stray_days)
+    }
+}
+
+// GSOD's fixed-width files, which NOAA publishes as a tarball for each year with a gzipped
+// file for each station, named by its USAF and WBAN ids and the year.
+pub(crate) struct GsodLegacy<'a> {
+    data: &'a Data,
+    stray_days: render::StrayDays,
+}
+
+impl DataSource for GsodLegacy<'_> {
+    fn name(&self) -> &'static str {
+        "NOAA GSOD (fixed width)"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        self.data.latest_year()
+    }
+
+    fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+        let year = year.ordinal();
+        let name = match (id.get(..6), id.get(6..)) {
+            (Some(usaf), Some(wban)) if id.len() == 11 => {
+                format!("{}-{}-{}.op.gz", usaf, wban, year)
+            }
+            _ => return Err(format!("not a GSOD station id: {}", id).into()),
+        };
+        let mut archive = tar::Archive::new(
+            self.data
+                .download_and_open(&gsod::legacy_url_for(year), format!("gsod_{}.tar", year))?,
+        );
+        let mut station = None;
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if entry.path()?.file_name().and_then(|n| n.to_str()) == Some(name.as_str()) {
+                station = Some(Station::from_op_reader(GzDecoder::new(entry))?);
+                break;
+            }
+        }
+        let station = station.ok_or_else(|| not_found(format!("uknown station: {}", id)))?;
+
+        // the files only have the days, the rest of the station comes from ISD's list.
+        let station = match isd::history(self.data)
+            .ok()
+            .and_then(|h| h.get(id).cloned())
+        {
+            Some(info) => Station::new(
+                String::from(id),
+                info.name().map(String::from),
+                info.location().cloned(),
+                None,
+                station.days().to_vec(),
+            ),
+            None => station,
+        };
+        render::check_year(station, year, self.stray_days)
+    }
+}
+
+// A single station's CSV in GSOD's format, like the files NOAA serves for each station and
+// year, or one of GSOD's older fixed-width files, read from a file or from stdin in place of
+// the archives.
+pub(crate) struct StationFile {
+    station: Station,
+}
+
+impl StationFile {
+    // reads the whole file up front, since stdin can only be read once.
+    pub(crate) fn read(path: &str) -> Result<StationFile, Box<dyn Error>> {
+        let (contents, origin) = match path {
+            "-" => (read_all(io::stdin().lock()), "stdin"),
+            path => (
+                read_all(
+                    fs::File::open(path).map_err(|e| format!("unable to read {}: {}", path, e))?,
+                ),
+                path,
+            ),
+        };
+        let station = contents
+            .and_then(|contents| match contents.starts_with(b"STN---") {
+                true => Station::from_op_reader(&contents[..]),
+                false => Station::from_reader(&contents[..]),
+            })
+            .map_err(|e| format!("unable to read {}: {}", origin, e))?;
+        Ok(StationFile { station })
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        self.station.id()
+    }
+}
+
+// reads a file, inflating it when it is gzipped like the .op.gz files.
+fn read_all<R: io::Read>(mut r: R) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut contents = Vec::new();
+    r.read_to_end(&mut contents)?;
+    if contents.starts_with(&[0x1f, 0x8b]) {
+        let mut inflated = Vec::new();
+        GzDecoder::new(&contents[..]).read_to_end(&mut inflated)?;
+        return Ok(inflated);
+    }
+    Ok(contents)
+}
+
+impl DataSource for StationFile {
+    fn name(&self) -> &'static str {
+        "NOAA GSOD file"
+    }
+
+    fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+        self.station
+            .days()
+            .last()
+            .map(|day| day.date().year())
+            .ok_or_else(|| format!("station {} has no days", self.id()).into())
+    }
+
+    fn station(&self, year: time::Year, _: &str) -> Result<Station, Box<dyn Error>> {
+        year_of(&self.station, year)
+    }
+
+    fn stations(
+        &self,
+        years: time::Years,
+        _: &str,
+    ) -> Result<Vec<(time::Year, Station)>, Box<dyn Error>> {
+        Ok(years_of(&self.station, years))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Serves a station for 2022, has none for 2023 and fails to load 2024.
+    struct Fake;
+
+    impl DataSource for Fake {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn latest_year(&self) -> Result<i32, Box<dyn Error>> {
+            Ok(2024)
+        }
+
+        fn station(&self, year: time::Year, id: &str) -> Result<Station, Box<dyn Error>> {
+            match (year.ordinal(), id) {
+                (2022, "a") => input::read(
+                    "date,tmin,tmax\n2022-12-30,30,40\n2022-12-31,31,41\n".as_bytes(),
+                    "fake",
+                    String::from("a"),
+                ),
+                (2024, _) => Err("unable to download 2024".into()),
+                _ => Err(not_found(format!("no data for station {} in {}", id, year))),
+            }
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn load_span_skips_missing_years() {
+        let station =
+            load_span(&Fake, &["b", "a"], date("2022-12-31"), date("2023-01-02")).unwrap();
+        assert_eq!(station.days().len(), 1);
+
+        let err = load_span(&Fake, &["b"], date("2022-12-31"), date("2023-01-02")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no data for station b from 2022-12-31 to 2023-01-02"
+        );
+    }
+
+    #[test]
+    fn load_span_fails_when_a_year_does_not_load() {
+        let err = load_span(&Fake, &["a"], date("2022-12-31"), date("2024-01-02")).unwrap_err();
+        assert_eq!(err.to_string(), "unable to download 2024");
+    }
+}
@@ -0,0 +1,123 @@
+use super::{gsod, isd, render, time, Data, Series, YearSpec};
+use std::error::Error;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The station, by GSOD id or by ICAO or IATA airport code (e.g. KBOS or BOS).
+    #[clap(long, alias = "station", default_value_t = String::from("72309693727"))]
+    station_id: String,
+
+    /// The year to use, "latest" for the most recent complete year or "current" for the
+    /// year so far.
+    #[clap(long, default_value = "latest")]
+    year: YearSpec,
+
+    /// What to do with rows whose date falls outside of the requested year.
+    #[clap(long, value_enum, default_value_t = render::StrayDays::Drop)]
+    stray_days: render::StrayDays,
+
+    /// How many characters wide each sparkline is. The days are split evenly among them.
+    #[clap(long, default_value_t = 73)]
+    width: usize,
+}
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Prints a line of block characters for each of temperature, wind and precipitation, a quick
+// look at a station's year before rendering it. Columns without a single day of data are
+// left blank.
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    if args.width == 0 {
+        return Err("--width must be at least 1".into());
+    }
+    let year_num = args.year.resolve(data)?;
+    let station_id = isd::resolve_station(data, &args.station_id)?;
+    let station = render::find_station_for_year(data, year_num, &station_id, args.stray_days)?;
+
+    let year = time::Year::from_ordinal(year_num);
+    let series =
+        |f: fn(&gsod::Day) -> Option<f64>| Series::for_each_day(year, station.days().iter(), f);
+    let temperature = buckets(
+        &series(|day| day.mean_temperature().map(|t| t.in_fahrenheit())),
+        args.width,
+        mean,
+    );
+    let wind = buckets(
+        &series(|day| day.mean_wind().map(|s| s.in_knots())),
+        args.width,
+        mean,
+    );
+    let precipitation = buckets(
+        &series(|day| day.precipitation().map(|p| p.in_inches())),
+        args.width,
+        |vals| vals.iter().sum(),
+    );
+
+    println!("{} {}", station.name().unwrap_or(station.id()), year_num);
+    let (lo, hi) = bounds(&temperature);
+    println!(
+        "temperature    {}  {:.1}–{:.1}°F",
+        spark(&temperature, false),
+        lo,
+        hi
+    );
+    let (lo, hi) = bounds(&wind);
+    println!(
+        "wind           {}  {:.1}–{:.1} kts",
+        spark(&wind, false),
+        lo,
+        hi
+    );
+    let total = precipitation.iter().flatten().sum::<f64>();
+    println!(
+        "precipitation  {}  {:.1} in",
+        spark(&precipitation, true),
+        total
+    );
+    Ok(())
+}
+
+fn mean(vals: &[f64]) -> f64 {
+    vals.iter().sum::<f64>() / vals.len() as f64
+}
+
+// Splits the days of the series into n spans of about the same length and aggregates the
+// days that have data in each.
+fn buckets<F>(series: &Series, n: usize, agg: F) -> Vec<Option<f64>>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let days = series.values().len();
+    (0..n)
+        .map(|i| {
+            let vals = (i * days / n..(i + 1) * days / n)
+                .filter(|ix| !series.is_filled(*ix))
+                .map(|ix| series.values()[ix])
+                .collect::<Vec<_>>();
+            (!vals.is_empty()).then(|| agg(&vals))
+        })
+        .collect()
+}
+
+fn bounds(vals: &[Option<f64>]) -> (f64, f64) {
+    vals.iter()
+        .flatten()
+        .fold((f64::NAN, f64::NAN), |(lo, hi), v| (v.min(lo), v.max(hi)))
+}
+
+// The bars are scaled from the lowest value to the highest, or from zero for amounts like
+// precipitation, so that a span with a little rain isn't drawn as if it were dry.
+fn spark(vals: &[Option<f64>], from_zero: bool) -> String {
+    let (lo, hi) = bounds(vals);
+    let lo = if from_zero { 0.0 } else { lo };
+    vals.iter()
+        .map(|v| match v {
+            None => ' ',
+            Some(v) if hi > lo => {
+                let u = (v - lo) / (hi - lo);
+                BARS[((u * (BARS.len() - 1) as f64).round() as usize).min(BARS.len() - 1)]
+            }
+            Some(_) => BARS[0],
+        })
+        .collect()
+}
@@ -0,0 +1,167 @@
+use super::{
+    chart, check_unit, gsod::Station, heatmap, render, time, Color, Font, Range, Series, TAU,
+};
+use cairo::{Context, FontSlant, FontWeight};
+use std::error::Error;
+
+// Renders consecutive years as one continuous ribbon that winds outward from the center,
+// one full turn per year, colored by the chosen metric.
+pub(crate) fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    stations: &[(time::Year, Station)],
+    metric: heatmap::Metric,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let (first, _) = stations.first().ok_or("no stations to render")?;
+    let (last, station) = stations.last().ok_or("no stations to render")?;
+
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let period = if first == last {
+        render::describe_year(*first, &opts.locale)
+    } else {
+        format!("{} – {}", first, last)
+    };
+    let header_height = render::render_header(ctx, station, &period, width, opts)?;
+    ctx.restore()?;
+
+    let range = metric.range_of(
+        stations
+            .iter()
+            .flat_map(|(_, s)| s.days().iter())
+            .filter_map(|day| metric.value_of(day)),
+    );
+    let gradient = metric.gradient();
+    let empty = opts.theme.foreground.with_alpha(0.05);
+
+    let body_height = height - header_height;
+    let cx = width / 2.0;
+    let cy = header_height + body_height / 2.0;
+    let rmax = (width.min(body_height) / 2.0 - 60.0).max(10.0);
+    let r0 = rmax * 0.25;
+    let n = (last.ordinal() - first.ordinal() + 1) as f64;
+    let step = (rmax - r0) / n;
+    let thickness = step * 0.85;
+    let t0 = -TAU / 4.0;
+
+    // p is the position along the track, measured in years from the start of the first one.
+    let radius_at = |p: f64| r0 + p * step;
+
+    ctx.save()?;
+    ctx.translate(cx, cy);
+
+    render::render_months(ctx, *last, &Range::new(rmax + 5.0, rmax + 30.0), opts)?;
+
+    ctx.save()?;
+    render::orient(ctx, *last, opts);
+    ctx.set_line_width(0.5);
+    for (year, station) in stations {
+        let offset = (year.ordinal() - first.ordinal()) as f64;
+        let num_days = year.duration().num_days() as f64;
+        let k = opts.downsample_by.max(1) as usize;
+        let series = Series::for_each_day(*year, station.days().iter(), |day| metric.value_of(day))
+            .downsample_by(k, |vals| vals.iter().sum::<f64>() / vals.len() as f64);
+
+        let m = series.values().len();
+        for (i, v) in series.values().iter().enumerate() {
+            let fa = (i * k) as f64 / num_days;
+            let fb = if i == m - 1 {
+                1.0
+            } else {
+                ((i + 1) * k) as f64 / num_days
+            };
+            let (ta, tb) = (t0 + fa * TAU, t0 + fb * TAU);
+            let (ra, rb) = (radius_at(offset + fa), radius_at(offset + fb));
+
+            match metric {
+                heatmap::Metric::Precipitation if *v <= 0.0 => empty.set(ctx),
+                _ => gradient.at(check_unit(range.normalize(*v))).set(ctx),
+            }
+            ctx.new_path();
+            ctx.move_to(
+                (ra - thickness / 2.0) * ta.cos(),
+                (ra - thickness / 2.0) * ta.sin(),
+            );
+            ctx.line_to(
+                (rb - thickness / 2.0) * tb.cos(),
+                (rb - thickness / 2.0) * tb.sin(),
+            );
+            ctx.line_to(
+                (rb + thickness / 2.0) * tb.cos(),
+                (rb + thickness / 2.0) * tb.sin(),
+            );
+            ctx.line_to(
+                (ra + thickness / 2.0) * ta.cos(),
+                (ra + thickness / 2.0) * ta.sin(),
+            );
+            ctx.close_path();
+            ctx.fill_preserve()?;
+            ctx.stroke()?;
+        }
+    }
+    ctx.restore()?;
+
+    // label the start of each year where the turns start, at the top of the spiral unless
+    // the rings start in another month, thinning them out when the turns are too close
+    // together to fit text.
+    let label_font = Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 10.0);
+    label_font.set(ctx);
+    let every = (12.0 / step).ceil().max(1.0) as i32;
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    let a0 = opts.angle_of(*last, 0.0);
+    for year in time::Years::new(*first, *last).iter() {
+        let offset = year.ordinal() - first.ordinal();
+        if offset % every != 0 && year != *last {
+            continue;
+        }
+        let label = format!("{}", year);
+        let exts = ctx.text_extents(&label)?;
+        ctx.new_path();
+        let r = radius_at(offset as f64);
+        ctx.move_to(
+            r * a0.sin() - exts.x_advance() - 4.0,
+            -r * a0.cos() + exts.height() / 2.0,
+        );
+        ctx.show_text(&label)?;
+    }
+
+    if opts.debug {
+        Color::from_u32_with_alpha(0xff9900, 0.4).set(ctx);
+        ctx.new_path();
+        ctx.arc(0.0, 0.0, r0, 0.0, TAU);
+        ctx.stroke()?;
+        ctx.new_path();
+        ctx.arc(0.0, 0.0, rmax, 0.0, TAU);
+        ctx.stroke()?;
+    }
+    ctx.restore()?;
+
+    render::render_title(
+        ctx,
+        opts.locale.label(metric.title()),
+        cx,
+        cy - rmax - 40.0,
+        opts,
+    )?;
+
+    ctx.save()?;
+    heatmap::render_legend(
+        ctx,
+        &gradient,
+        &range,
+        metric.units(),
+        &label_font,
+        width - 20.0,
+        height - 32.0,
+        12.0,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    Ok(())
+}
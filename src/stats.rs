@@ -0,0 +1,120 @@
+use super::{feels_like, gsod, isd, render, time, Data, DegreeDays, Series, Summary, YearSpec};
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The station, by GSOD id or by ICAO or IATA airport code (e.g. KBOS or BOS).
+    #[clap(long, alias = "station", default_value_t = String::from("72309693727"))]
+    station_id: String,
+
+    /// The year to use, "latest" for the most recent complete year or "current" for the
+    /// year so far.
+    #[clap(long, default_value = "latest")]
+    year: YearSpec,
+
+    /// What to do with rows whose date falls outside of the requested year.
+    #[clap(long, value_enum, default_value_t = render::StrayDays::Drop)]
+    stray_days: render::StrayDays,
+
+    /// The base temperature, in °F, for heating and cooling degree days.
+    #[clap(long, default_value_t = 65.0)]
+    degree_day_base: f64,
+
+    /// Also load the station's hourly ISD observations for the stats that need them, like
+    /// the range of temperature through each day.
+    #[clap(long, default_value_t = false)]
+    hourly: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Stats<'a> {
+    station: &'a str,
+    name: Option<&'a str>,
+    year: i32,
+    stray_days: usize,
+    max_temperature: Summary,
+    min_temperature: Summary,
+    mean_temperature: Summary,
+    feels_like: Summary,
+    mean_wind: Summary,
+    max_sustained_wind: Summary,
+    precipitation: Summary,
+    degree_days: DegreeDays,
+    indicators: Indicators,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diurnal_range: Option<Summary>,
+}
+
+// Counts of days past a few fixed temperature thresholds.
+#[derive(Debug, Serialize)]
+pub(crate) struct Indicators {
+    // days with a low below 32°F.
+    pub(crate) frost_days: usize,
+    // days with a low below 0°F.
+    pub(crate) ice_days: usize,
+    // days with a high above 90°F.
+    pub(crate) summer_days: usize,
+}
+
+pub(crate) fn indicators_of(year: time::Year, station: &gsod::Station) -> Indicators {
+    let min_temps = Series::for_each_day(year, station.days().iter(), |day| {
+        day.min_temperature().map(|t| t.in_fahrenheit())
+    });
+    let max_temps = Series::for_each_day(year, station.days().iter(), |day| {
+        day.max_temperature().map(|t| t.in_fahrenheit())
+    });
+    Indicators {
+        frost_days: min_temps.count_where(|t| t < 32.0),
+        ice_days: min_temps.count_where(|t| t < 0.0),
+        summer_days: max_temps.count_where(|t| t > 90.0),
+    }
+}
+
+// Summarizes a station's year. The range of temperature through each day needs the hourly
+// observations, so it is left to the caller.
+pub(crate) fn stats_of(
+    year: time::Year,
+    station: &gsod::Station,
+    degree_day_base: f64,
+) -> Stats<'_> {
+    let series =
+        |f: fn(&gsod::Day) -> Option<f64>| Series::for_each_day(year, station.days().iter(), f);
+    let summarize = |f: fn(&gsod::Day) -> Option<f64>| series(f).summarize();
+    Stats {
+        station: station.id(),
+        name: station.name(),
+        year: year.ordinal(),
+        stray_days: station.num_stray_days(),
+        max_temperature: summarize(|day| day.max_temperature().map(|t| t.in_fahrenheit())),
+        min_temperature: summarize(|day| day.min_temperature().map(|t| t.in_fahrenheit())),
+        mean_temperature: summarize(|day| day.mean_temperature().map(|t| t.in_fahrenheit())),
+        feels_like: summarize(feels_like),
+        mean_wind: summarize(|day| day.mean_wind().map(|s| s.in_knots())),
+        max_sustained_wind: summarize(|day| day.max_sustained_wind().map(|s| s.in_knots())),
+        precipitation: summarize(|day| day.precipitation().map(|p| p.in_inches())),
+        degree_days: series(|day| day.mean_temperature().map(|t| t.in_fahrenheit()))
+            .degree_days(degree_day_base),
+        indicators: indicators_of(year, station),
+        diurnal_range: None,
+    }
+}
+
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    let year_num = args.year.resolve(data)?;
+    let station_id = isd::resolve_station(data, &args.station_id)?;
+    let station = render::find_station_for_year(data, year_num, &station_id, args.stray_days)?;
+
+    let year = time::Year::from_ordinal(year_num);
+    let mut stats = stats_of(year, &station, args.degree_day_base);
+    if args.hourly {
+        let ranges = isd::diurnal_ranges(&isd::hours_for_year(data, year_num, &station_id)?);
+        stats.diurnal_range = Some(
+            Series::from_iterator(year.days().map(|day| ranges.get(&day.date()).copied()))
+                .summarize(),
+        );
+    }
+
+    println!("{}", serde_json::to_string_pretty(&stats)?);
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use super::{gsod, gsod::Station, time, Data};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, params_from_iter, Connection};
+use std::error::Error;
+use std::io;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Load the yearly archives into the database, replacing what was loaded before.
+    Ingest {
+        /// The span of years to load, e.g. 2000..2023.
+        #[clap(long)]
+        years: time::Years,
+    },
+
+    /// Run SQL against the database and print the rows as CSV.
+    Query { sql: String },
+}
+
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    let mut store = Store::open(data)?;
+    match &args.command {
+        Command::Ingest { years } => {
+            for year in years.iter() {
+                let n = store.ingest(data, year.ordinal())?;
+                eprintln!("{}: {} stations", year, n);
+            }
+            Ok(())
+        }
+        Command::Query { sql } => store.query(sql, io::stdout()),
+    }
+}
+
+// The columns of a day in a GSOD archive that the day table keeps, named after their GSOD
+// headers. Values that GSOD marks as missing are NULL.
+enum Kind {
+    // a value and what it is when missing.
+    Real(&'static str),
+    Count,
+    Text,
+}
+
+const COLUMNS: [(&str, Kind); 22] = [
+    ("temp", Kind::Real("9999.9")),
+    ("temp_count", Kind::Count),
+    ("dewp", Kind::Real("9999.9")),
+    ("dewp_count", Kind::Count),
+    ("slp", Kind::Real("9999.9")),
+    ("slp_count", Kind::Count),
+    ("stp", Kind::Real("9999.9")),
+    ("stp_count", Kind::Count),
+    ("visib", Kind::Real("999.9")),
+    ("visib_count", Kind::Count),
+    ("wdsp", Kind::Real("999.9")),
+    ("wdsp_count", Kind::Count),
+    ("mxspd", Kind::Real("999.9")),
+    ("gust", Kind::Real("999.9")),
+    ("max", Kind::Real("9999.9")),
+    ("max_flag", Kind::Text),
+    ("min", Kind::Real("9999.9")),
+    ("min_flag", Kind::Text),
+    ("prcp", Kind::Real("99.99")),
+    ("prcp_flag", Kind::Text),
+    ("sndp", Kind::Real("999.9")),
+    ("frshtt", Kind::Text),
+];
+
+// where the columns start in a GSOD record, after the station, date, location, elevation
+// and name.
+const FIRST_COLUMN: usize = 6;
+
+// The archives loaded into a SQLite database in the data directory, so that lookups across
+// many years and stations don't decompress anything and the data can be queried directly:
+//
+//   archive  the years that have been loaded
+//   station  each station's name, location and elevation, by year
+//   day      each station's days, by year
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    const FILE: &'static str = "weather.db";
+
+    pub fn open(data: &Data) -> Result<Store, Box<dyn Error>> {
+        let conn = Connection::open(data.path(Self::FILE))?;
+        let columns = COLUMNS
+            .iter()
+            .map(|(name, kind)| {
+                let ty = match kind {
+                    Kind::Real(_) => "REAL",
+                    Kind::Count => "INTEGER",
+                    Kind::Text => "TEXT",
+                };
+                format!("{} {},", name, ty)
+            })
+            .collect::<String>();
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS archive (year INTEGER PRIMARY KEY);
+            CREATE TABLE IF NOT EXISTS station (
+                id TEXT, year INTEGER, name TEXT, latitude REAL, longitude REAL, elevation REAL,
+                PRIMARY KEY (id, year)
+            );
+            CREATE TABLE IF NOT EXISTS day (
+                station TEXT, year INTEGER, date TEXT, {}
+                PRIMARY KEY (station, year, date)
+            );",
+            columns
+        ))?;
+        Ok(Store { conn })
+    }
+
+    // The store, if there is one. Lookups use it for the years it has and go to the
+    // archives otherwise.
+    pub(crate) fn existing(data: &Data) -> Result<Option<Store>, Box<dyn Error>> {
+        if !data.path(Self::FILE).exists() {
+            return Ok(None);
+        }
+        Ok(Some(Store::open(data)?))
+    }
+
+    pub fn has_year(&self, year: i32) -> Result<bool, Box<dyn Error>> {
+        Ok(self
+            .conn
+            .prepare("SELECT 1 FROM archive WHERE year = ?1")?
+            .exists([year])?)
+    }
+
+    // Loads the year's archive, returning the number of stations in it.
+    pub fn ingest(&mut self, data: &Data, year: i32) -> Result<usize, Box<dyn Error>> {
+        let mut archive = gsod::Archive::open(data, year)?;
+
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM archive WHERE year = ?1", [year])?;
+        tx.execute("DELETE FROM station WHERE year = ?1", [year])?;
+        tx.execute("DELETE FROM day WHERE year = ?1", [year])?;
+        let mut n = 0;
+        {
+            let mut station = tx.prepare(
+                "INSERT INTO station (id, year, name, latitude, longitude, elevation)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            let mut day = tx.prepare(&format!(
+                "INSERT INTO day (station, year, date, {}) VALUES (?1, ?2, ?3, {})",
+                COLUMNS.map(|(name, _)| name).join(", "),
+                (4..4 + COLUMNS.len())
+                    .map(|i| format!("?{}", i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))?;
+            for entry in archive.entries()? {
+                let mut records = csv::ReaderBuilder::new()
+                    .has_headers(true)
+                    .from_reader(entry?)
+                    .into_records()
+                    .peekable();
+                if let Some(Ok(first)) = records.peek() {
+                    let field = |ix| first.get(ix).unwrap_or("");
+                    station.execute(params![
+                        field(0),
+                        year,
+                        Some(field(5)).filter(|s| !s.is_empty()),
+                        real(field(2), "")?,
+                        real(field(3), "")?,
+                        real(field(4), "")?,
+                    ])?;
+                    n += 1;
+                }
+                for record in records {
+                    let record = record?;
+                    let mut values = vec![
+                        Value::Text(record.get(0).unwrap_or("").to_owned()),
+                        Value::Integer(year.into()),
+                        Value::Text(record.get(1).unwrap_or("").to_owned()),
+                    ];
+                    for (i, (_, kind)) in COLUMNS.iter().enumerate() {
+                        let field = record.get(FIRST_COLUMN + i).unwrap_or("");
+                        values.push(match kind {
+                            Kind::Real(missing) => real(field, missing)?,
+                            Kind::Count => Value::Integer(field.trim().parse().unwrap_or(0)),
+                            Kind::Text => Value::Text(field.to_owned()),
+                        });
+                    }
+                    day.execute(params_from_iter(values))?;
+                }
+            }
+        }
+        tx.execute("INSERT INTO archive (year) VALUES (?1)", [year])?;
+        tx.commit()?;
+        Ok(n)
+    }
+
+    pub fn station(&self, year: i32, id: &str) -> Result<Option<Station>, Box<dyn Error>> {
+        Ok(self.stations_where(year, Some(id))?.pop())
+    }
+
+    pub fn stations(&self, year: i32) -> Result<Vec<Station>, Box<dyn Error>> {
+        self.stations_where(year, None)
+    }
+
+    // Rebuilds the stations' records in GSOD's own format and parses them as if they came
+    // from the archive, so a station from the store is the same as one from the archive. They
+    // come out in the archive's order.
+    fn stations_where(&self, year: i32, id: Option<&str>) -> Result<Vec<Station>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT s.id, d.date, s.latitude, s.longitude, s.elevation, s.name, {}
+            FROM station s JOIN day d ON d.station = s.id AND d.year = s.year
+            WHERE s.year = ?1 AND (?2 IS NULL OR s.id = ?2)
+            ORDER BY s.rowid, d.date",
+            COLUMNS.map(|(name, _)| format!("d.{}", name)).join(", ")
+        ))?;
+        let mut rows = stmt.query(params![year, id])?;
+
+        let mut stations = Vec::new();
+        let (mut current, mut w) = (None::<String>, csv::Writer::from_writer(Vec::new()));
+        while let Some(row) = rows.next()? {
+            let station = row.get::<_, String>(0)?;
+            if current.as_ref() != Some(&station) {
+                if current.is_some() {
+                    stations.push(Station::from_reader(&w.into_inner()?[..])?);
+                }
+                current = Some(station);
+                w = csv::Writer::from_writer(Vec::new());
+                // the header is skipped by the parser.
+                w.write_record([""; FIRST_COLUMN + COLUMNS.len()])?;
+            }
+
+            let mut record = (0..FIRST_COLUMN)
+                .map(|i| text(row.get_ref(i)?, ""))
+                .collect::<Result<Vec<_>, _>>()?;
+            for (i, (_, kind)) in COLUMNS.iter().enumerate() {
+                let missing = match kind {
+                    Kind::Real(missing) => missing,
+                    _ => "",
+                };
+                record.push(text(row.get_ref(FIRST_COLUMN + i)?, missing)?);
+            }
+            w.write_record(record)?;
+        }
+        if current.is_some() {
+            stations.push(Station::from_reader(&w.into_inner()?[..])?);
+        }
+        Ok(stations)
+    }
+
+    // Runs the statement, writing the rows it returns as CSV with a header of the column
+    // names.
+    pub fn query<W: io::Write>(&self, sql: &str, dst: W) -> Result<(), Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let mut w = csv::Writer::from_writer(dst);
+        w.write_record(stmt.column_names())?;
+        let n = stmt.column_count();
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let record = (0..n)
+                .map(|i| text(row.get_ref(i)?, ""))
+                .collect::<Result<Vec<_>, _>>()?;
+            w.write_record(record)?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
+
+fn real(field: &str, missing: &str) -> Result<Value, Box<dyn Error>> {
+    match field.trim() {
+        "" => Ok(Value::Null),
+        v if v == missing => Ok(Value::Null),
+        v => Ok(Value::Real(
+            v.parse()
+                .map_err(|e| format!("invalid value {:?}: {}", v, e))?,
+        )),
+    }
+}
+
+fn text(v: ValueRef, missing: &str) -> Result<String, Box<dyn Error>> {
+    Ok(match v {
+        ValueRef::Null => String::from(missing),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(t) | ValueRef::Blob(t) => String::from_utf8_lossy(t).into_owned(),
+    })
+}
@@ -0,0 +1,192 @@
+use super::{
+    chart, check_unit, gsod::Station, heatmap, locale::Label, render, time, Color, Font, Gradient,
+    Range, Series,
+};
+use cairo::{Context, FontSlant, FontWeight};
+use std::collections::HashMap;
+use std::error::Error;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Resolution {
+    Annual,
+    Daily,
+}
+
+// The blue to red palette used by Ed Hawkins' warming stripes.
+pub(crate) fn diverging() -> Gradient {
+    Gradient::new(
+        [
+            0x08306b, 0x08519c, 0x2171b5, 0x4292c6, 0x6baed6, 0x9ecae1, 0xc6dbef, 0xdeebf7,
+            0xfee0d2, 0xfcbba1, 0xfc9272, 0xfb6a4a, 0xef3b2c, 0xcb181d, 0xa50f15, 0x67000d,
+        ]
+        .iter()
+        .map(|c| Color::from_u32(*c))
+        .collect(),
+    )
+}
+
+// Computes the mean temperature anomaly of each stripe. Annual stripes are relative to the
+// mean of all years; daily stripes are relative to the mean of the same day of the year so
+// that the seasonal cycle does not drown out the year to year differences.
+fn anomalies(
+    years: time::Years,
+    stations: &[(time::Year, Station)],
+    resolution: Resolution,
+) -> Vec<Option<f64>> {
+    let idx = stations
+        .iter()
+        .map(|(year, station)| {
+            (
+                year.ordinal(),
+                Series::for_each_day(*year, station.days().iter(), |day| {
+                    day.mean_temperature().map(|t| t.in_fahrenheit())
+                }),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    match resolution {
+        Resolution::Annual => {
+            let means = years
+                .iter()
+                .map(|year| {
+                    idx.get(&year.ordinal())
+                        .map(|series| series.summarize())
+                        .filter(|summary| summary.count() > summary.filled())
+                        .map(|summary| summary.mean())
+                })
+                .collect::<Vec<_>>();
+            let n = means.iter().flatten().count() as f64;
+            let mean = means.iter().flatten().sum::<f64>() / n;
+            means.iter().map(|v| v.map(|v| v - mean)).collect()
+        }
+        Resolution::Daily => {
+            let mut sums = [(0.0, 0); 366];
+            for series in idx.values() {
+                for (i, v) in series.values().iter().enumerate() {
+                    if !series.is_filled(i) {
+                        sums[i].0 += v;
+                        sums[i].1 += 1;
+                    }
+                }
+            }
+
+            let mut vals = Vec::new();
+            for year in years.iter() {
+                let series = idx.get(&year.ordinal());
+                let num_days = year.duration().num_days() as usize;
+                for (i, (sum, n)) in sums.iter().enumerate().take(num_days) {
+                    vals.push(
+                        series
+                            .filter(|s| !s.is_filled(i))
+                            .map(|s| s.values()[i] - sum / *n as f64),
+                    );
+                }
+            }
+            vals
+        }
+    }
+}
+
+pub(crate) fn render(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    years: time::Years,
+    stations: &[(time::Year, Station)],
+    resolution: Resolution,
+    opts: &chart::Options,
+) -> Result<(), Box<dyn Error>> {
+    let (_, station) = stations.last().ok_or("no stations to render")?;
+
+    opts.theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    ctx.save()?;
+    let period = format!("{} – {}", years.first(), years.last());
+    let header_height = render::render_header(ctx, station, &period, width, opts)?;
+    ctx.restore()?;
+
+    let vals = anomalies(years, stations, resolution);
+    let extent = vals
+        .iter()
+        .flatten()
+        .fold(0.0f64, |max, v| max.max(v.abs()))
+        .max(0.1);
+    let range = Range::new(-extent, extent);
+    let gradient = diverging();
+
+    let title_height = 30.0;
+    let footer_height = 40.0;
+    let top = header_height + title_height;
+    let stripe_height = height - top - footer_height;
+    let dx = width / vals.len() as f64;
+
+    render::render_title(
+        ctx,
+        opts.locale.label(match resolution {
+            Resolution::Annual => Label::AnnualMeanTemperature,
+            Resolution::Daily => Label::DailyMeanTemperature,
+        }),
+        width / 2.0,
+        top - 10.0,
+        opts,
+    )?;
+
+    ctx.save()?;
+    for (i, v) in vals.iter().enumerate() {
+        if let Some(v) = v {
+            gradient.at(check_unit(range.normalize(*v))).set(ctx);
+            ctx.new_path();
+            // overlap each stripe slightly with the next to avoid hairline seams.
+            ctx.rectangle(i as f64 * dx, top, dx + 0.5, stripe_height);
+            ctx.fill()?;
+        }
+    }
+    ctx.restore()?;
+
+    let label_font = Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 10.0);
+
+    ctx.save()?;
+    label_font.set(ctx);
+    opts.theme.foreground.with_alpha(0.6).set(ctx);
+    let first = format!("{}", years.first());
+    let last = format!("{}", years.last());
+    let exts = ctx.text_extents(&last)?;
+    let y = top + stripe_height + 6.0 + exts.height();
+    ctx.new_path();
+    ctx.move_to(20.0, y);
+    ctx.show_text(&first)?;
+    ctx.new_path();
+    ctx.move_to(width - 20.0 - exts.x_advance(), y);
+    ctx.show_text(&last)?;
+    ctx.restore()?;
+
+    ctx.save()?;
+    heatmap::render_legend(
+        ctx,
+        &gradient,
+        &range,
+        "°F",
+        &label_font,
+        width / 2.0 + 90.0,
+        top + stripe_height + 6.0,
+        12.0,
+        opts,
+    )?;
+    ctx.restore()?;
+
+    if opts.debug {
+        ctx.save()?;
+        Color::from_u32(0xff9900).set(ctx);
+        ctx.set_line_width(1.0);
+        ctx.new_path();
+        ctx.rectangle(0.0, top, width, stripe_height);
+        ctx.stroke()?;
+        ctx.restore()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+#[cfg(feature = "native")]
+use std::error::Error;
+#[cfg(feature = "native")]
+use std::fmt::Write;
+
+// An invisible shape laid over part of a chart that shows a tooltip when hovered. The
+// points are in the coordinates of the image.
+#[cfg(feature = "native")]
+#[derive(Debug)]
+pub(crate) struct Hotspot {
+    pub(crate) points: Vec<(f64, f64)>,
+    pub(crate) title: String,
+    pub(crate) data: Vec<(&'static str, String)>,
+}
+
+// Adds a group of hotspots on top of everything else in an SVG written by cairo.
+#[cfg(feature = "native")]
+pub(crate) fn add_hotspots(svg: &[u8], hotspots: &[Hotspot]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let svg = std::str::from_utf8(svg)?;
+    let end = svg.rfind("</svg>").ok_or("not an svg")?;
+
+    let mut group = String::from(concat!(
+        "<style>.hotspots path { fill: #fff; fill-opacity: 0; }",
+        " .hotspots path:hover { fill-opacity: 0.15; }</style>\n",
+        "<g class=\"hotspots\">\n"
+    ));
+    for hotspot in hotspots {
+        let mut d = String::new();
+        for (i, (x, y)) in hotspot.points.iter().enumerate() {
+            write!(d, "{}{:.2} {:.2} ", if i == 0 { 'M' } else { 'L' }, x, y)?;
+        }
+        d.push('Z');
+
+        write!(group, "<path d=\"{}\"", d)?;
+        for (key, val) in &hotspot.data {
+            write!(group, " data-{}=\"{}\"", key, escape(val))?;
+        }
+        writeln!(group, "><title>{}</title></path>", escape(&hotspot.title))?;
+    }
+    group.push_str("</g>\n");
+
+    let mut out = String::with_capacity(svg.len() + group.len());
+    out.push_str(&svg[..end]);
+    out.push_str(&group);
+    out.push_str(&svg[end..]);
+    Ok(out.into_bytes())
+}
+
+// Adds a logo on top of an SVG written by cairo, fit into a square at x, y of the given size
+// and aligned within it as by preserveAspectRatio, e.g. xMaxYMax for the bottom right. The
+// logo keeps its own coordinates by way of its viewBox, or its width and height without one.
+#[cfg(feature = "native")]
+pub(crate) fn add_logo(
+    svg: &[u8],
+    logo: &str,
+    (x, y, size): (f64, f64, f64),
+    align: &str,
+    opacity: f64,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let svg = std::str::from_utf8(svg)?;
+    let end = svg.rfind("</svg>").ok_or("not an svg")?;
+
+    let root = regex::Regex::new(r"<svg\b([^>]*)>")?;
+    let (open, attrs) = root
+        .captures(logo)
+        .and_then(|c| Some((c.get(0)?, c.get(1)?.as_str())))
+        .ok_or("the logo isn't an svg")?;
+    let close = logo.rfind("</svg>").ok_or("the logo isn't an svg")?;
+    let attr = |name: &str| {
+        regex::Regex::new(&format!(r#"\b{}\s*=\s*["']([^"']*)["']"#, name))
+            .ok()?
+            .captures(attrs)
+            .map(|c| String::from(&c[1]))
+    };
+    let length =
+        |name: &str| attr(name).and_then(|v| v.trim_end_matches("px").trim().parse::<f64>().ok());
+    let view_box = match (attr("viewBox"), length("width"), length("height")) {
+        (Some(view_box), _, _) => view_box,
+        (None, Some(w), Some(h)) => format!("0 0 {} {}", w, h),
+        _ => return Err("the logo needs a viewBox or a width and height".into()),
+    };
+
+    let mut group = String::new();
+    writeln!(
+        group,
+        "<g class=\"logo\" opacity=\"{:.2}\"><svg x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" viewBox=\"{}\" preserveAspectRatio=\"{} meet\">",
+        opacity,
+        x,
+        y,
+        size,
+        size,
+        escape(&view_box),
+        align
+    )?;
+    group.push_str(&logo[open.end()..close]);
+    group.push_str("</svg></g>\n");
+
+    let mut out = String::with_capacity(svg.len() + group.len());
+    out.push_str(&svg[..end]);
+    out.push_str(&group);
+    out.push_str(&svg[end..]);
+    Ok(out.into_bytes())
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,50 @@
+use super::Color;
+use serde::{Deserialize, Serialize};
+
+// The colors of a render. Text and other decoration use the foreground at various
+// opacities.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Theme {
+    pub background: Color,
+    pub foreground: Color,
+    pub temperature_band: Color,
+    pub temperature_line: Color,
+    pub feels_like: Color,
+    pub wind: Color,
+    pub precipitation: Color,
+    pub fog: Color,
+    pub snow: Color,
+    pub thunder: Color,
+    pub winter: Color,
+    pub spring: Color,
+    pub summer: Color,
+    pub autumn: Color,
+    // the dashes, in pixels, and the opacity of the foreground of --grid-rings. No dashes
+    // draws them solid.
+    pub grid_dash: Vec<f64>,
+    pub grid_opacity: f64,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: Color::from_u32(0x3b3938),
+            foreground: Color::from_u32(0xffffff),
+            temperature_band: Color::from_u32(0x6eb078),
+            temperature_line: Color::from_u32(0xe45f91),
+            feels_like: Color::from_u32(0xf29e4c),
+            wind: Color::from_u32(0x9f83c3),
+            precipitation: Color::from_u32(0x2fcbcc),
+            fog: Color::from_u32(0x9a9897),
+            snow: Color::from_u32(0xe8f1f8),
+            thunder: Color::from_u32(0xf2b134),
+            winter: Color::from_u32(0x6fa8dc),
+            spring: Color::from_u32(0x93c47d),
+            summer: Color::from_u32(0xf6b26b),
+            autumn: Color::from_u32(0xc27c4e),
+            grid_dash: Vec::new(),
+            grid_opacity: 0.15,
+        }
+    }
+}
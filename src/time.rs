@@ -1,19 +1,30 @@
 use chrono::prelude::*;
 use chrono::{Duration, NaiveDate};
 
+// A calendar year or, for a trailing year, the 365 days ending on a given day.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 pub struct Year {
     start: NaiveDate,
+    end: NaiveDate,
 }
 
 impl Year {
     pub fn from_start(start: NaiveDate) -> Year {
-        Year { start }
+        Year {
+            start,
+            end: NaiveDate::from_yo_opt(start.year() + 1, 1).unwrap(),
+        }
     }
 
     pub fn from_ordinal(ord: i32) -> Year {
+        Self::from_start(NaiveDate::from_yo_opt(ord, 1).unwrap())
+    }
+
+    // The 365 days up to and including the given one.
+    pub fn trailing(last: NaiveDate) -> Year {
         Year {
-            start: NaiveDate::from_yo_opt(ord, 1).unwrap(),
+            start: last - Duration::days(364),
+            end: last + Duration::days(1),
         }
     }
 
@@ -22,7 +33,13 @@ impl Year {
     }
 
     pub fn end(&self) -> NaiveDate {
-        NaiveDate::from_yo_opt(self.start.year() + 1, 1).unwrap()
+        self.end
+    }
+
+    // Whether the year runs from January 1st through December 31st, which a trailing year
+    // only does when it ends on New Year's Eve.
+    pub fn is_calendar(&self) -> bool {
+        self.start.ordinal() == 1 && self.end == Self::from_ordinal(self.start.year()).end
     }
 
     pub fn duration(&self) -> Duration {
@@ -40,41 +57,172 @@ impl Year {
         }
     }
 
+    // The months of the year, the first and last of which are cut short by a trailing year
+    // that doesn't start or end with a month.
     pub fn months(&self) -> MonthsIter {
         MonthsIter {
             cur: Month::from_start(self.start),
-            end: Month::from_start(self.end()),
+            end: self.end,
+        }
+    }
+
+    // The ISO weeks numbered in the year, the first of which can start in the year before
+    // and the last of which can end in the year after. The weeks of a trailing year are the
+    // ones with any of its days.
+    pub fn weeks(&self) -> WeeksIter {
+        if !self.is_calendar() {
+            return WeeksIter {
+                cur: Week::containing(self.start),
+                end: Week::containing(self.end - Duration::days(1)).next(),
+            };
+        }
+        let monday = |year| NaiveDate::from_isoywd_opt(year, 1, Weekday::Mon).unwrap();
+        WeeksIter {
+            cur: Week::containing(monday(self.ordinal())),
+            end: Week::containing(monday(self.ordinal() + 1)),
         }
     }
 
     pub fn ordinal(&self) -> i32 {
         self.start.year()
     }
+
+    // The fraction of the year that has elapsed at the middle of the given date.
+    pub fn fraction_of(&self, date: NaiveDate) -> f64 {
+        let days = date.signed_duration_since(self.start).num_days() as f64;
+        (days + 0.5) / self.duration().num_days() as f64
+    }
 }
 
 impl std::fmt::Display for Year {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.start.year())
+        match self.is_calendar() {
+            true => write!(f, "{}", self.start.year()),
+            false => write!(f, "{}..{}", self.start, self.end - Duration::days(1)),
+        }
+    }
+}
+
+// An inclusive span of consecutive years, written as "2014..2023".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Years {
+    first: Year,
+    last: Year,
+}
+
+impl Years {
+    pub fn new(first: Year, last: Year) -> Years {
+        if first <= last {
+            Years { first, last }
+        } else {
+            Years {
+                first: last,
+                last: first,
+            }
+        }
+    }
+
+    pub fn first(&self) -> Year {
+        self.first
+    }
+
+    pub fn last(&self) -> Year {
+        self.last
+    }
+
+    pub fn len(&self) -> usize {
+        (self.last.ordinal() - self.first.ordinal()) as usize + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn iter(&self) -> YearsIter {
+        YearsIter {
+            cur: self.first,
+            end: self.last.next(),
+        }
+    }
+}
+
+impl std::str::FromStr for Years {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<i32>()
+                .map(Year::from_ordinal)
+                .map_err(|_| format!("invalid year: {}", s))
+        };
+        match s.split_once("..") {
+            Some((first, last)) => Ok(Years::new(
+                parse(first)?,
+                parse(last.trim_start_matches('='))?,
+            )),
+            None => {
+                let year = parse(s)?;
+                Ok(Years::new(year, year))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Years {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}..{}", self.first, self.last)
+    }
+}
+
+impl serde::Serialize for Years {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Years {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <String as serde::Deserialize>::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+pub struct YearsIter {
+    cur: Year,
+    end: Year,
+}
+
+impl Iterator for YearsIter {
+    type Item = Year;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.cur;
+        if cur != self.end {
+            self.cur = cur.next();
+            Some(cur)
+        } else {
+            None
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Month {
     start: NaiveDate,
+    end: NaiveDate,
 }
 
 impl Month {
     pub fn from_start(start: NaiveDate) -> Month {
-        Month { start }
-    }
-
-    pub fn start(&self) -> NaiveDate {
-        self.start
-    }
-
-    pub fn end(&self) -> NaiveDate {
-        let start = self.start;
-        NaiveDate::from_ymd_opt(
+        let end = NaiveDate::from_ymd_opt(
             match start.month() {
                 12 => start.year() + 1,
                 _ => start.year(),
@@ -85,7 +233,16 @@ impl Month {
             },
             1,
         )
-        .unwrap()
+        .unwrap();
+        Month { start, end }
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.end
     }
 
     pub fn duration(&self) -> Duration {
@@ -114,6 +271,88 @@ impl std::fmt::Display for Month {
     }
 }
 
+// A month is written as "2023-07".
+impl std::str::FromStr for Month {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveDate::parse_from_str(&format!("{}-01", s.trim()), "%Y-%m-%d")
+            .map(Month::from_start)
+            .map_err(|_| format!("invalid month: {}, e.g. 2023-07", s))
+    }
+}
+
+impl serde::Serialize for Month {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.collect_str(&self.start.format("%Y-%m"))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Month {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <String as serde::Deserialize>::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// An ISO 8601 week, from Monday through Sunday.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+pub struct Week {
+    start: NaiveDate,
+}
+
+impl Week {
+    pub fn containing(date: NaiveDate) -> Week {
+        Week {
+            start: date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        }
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.start + Duration::days(7)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end().signed_duration_since(self.start)
+    }
+
+    pub fn next(&self) -> Week {
+        Week::containing(self.end())
+    }
+
+    pub fn days(&self) -> DaysIter {
+        DaysIter {
+            cur: Day::new(self.start),
+            end: Day::new(self.end()),
+        }
+    }
+
+    // The ISO year and number of the week, e.g. (2021, 52) for the week of January 1st,
+    // 2022.
+    pub fn iso(&self) -> (i32, u32) {
+        let week = self.start.iso_week();
+        (week.year(), week.week())
+    }
+}
+
+impl std::fmt::Display for Week {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (year, week) = self.iso();
+        write!(f, "{}-W{:02}", year, week)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Day {
     t: NaiveDate,
@@ -176,14 +415,35 @@ impl Iterator for DaysIter {
 
 pub struct MonthsIter {
     cur: Month,
-    end: Month,
+    end: NaiveDate,
 }
 
 impl Iterator for MonthsIter {
     type Item = Month;
     fn next(&mut self) -> Option<Self::Item> {
         let cur = self.cur;
-        if cur.start != self.end.start {
+        if cur.start < self.end {
+            self.cur = cur.next();
+            Some(Month {
+                end: cur.end.min(self.end),
+                ..cur
+            })
+        } else {
+            None
+        }
+    }
+}
+
+pub struct WeeksIter {
+    cur: Week,
+    end: Week,
+}
+
+impl Iterator for WeeksIter {
+    type Item = Week;
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.cur;
+        if cur != self.end {
             self.cur = cur.next();
             Some(cur)
         } else {
@@ -0,0 +1,241 @@
+use super::{
+    gsod, isd,
+    locale::{Label, Locale},
+    render,
+    theme::Theme,
+    time, Data, Font, Range, Scale, Series, TAU,
+};
+use cairo::{Context, FontSlant, FontWeight, Format, ImageSurface};
+use std::error::Error;
+use std::fs::File;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The station, by GSOD id or by ICAO or IATA airport code (e.g. KBOS or BOS).
+    #[clap(long, alias = "station", default_value_t = String::from("72309693727"))]
+    station_id: String,
+
+    /// Span of years, e.g. 1994..2023.
+    #[clap(long)]
+    years: time::Years,
+
+    /// What to do with rows whose date falls outside of the requested year.
+    #[clap(long, value_enum, default_value_t = render::StrayDays::Drop)]
+    stray_days: render::StrayDays,
+
+    /// Leave the years with fewer days of data than this out of the trend, since the days
+    /// they are missing skew their means.
+    #[clap(long, default_value_t = 300)]
+    min_days: usize,
+
+    /// Also draw the annual means and the trend to this PNG.
+    #[clap(long)]
+    chart: Option<String>,
+
+    #[clap(long, default_value_t = 800)]
+    width: i32,
+
+    #[clap(long, default_value_t = 300)]
+    height: i32,
+}
+
+// The mean temperature of a year and how many days of data it comes from.
+struct Annual {
+    year: time::Year,
+    mean: f64,
+    days: usize,
+}
+
+// Prints the annual mean temperature of a station over a span of years and the least squares
+// trend through them, in °F a decade.
+pub fn execute(data: &Data, args: &Args) -> Result<(), Box<dyn Error>> {
+    let station_id = isd::resolve_station(data, &args.station_id)?;
+    let stations = render::find_station_for_years(data, args.years, &station_id, args.stray_days)?;
+
+    let annuals = stations
+        .iter()
+        .map(|(year, station)| {
+            let summary = Series::for_each_day(*year, station.days().iter(), |day| {
+                day.mean_temperature().map(|t| t.in_fahrenheit())
+            })
+            .summarize();
+            Annual {
+                year: *year,
+                mean: summary.mean(),
+                days: summary.count(),
+            }
+        })
+        .filter(|annual| annual.days > 0)
+        .collect::<Vec<_>>();
+    let kept = annuals
+        .iter()
+        .filter(|annual| annual.days >= args.min_days)
+        .collect::<Vec<_>>();
+
+    let (_, station) = stations.last().ok_or("no stations")?;
+    println!("{} {}", station.name().unwrap_or(station.id()), args.years);
+    for annual in &annuals {
+        match annual.days >= args.min_days {
+            true => println!("{}  {:.1}°F", annual.year, annual.mean),
+            false => println!(
+                "{}  {:.1}°F  ({} days, left out)",
+                annual.year, annual.mean, annual.days
+            ),
+        }
+    }
+
+    let trend = fit(&kept);
+    match trend {
+        Some((slope, _)) => println!(
+            "trend  {:+.2}°F/decade over {} years",
+            slope * 10.0,
+            kept.len()
+        ),
+        None => println!("trend  needs at least 2 years with {} days", args.min_days),
+    }
+
+    if let Some(path) = &args.chart {
+        let surface = ImageSurface::create(Format::ARgb32, args.width, args.height)?;
+        let ctx = Context::new(&surface)?;
+        draw(
+            &ctx,
+            args.width as f64,
+            args.height as f64,
+            station,
+            &annuals,
+            args.min_days,
+            trend,
+        )?;
+        drop(ctx);
+        surface.write_to_png(&mut File::create(path)?)?;
+    }
+    Ok(())
+}
+
+// The slope and intercept of the least squares line through the annual means, by year.
+fn fit(annuals: &[&Annual]) -> Option<(f64, f64)> {
+    if annuals.len() < 2 {
+        return None;
+    }
+    let n = annuals.len() as f64;
+    let mx = annuals.iter().map(|a| a.year.ordinal() as f64).sum::<f64>() / n;
+    let my = annuals.iter().map(|a| a.mean).sum::<f64>() / n;
+    let (sxy, sxx) = annuals.iter().fold((0.0, 0.0), |(sxy, sxx), a| {
+        let dx = a.year.ordinal() as f64 - mx;
+        (sxy + dx * (a.mean - my), sxx + dx * dx)
+    });
+    let slope = sxy / sxx;
+    Some((slope, my - slope * mx))
+}
+
+// Plots the annual means, the ones left out of the trend hollow, with the trend as a dashed
+// line through them.
+fn draw(
+    ctx: &Context,
+    width: f64,
+    height: f64,
+    station: &gsod::Station,
+    annuals: &[Annual],
+    min_days: usize,
+    trend: Option<(f64, f64)>,
+) -> Result<(), Box<dyn Error>> {
+    let theme = Theme::default();
+    theme.background.set(ctx);
+    ctx.rectangle(0.0, 0.0, width, height);
+    ctx.fill()?;
+
+    let (first, last) = match (annuals.first(), annuals.last()) {
+        (Some(first), Some(last)) => (first.year.ordinal() as f64, last.year.ordinal() as f64),
+        _ => return Err("no years to chart".into()),
+    };
+    let means = annuals.iter().map(|a| a.mean);
+    let trange = means.fold(Range::new(f64::MAX, f64::MIN), |r, m| {
+        Range::new(r.min().min(m), r.max().max(m))
+    });
+    let trange = Range::new(trange.min() - 0.5, trange.max() + 0.5);
+    let xrange = Range::new(first - 0.5, last + 0.5);
+
+    let (x0, x1, y0, y1) = (60.0, width - 20.0, 50.0, height - 40.0);
+    let x_of = |year: f64| x0 + xrange.normalize_clamped(year).value() * (x1 - x0);
+    let y_of = |t: f64| y1 - trange.normalize_clamped(t).value() * (y1 - y0);
+
+    let label_font = Font::new("HelveticaNeue", FontSlant::Normal, FontWeight::Normal, 10.0);
+    let title_font = Font::new(
+        "HelveticaNeue-Medium",
+        FontSlant::Normal,
+        FontWeight::Bold,
+        11.0,
+    );
+
+    title_font.set(ctx);
+    theme.foreground.with_alpha(0.6).set(ctx);
+    let title = format!(
+        "{}  {}",
+        station.name().unwrap_or(station.id()),
+        Locale::default().label(Label::AnnualMeanTemperature)
+    );
+    let title = match trend {
+        Some((slope, _)) => format!("{}  {:+.2}°F/DECADE", title, slope * 10.0),
+        None => title,
+    };
+    ctx.move_to(x0, 30.0);
+    ctx.show_text(&title)?;
+
+    label_font.set(ctx);
+    let scale = Scale::from_range(&trange, 5.0)?;
+    for (i, t) in scale.steps().iter().enumerate() {
+        theme.foreground.with_alpha(0.1).set(ctx);
+        ctx.new_path();
+        ctx.move_to(x0, y_of(*t));
+        ctx.line_to(x1, y_of(*t));
+        ctx.stroke()?;
+        let label = format!("{}°F", scale.label_for(i));
+        let exts = ctx.text_extents(&label)?;
+        theme.foreground.with_alpha(0.6).set(ctx);
+        ctx.move_to(x0 - exts.x_advance() - 6.0, y_of(*t) + exts.height() / 2.0);
+        ctx.show_text(&label)?;
+    }
+    let years =
+        Scale::from_range_with_step(&xrange, Scale::from_range(&xrange, 12.0)?.step().max(1.0))?;
+    for (i, year) in years.steps().iter().enumerate() {
+        let label = years.label_for(i);
+        let exts = ctx.text_extents(&label)?;
+        ctx.move_to(
+            x_of(*year) - exts.x_advance() / 2.0,
+            y1 + 8.0 + exts.height(),
+        );
+        ctx.show_text(&label)?;
+    }
+
+    theme.temperature_line.set(ctx);
+    ctx.set_line_width(1.5);
+    ctx.new_path();
+    for a in annuals {
+        ctx.line_to(x_of(a.year.ordinal() as f64), y_of(a.mean));
+    }
+    ctx.stroke()?;
+    for a in annuals {
+        ctx.new_path();
+        ctx.arc(x_of(a.year.ordinal() as f64), y_of(a.mean), 3.0, 0.0, TAU);
+        match a.days >= min_days {
+            true => ctx.fill()?,
+            false => {
+                theme.background.set(ctx);
+                ctx.fill_preserve()?;
+                theme.temperature_line.set(ctx);
+                ctx.stroke()?;
+            }
+        }
+    }
+
+    if let Some((slope, intercept)) = trend {
+        theme.foreground.with_alpha(0.8).set(ctx);
+        ctx.set_line_width(1.0);
+        ctx.set_dash(&[4.0, 3.0], 0.0);
+        ctx.new_path();
+        ctx.move_to(x_of(first), y_of(intercept + slope * first));
+        ctx.line_to(x_of(last), y_of(intercept + slope * last));
+        ctx.stroke()?;
+    }
+    Ok(())
+}
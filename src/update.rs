@@ -0,0 +1,66 @@
+use super::{filename, gsod, render, Data};
+use clap::ArgMatches;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+// the render args are flattened in, so they don't need a group of their own.
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    #[command(flatten)]
+    render: render::Args,
+
+    /// Render even if nothing changed since the last update.
+    #[clap(long, default_value_t = false)]
+    force: bool,
+}
+
+// Brings a render up to date, for running from cron with the same args every time, e.g.
+// --year current --destination /srv/www/banner.png. The archives of the render are
+// downloaded again when NOAA has newer ones, and the banner is rendered again only when an
+// archive, the --config file or the args changed since the last update. The args of the
+// last update are kept in the data directory, under updates/.
+pub fn execute(data: &Data, args: &Args, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let render_args = args.render.clone().with_config(matches)?;
+    if render_args.destination().contains("{date}") {
+        return Err("--destination can't have a {date} when updating".into());
+    }
+
+    let mut inputs = Vec::new();
+    for year in render_args.archive_years(data)? {
+        let name = format!("{}.tar.gz", year);
+        if data.refresh(&gsod::url_for(year), &name)? {
+            eprintln!("downloaded {}", name);
+        }
+        inputs.push(data.path(&name));
+    }
+    inputs.extend(render_args.config().map(|path| Path::new(path).to_owned()));
+
+    let stamp = data.path("updates").join(format!(
+        "{}.json",
+        filename::sanitize(match render_args.destination() {
+            "" => "default",
+            dst => dst,
+        })
+    ));
+    let settings = serde_json::to_string(&render_args)?;
+    let rendered = modified(&stamp);
+    let changed = inputs.iter().any(|path| modified(path) > rendered)
+        || fs::read_to_string(&stamp).ok().as_deref() != Some(settings.as_str());
+    if !changed && !args.force {
+        eprintln!("nothing changed since the last update");
+        return Ok(());
+    }
+
+    render::execute(data, &render_args)?;
+    fs::create_dir_all(data.path("updates"))?;
+    fs::write(&stamp, settings)?;
+    Ok(())
+}
+
+// files that aren't there are older than everything else.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
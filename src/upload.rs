@@ -0,0 +1,188 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+
+// Publishes renders straight to where they are served, for scheduled renders:
+//
+//   s3://bucket/key       a PUT to S3, signed with AWS_ACCESS_KEY_ID and
+//                         AWS_SECRET_ACCESS_KEY (and AWS_SESSION_TOKEN) for AWS_REGION.
+//                         AWS_ENDPOINT_URL points it at another S3 API, like MinIO or R2.
+//   gs://bucket/key       the same, against Google Cloud Storage's S3 API with an HMAC key.
+//   http(s)://host/path   a plain PUT, with WEATHER_BANNER_AUTHORIZATION as its
+//                         Authorization header when it is set.
+//
+// The credentials only come from the environment, so that they don't end up in a config
+// file or a repro.
+
+pub(crate) fn put(dst: &str, body: Vec<u8>) -> Result<(), Box<dyn Error>> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(concat!("weather-banner/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let req = match dst.split_once("://") {
+        Some(("s3", path)) => {
+            let region = env::var("AWS_REGION")
+                .or_else(|_| env::var("AWS_DEFAULT_REGION"))
+                .unwrap_or_else(|_| String::from("us-east-1"));
+            let endpoint = env::var("AWS_ENDPOINT_URL").ok();
+            signed(&client, path, endpoint.as_deref(), &region, &body)?
+        }
+        Some(("gs", path)) => signed(
+            &client,
+            path,
+            Some("https://storage.googleapis.com"),
+            "auto",
+            &body,
+        )?,
+        Some(("http" | "https", _)) => {
+            let req = client.put(dst);
+            match env::var("WEATHER_BANNER_AUTHORIZATION") {
+                Ok(auth) => req.header(reqwest::header::AUTHORIZATION, auth),
+                Err(_) => req,
+            }
+        }
+        _ => return Err(format!("unable to upload to {}", dst).into()),
+    };
+    req.header(reqwest::header::CONTENT_TYPE, content_type(dst))
+        .body(body)
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+// the renders are served as they are, so they are labeled by the extension they are written
+// with.
+fn content_type(dst: &str) -> &'static str {
+    match Path::new(dst).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => "image/svg+xml",
+        Some(ext) if ext.eq_ignore_ascii_case("html") => "text/html; charset=utf-8",
+        Some(ext) if ext.eq_ignore_ascii_case("json") => "application/json",
+        _ => "image/png",
+    }
+}
+
+// Builds a PUT of the object signed with AWS's Signature Version 4. Objects go to
+// bucket.s3.region.amazonaws.com, or under the bucket's path on another endpoint, which is
+// what MinIO and Cloud Storage expect.
+fn signed(
+    client: &reqwest::blocking::Client,
+    path: &str,
+    endpoint: Option<&str>,
+    region: &str,
+    body: &[u8],
+) -> Result<reqwest::blocking::RequestBuilder, Box<dyn Error>> {
+    let (bucket, key) = match path.split_once('/') {
+        Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => (bucket, key),
+        _ => return Err(format!("expected a bucket and a key: {}", path).into()),
+    };
+    let key = key.split('/').map(encode).collect::<Vec<_>>().join("/");
+    let (url, uri) = match endpoint {
+        Some(endpoint) => (
+            format!("{}/{}/{}", endpoint.trim_end_matches('/'), bucket, key),
+            format!("/{}/{}", bucket, key),
+        ),
+        None => (
+            format!("https://{}.s3.{}.amazonaws.com/{}", bucket, region, key),
+            format!("/{}", key),
+        ),
+    };
+    let parsed = reqwest::Url::parse(&url)?;
+    let host = match (parsed.host_str(), parsed.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => String::from(host),
+        (None, _) => return Err(format!("no host in {}", url).into()),
+    };
+
+    let access_key = env::var("AWS_ACCESS_KEY_ID").map_err(|_| "AWS_ACCESS_KEY_ID isn't set")?;
+    let secret_key =
+        env::var("AWS_SECRET_ACCESS_KEY").map_err(|_| "AWS_SECRET_ACCESS_KEY isn't set")?;
+    let token = env::var("AWS_SESSION_TOKEN").ok().filter(|t| !t.is_empty());
+
+    let now = Utc::now();
+    let (date, time) = (
+        now.format("%Y%m%d").to_string(),
+        now.format("%Y%m%dT%H%M%SZ").to_string(),
+    );
+    let payload = hex(&Sha256::digest(body));
+
+    // the headers are signed in order of their names.
+    let mut headers = vec![
+        ("host", host),
+        ("x-amz-content-sha256", payload.clone()),
+        ("x-amz-date", time.clone()),
+    ];
+    if let Some(token) = &token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical = format!(
+        "PUT\n{}\n\n{}\n{}\n{}",
+        uri,
+        headers
+            .iter()
+            .map(|(name, val)| format!("{}:{}\n", name, val))
+            .collect::<String>(),
+        signed_headers,
+        payload
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        time,
+        scope,
+        hex(&Sha256::digest(canonical.as_bytes()))
+    );
+    let key = [date.as_str(), region, "s3", "aws4_request"]
+        .iter()
+        .try_fold(format!("AWS4{}", secret_key).into_bytes(), |key, part| {
+            hmac(&key, part.as_bytes())
+        })?;
+    let signature = hex(&hmac(&key, to_sign.as_bytes())?);
+
+    let mut req = client.put(url).header(
+        reqwest::header::AUTHORIZATION,
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            access_key, scope, signed_headers, signature
+        ),
+    );
+    for (name, val) in headers.into_iter().filter(|(name, _)| *name != "host") {
+        req = req.header(name, val);
+    }
+    Ok(req)
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+// S3 wants every byte of a key's segments but the unreserved ones percent encoded.
+fn encode(segment: &str) -> String {
+    segment.bytes().fold(String::new(), |mut s, b| {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                s.push(b as char)
+            }
+            b => {
+                let _ = write!(s, "%{:02X}", b);
+            }
+        }
+        s
+    })
+}
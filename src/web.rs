@@ -0,0 +1,127 @@
+use super::{
+    canvas::{self, Anchor, Canvas, Path, Text, Weight},
+    svg::escape,
+    Color,
+};
+use std::error::Error;
+use std::fmt::Write;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Renders banners without cairo, for a web page that draws them client-side from station
+// CSVs it fetched itself. The chart is the same model that --format json writes, drawn as an
+// SVG string the page can drop into the document. It is built for the browser with:
+//
+//   cargo build --release --lib --target wasm32-unknown-unknown --no-default-features \
+//     --features wasm
+//
+// and then handed to wasm-bindgen, which generates the JavaScript that calls renderSvg.
+
+// Renders the year of a station in CSV, in the format of --source file, to an SVG of the
+// given size. The name is what the header calls the station.
+pub fn render_svg(
+    csv: &str,
+    name: &str,
+    year: i32,
+    width: u32,
+    height: u32,
+) -> Result<String, Box<dyn Error>> {
+    let (model, theme) = canvas::banner(csv, name, year)?;
+    let mut svg = Svg(String::new());
+    writeln!(
+        svg.0,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Helvetica Neue, Helvetica, Arial, sans-serif">"#,
+        w = width,
+        h = height
+    )?;
+    canvas::draw(&mut svg, &model, width as f64, height as f64, &theme);
+    svg.0.push_str("</svg>\n");
+    Ok(svg.0)
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = renderSvg)]
+pub fn render_svg_js(
+    csv: &str,
+    name: &str,
+    year: i32,
+    width: u32,
+    height: u32,
+) -> Result<String, JsValue> {
+    render_svg(csv, name, year, width, height).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// writing to a String can't fail, so the results of write! are ignored.
+struct Svg(String);
+
+impl Canvas for Svg {
+    fn fill(&mut self, path: &Path, color: &Color) {
+        let _ = writeln!(
+            self.0,
+            r#"<path d="{}" fill="{}" fill-rule="evenodd"/>"#,
+            d(path),
+            rgba(color)
+        );
+    }
+
+    fn stroke(&mut self, path: &Path, color: &Color, dots: Option<(f64, f64)>) {
+        let dots = match dots {
+            Some((on, off)) => format!(r#" stroke-dasharray="{} {}""#, on, off),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            self.0,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="2"{}/>"#,
+            d(path),
+            rgba(color),
+            dots
+        );
+    }
+
+    fn text(&mut self, text: &Text, color: &Color) {
+        let anchor = match text.anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+            Anchor::End => "end",
+        };
+        let weight = match text.weight {
+            Weight::Thin => "100",
+            Weight::Normal => "normal",
+            Weight::Bold => "bold",
+        };
+        let _ = writeln!(
+            self.0,
+            r#"<text x="{x:.2}" y="{y:.2}" transform="rotate({:.2} {x:.2} {y:.2})" font-size="{}" font-weight="{}" text-anchor="{}"{} fill="{}">{}</text>"#,
+            text.angle,
+            text.size,
+            weight,
+            anchor,
+            if text.centered {
+                r#" dominant-baseline="middle""#
+            } else {
+                ""
+            },
+            rgba(color),
+            escape(&text.text),
+            x = text.x,
+            y = text.y
+        );
+    }
+}
+
+fn d(path: &Path) -> String {
+    let mut d = String::new();
+    for subpath in &path.subpaths {
+        for (i, (x, y)) in subpath.points.iter().enumerate() {
+            let _ = write!(d, "{}{:.2} {:.2} ", if i == 0 { 'M' } else { 'L' }, x, y);
+        }
+        if subpath.closed {
+            d.push_str("Z ");
+        }
+    }
+    d
+}
+
+fn rgba(c: &Color) -> String {
+    format!("rgba({},{},{},{:.3})", c.r, c.g, c.b, c.a as f64 / 255.0)
+}
@@ -0,0 +1,98 @@
+use super::{render, Data};
+use cairo::ImageSurface;
+use clap::ArgMatches;
+use minifb::{Key, ScaleMode, Window, WindowOptions};
+use std::error::Error;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+// how often the watched files are checked for changes.
+const POLL: Duration = Duration::from_millis(250);
+
+// the render's args are the only ones, so they don't need a group of their own.
+#[derive(clap::Args, Debug)]
+#[group(skip)]
+pub struct Args {
+    #[command(flatten)]
+    render: render::Args,
+}
+
+// The pixels of a render, as minifb wants them: a u32 of 0RGB for each pixel.
+struct Frame {
+    pixels: Vec<u32>,
+    width: usize,
+    height: usize,
+}
+
+// Shows the render in a window and renders it again whenever the --config file or the
+// --input file changes, so a theme or layout can be tweaked with the result in sight. A
+// render that fails leaves the last one in the window and reports why on stderr. The
+// window closes with Escape.
+pub fn execute(data: &Data, args: &Args, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let render = || -> Result<(Frame, Vec<String>), Box<dyn Error>> {
+        let args = args.render.clone().with_config(matches)?;
+        let frame = decode(&render::render_png_bytes(data, &args)?)?;
+        Ok((frame, args.watched_files()))
+    };
+
+    let (mut frame, mut watched) = render()?;
+    let mut window = Window::new(
+        "weather-banner",
+        frame.width,
+        frame.height,
+        WindowOptions {
+            resize: true,
+            scale_mode: ScaleMode::AspectRatioStretch,
+            ..WindowOptions::default()
+        },
+    )?;
+    window.set_target_fps(30);
+
+    let mut stamps = modified(&watched);
+    let mut checked = Instant::now();
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        if checked.elapsed() >= POLL {
+            checked = Instant::now();
+            if modified(&watched) != stamps {
+                match render() {
+                    Ok((f, w)) => {
+                        (frame, watched) = (f, w);
+                        eprintln!("rendered again");
+                    }
+                    Err(e) => eprintln!("unable to render: {}", e),
+                }
+                stamps = modified(&watched);
+            }
+        }
+        window.update_with_buffer(&frame.pixels, frame.width, frame.height)?;
+    }
+    Ok(())
+}
+
+// the files that can't be read have no time, so a file that goes away also counts as a change.
+fn modified(paths: &[String]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+fn decode(png: &[u8]) -> Result<Frame, Box<dyn Error>> {
+    let mut surface = ImageSurface::create_from_png(&mut &png[..])?;
+    let (width, height, stride) = (
+        surface.width() as usize,
+        surface.height() as usize,
+        surface.stride() as usize,
+    );
+    let data = surface.data()?;
+    // cairo's pixels are native endian ARGB, which is minifb's layout but for the alpha.
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| y * stride + x * 4))
+        .map(|i| u32::from_ne_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]) & 0xffffff)
+        .collect();
+    Ok(Frame {
+        pixels,
+        width,
+        height,
+    })
+}